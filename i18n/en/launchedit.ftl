@@ -1,16 +1,52 @@
 app-title = Desktop Entry Editor
+title-document = { $name } — { app-title }
+title-modified = • { $title }
 
 generic-name = Name
 generic-value = Value
 generic-save = Save
 generic-cancel = Cancel
+generic-add = Add
+generic-create = Create
+generic-notnow = Not now
+
+bool-unset = Not set
+bool-true = Yes
+bool-false = No
 
 menu-about = About
 menu-view = View
+menu-edit = Edit
+menu-copy = Copy entry
+menu-paste = Paste as new entry
 menu-file = File
 menu-open = Open
+menu-recent = Recent
+menu-clearrecent = Clear recent
+menu-compare = Compare…
+menu-quickopen = Quick open…
+menu-mimeapps = Default applications…
+menu-xdgmenu = Application menu…
+menu-opentest = Which app opens a file…
 menu-save = Save
+menu-savenormalized = Save normalized
 menu-saveas = Save as
+menu-review = Review changes…
+menu-restorebackup = Restore backup
+menu-revert = Revert
+menu-history = History…
+menu-savetemplate = Save as template…
+menu-import = Import
+menu-importurl = Internet shortcut (.url)…
+menu-importjson = JSON document…
+menu-export = Export
+menu-rename = Rename or move…
+menu-openfolder = Open containing folder
+menu-delete = Move to trash…
+menu-autostart = Add to autostart
+menu-exportjson = JSON…
+menu-testlaunch = Test launch
+menu-preferences = Preferences
 menu-quit = Quit
 
 hint-name-application = My Application
@@ -18,18 +54,26 @@ hint-name-link = My Application
 hint-name-directory = My Application
 
 hint-icon = my-icon.svg
+icon-missing = No installed icon is called { $name }.
+icon-didyoumean = Did you mean:
+tryexec-found = Check passes, found { $path }
+tryexec-missing = { $program } was not found, launchers will hide this entry
 hint-comment = A descriptive comment
 hint-exec = executable %F
+hint-execprogram = /usr/bin/program
+hint-execarg = An argument, without quotes
 hint-tryexec = test_executable
 hint-path = /path/to/my/files/
 hint-url = http://my.location.org
 
 hint-genericname = Type of my application;
-hint-onlyshownin = environment1;environment2;
-hint-notshownin = environment3;environment4;
-hint-keywords = keyword1;keyword2;
-hint-categories = category1;category2;
-hint-implements = SomeInterface
+hint-keywords = Add keyword
+hint-implements = Add interface
+hint-desktops = Add desktop
+hint-categories = Add category
+hint-autostartcondition = GSettings org.example.app autostart
+hint-mimetype = type/subtype
+hint-mimefilter = Filter mimetypes
 
 my-application = My Application
 my-link = My Link
@@ -46,7 +90,43 @@ nav-custom = Vendor Extensions
 nav-advanced = Advanced
 
 dialog-title-newmime = New Mimetype
+dialog-title-bulkmime = Add Several Mimetypes
+bulkmime-expl = Paste mimetypes separated by semicolons, commas or new lines.
+bulkmime-summary = { $add } to add, { $duplicates } already present
+bulkmime-invalid = Not type/subtype, skipped: { $names }
+bulkmime-unknown = Not in the system mime database: { $names }
+mime-invalid = { $name } is not of the form type/subtype
+mime-unknown = { $name } is not in the system mime database, check for typos
 dialog-title-newxkey = New X-Custom key
+otherkeys-title = Other keys
+otherkeys-expl = Keys the editor has no field for. They are saved unchanged unless edited here.
+otherkeys-none = Every key in this file has a field.
+dialog-title-handler = Open .desktop Files Here?
+dialog-title-delete = Move Launcher to Trash?
+dialog-title-savetemplate = Save as Template
+savetemplate-expl = New entries can start from this one, with its command, categories and custom keys filled in.
+hint-template = Template name
+template-exists = A template with this name is replaced.
+dialog-title-webapp = New Web App
+webapp-expl = A launcher opening the website in its own browser window. The name is taken from the site once it answers.
+webapp-nobrowser = No installed web browser was found.
+dialog-title-saveasadmin = Change the System Launcher?
+saveasadmin-expl = { $path } is changed for every user of this computer, after authenticating as administrator. A package update may replace it again.
+dialog-title-revert = Discard Unsaved Changes?
+dialog-title-overrideexists = Replace Your Override?
+dialog-title-idconflict = Desktop-File ID Already Used
+idconflict-expl = Another launcher is installed as { $id }. Only one launcher per ID is shown in menus and docks, the one found first.
+idconflict-shadows = { $path } is hidden while this file exists
+idconflict-shadowed = { $path } is found first, this file is hidden
+action-saveanyway = Save anyway
+dialog-title-quickopen = Open Installed Launcher
+hint-quickopen = Name, command or ID
+delete-expl = { $path } will be moved to the trash. Unsaved changes are lost.
+revert-expl = Reload { $path } from disk, losing every change made since it was last saved.
+overrideexists-expl = You already have an override at { $path }. Replacing it loses the changes saved there.
+dialog-title-droppedicon = Use Dropped Image as Icon
+droppedicon-expl = Install { $path } into your icon theme and refer to it by name, or use the file's path as is.
+handler-expl = Make this editor the default application for .desktop files, so opening one in the file manager edits it here.
 dialog-title-newaction = New Action
 dialog-title-editaction = Edit Action
 
@@ -54,18 +134,30 @@ menu-new = New
 menu-newapplication = Application
 menu-newlink = Link (URL)
 menu-newdirectory = Folder Description
+menu-newwebapp = Web App…
+menu-fromtemplate = From template
+menu-notemplates = No saved templates
+menu-newwindow = New Window
 
 select-desktop = Select .desktop file
 select-executable = Select Executable
 select-directory = Select Working directory
+select-savefolder = Select Default Save Folder
 select-icon = Select Icon File
+select-urlshortcut = Select Internet Shortcut
+select-json = Select JSON Document
+select-samplefile = Select a File to Open
 save-desktopfile = Save .desktop file
+export-json = Export as JSON
 
 field-name = Name
 field-genericname = Generic name
 field-icon = Icon
 field-comment = Comment
+field-untranslated = Untranslated
 field-command = Command
+field-wrappers = Start through
+wrapper-notinstalled = { $program } is not installed
 field-workpath = Working path
 field-runinterm = Run in terminal
 field-hide = Hide from menus
@@ -76,21 +168,350 @@ field-keywords = Keywords
 field-categories = Categories
 field-implements = Implements
 field-dbusactivation = DBUS Activation
+field-version = Specification version
+field-autostartenabled = Start at login
+field-autostartdelay = Delay
+field-autostartcondition = Condition
 field-nondefaultgpu = Prefer non-default GPU
+gpu-discrete = Starts on { $name }
+gpu-single = This computer has a single GPU
 field-startupnotify = Startup notify
 field-hidden = Hidden
 field-startupwmclass = Startup WM Class
 field-singlemainwindow = Single Main Window
 field-url = URL
+
+# Names screen readers announce for icon-only buttons
+a11y-browse = Choose { $field } from a file
+a11y-remove = Remove { $item }
+a11y-help = About { $field }
 action-browse = Browse
+action-reload = Reload
+action-keepmine = Keep my changes
+action-showdifferences = Differences ({ $count })
+action-translations = Translations
+action-edituntranslated = Editing the { $locale } translation, click to edit the untranslated value
+action-edittranslation = Editing the untranslated value, click to edit the { $locale } translation
+action-revert = Revert
+action-saveasadmin = Save as administrator
+action-replaceoverride = Replace
+action-openoverride = Open existing
+action-revertkey = Revert to the saved value
+action-removekey = Remove { $key } from the file
+action-choose = Choose…
+action-apply = Apply
+action-add = Add
+action-addseveral = Add several…
+action-removeall = Remove all
+action-makedefault = Make default
+action-movetotrash = Move to trash
+action-undo = Undo
+action-dismiss = Dismiss
+action-open = Open
+action-installicon = Install into theme
+action-usepath = Use path
+action-pickrunning = Pick from running apps
+action-use = Use
+action-probewmclass = Launch and click its window (X11)
+action-runagain = Run again
+action-saveoverride = Save override to ~/.local/share
 
 name-desktopfiles = Desktop Files
+name-directoryfiles = Menu Directory Files
 name-executables = Executables
 name-images = Images
+name-urlshortcuts = Internet shortcuts
+name-jsonfiles = JSON Files
+filename-badext = Launchers are only found with a .desktop or .directory extension
+filename-notrdnn = { $name } doesn't follow the reverse-DNS convention, e.g. org.example.App. On Wayland the compositor matches windows to launchers by app ID, so a file named after it keeps the right icon and name in docks and task switchers
+filename-spaces = The file name contains spaces, which tools taking a desktop-file ID such as gtk-launch don't handle
+filename-nonascii = The file name contains non-ASCII characters, desktop-file IDs should be plain ASCII
 
 context-denied-expl = System launchers are read-only. User defined or overrides are usually saved to these locations:
 context-denied = Permission Denied
 context-unabletosave = Unable to save file
 context-ioerror = IO Error
+context-review = Review changes
+context-review-expl = Lines marked + will be written, lines marked - will be removed from the file.
+context-review-nochanges = The file on disk already matches.
+context-history = History
+context-history-expl = Versions saved earlier, newest first. Select one to compare it with the current edits; lines marked + come back when restoring.
+context-history-none = No saved versions of this file yet.
+context-opentest = Which app opens a file
+context-opentest-expl = Pick a file to see its detected type and which application opening it would start, with every mimeapps.list and launcher that has a say.
+opentest-winner = Opens with { $id }
+opentest-nowinner = No installed application opens this type
+opentest-order = Tried in this order:
+opentest-none = No mimeapps.list or launcher mentions this type.
+opentest-default = Default in { $path }
+opentest-added = Added association in { $path }
+opentest-declared = Declared by the launcher's MimeType
+opentest-opens = opens the file
+opentest-fallback = used if the ones above are gone
+opentest-notinstalled = not installed, skipped
+opentest-removed = removed by { $path }
+action-pickfile = Choose file…
+context-systemd = systemd user unit
+context-systemd-expl = The unit starts the program with the graphical session. This entry stays behind, hidden so the program isn't started twice.
+context-systemd-unsaved = Save the entry first, the unit is named after its file.
+systemd-existing = Converted to a unit
+systemd-preview = Unit to write
+systemd-enabled = The unit is enabled, converting back gives an entry that starts at login.
+systemd-disabled = The unit is disabled, converting back gives a hidden entry.
+systemd-willenable = The entry starts at login, so the unit will be enabled.
+systemd-willdisable = The entry is hidden or turned off, so the unit will be disabled.
+action-restoreversion = Restore this version
+context-overrides = Changes from system launcher
+context-overrides-none = This override is identical to the system launcher.
+override-system = System: { $value }
+override-mine = This file: { $value }
+override-base = When overridden: { $value }
+context-overrides-expl = Compared with the system launcher as it was when this override was made.
+context-overrides-nobase = The system launcher at the time this override was made is unknown, so all differences are listed alike.
+merge-upstream = Changed by the system launcher since
+merge-conflict = Changed in both
+merge-mine = Changed in this override
+action-takesystem = Take system value
+action-takeupstream = Take { $count } system changes
+action-markmerged = Mark as merged
+value-unset = (not set)
 
 error-parsingentry = Error parsing desktop entry
+
+context-translations = Translations of { $key }
+translations-default = Untranslated value
+translations-add = Add a language
+
+context-execbuilder = Command builder
+execbuilder-program = Program
+execbuilder-args = Arguments
+execbuilder-files = Files or URLs passed on launch
+execbuilder-nofiles = None
+execbuilder-result = Resulting command
+execbuilder-unparsed = The command didn't parse ({ $error }), so it was split at spaces. Check the cells before applying
+action-addarg = Add argument
+
+context-runningapps = Running apps
+runningapps-expl = Start the application, then pick its window. The identifier is what the desktop matches against StartupWMClass.
+runningapps-listing = Looking for open windows…
+runningapps-none = No open windows found
+runningapps-clickwindow = Click the application's window to capture its WM_CLASS…
+
+dbus-unsaved = Save the file first, its name must be the application's D-Bus name
+dbus-badname = "{ $name }" is not a valid D-Bus name, rename the file like org.example.App.desktop
+dbus-noservice = No D-Bus service file { $name }.service is installed
+
+mime-handler-this = { $id } (this file)
+mimefilter-all = { $total } mimetypes
+mime-groupbytype = Group by type
+mime-problems = The list has duplicates: { $duplicates }, empty items: { $empty }
+action-cleanlist = Clean list
+mime-groupcount = { $count ->
+    [one] 1 mimetype
+   *[other] { $count } mimetypes
+}
+mimefilter-count = { $shown } of { $total }
+
+mimeapps-title = Default applications
+mimeapps-defaults = Default applications
+mimeapps-added = Added associations
+mimeapps-removed = Removed associations
+hint-mimeapps = app.desktop;other.desktop;
+
+xdgmenu-title = Application menu
+xdgmenu-expl = Launchers are placed in a menu by its include and exclude rules. Changes are saved to your own menu file, the system menu stays as installed.
+xdgmenu-hidden = { $name } (hidden)
+xdgmenu-hide = Hide this menu
+xdgmenu-directory = Name and icon from
+xdgmenu-include = Include
+xdgmenu-exclude = Exclude
+xdgmenu-norules = No rules
+xdgmenu-notmenu = Not an XDG menu file
+hint-directory = name.directory
+hint-menurule = Category or app.desktop
+hint-submenu = New submenu name
+action-useopendirectory = Use open .directory file
+action-include = Include
+action-exclude = Exclude
+action-addsubmenu = Add submenu
+menurule-all = All launchers
+menurule-category = Category { $name }
+menurule-filename = Launcher { $id }
+menurule-and = and
+menurule-or = or
+menurule-not = not
+
+context-testlaunch = Test launch
+testlaunch-running = Running, results appear when the program exits…
+testlaunch-success = Exited successfully
+testlaunch-exitcode = Exited with status { $code }
+testlaunch-signal = Terminated by a signal
+testlaunch-stdout = Output
+testlaunch-stderr = Errors
+testlaunch-noexec = The entry has no command to run
+testlaunch-noterminal = No terminal emulator found, set $TERMINAL
+action-openterminal = Open a terminal in this folder
+terminal-nopath = No working directory is set
+
+fieldcodes-title = Field codes
+fieldcode-f = A single file name
+fieldcode-ff = A list of files
+fieldcode-u = A single URL, local files as file:// or a path
+fieldcode-uu = A list of URLs
+fieldcode-i = The Icon key as --icon <icon>
+fieldcode-c = The translated Name
+fieldcode-k = The location of the desktop file
+
+context-showin = Desktop environments
+showin-conflict = Only one of "Only shown in" and "Not shown in" may be set
+
+categories-main = Main categories
+categories-additional = Additional categories
+categories-other = Other categories
+categories-nomain = No main category, menus may file this launcher under Other
+categories-unknown = Not registered categories: { $names }
+save-duplicates = Removed repeated items from { $key }: { $items }
+
+prefs-saving = Saving
+prefs-savefolder = Save as starts in
+prefs-testing = Test launch
+prefs-terminal = Terminal for Terminal=true entries
+prefs-terminal-auto = Automatic
+prefs-integration = Desktop integration
+prefs-handler = Default application for .desktop files
+prefs-handler-active = This editor
+prefs-backups = Backup copies kept when overwriting
+prefs-history = Saved versions kept in the history
+prefs-deterministic = Deterministic output for version control (sorted keys, LF endings, file mode unchanged)
+prefs-chmod = Make launchers executable on save
+prefs-chmod-bits = Permissions added
+prefs-normalize = Normalize on every save
+prefs-normalize-steps = Normalizations
+prefs-normalize-order = Order groups and keys as the specification lists them
+prefs-normalize-empty = Remove keys with empty values
+prefs-normalize-booleans = Write booleans as true and false
+prefs-chmod-owner = Owner
+prefs-chmod-group = Group
+prefs-chmod-others = Others
+
+compare-title = Compare desktop entries
+compare-key = Key
+compare-summary = { $different } keys differ, { $identical } identical
+
+banner-changedondisk = The file was changed by another program.
+banner-trashed = Moved { $path } to the trash.
+banner-autostart = Starts at login through { $path }
+banner-overrides = Overrides the system launcher { $path }
+banner-readonly = This launcher is read-only. Changes can be saved as a personal override with the same name.
+banner-deprecated = This file uses deprecated keys: { $keys }
+action-migratedeprecated = Update deprecated keys
+banner-quoting = The quoting of { $keys } doesn't follow the Desktop Entry Specification
+action-fixquoting = Fix quoting
+banner-flatpak = Exported by the Flatpak app { $app }. Updates replace this file, keep your changes in an override instead.
+flatpak-branch = branch { $branch }
+flatpak-origin = from { $origin }
+banner-snap = Generated for the snap { $snap }. snapd replaces this file, so saving writes a personal override instead.
+snap-revision = { $name } (revision { $revision })
+
+cli-usage = Usage: launchedit [OPTIONS] [FILE...]
+
+    Options:
+      --new-application    Start with a new application entry
+      --new-link           Start with a new link entry
+      --new-directory      Start with a new directory entry
+      --name NAME          Name of the new entry
+      --exec COMMAND       Exec line of the new application
+      --url URL            URL of the new link
+      --validate FILE...   Print problems in the files and exit, with status 1 on errors
+      --dump-json FILE...  Print the files as JSON and exit
+      -h, --help           Show this help
+
+lint-missing = Required key { $key } is missing
+lint-badtype = Unknown Type { $value }, expected Application, Link or Directory
+lint-badmime = Not type/subtype: { $names }
+lint-badversion = Version { $value } is not a specification version like 1.5
+lint-newerkey = { $key } needs specification version { $since }, the entry targets { $version }
+lint-deprecated = { $key } is deprecated and ignored
+lint-deprecatedby = { $key } is deprecated, use { $replacement }
+lint-quoting = { $key } isn't quoted by the spec, it should read { $fixed }
+
+thumbnailer-expl = A thumbnailer tells the file manager which program draws previews for the file types listed under Mimetypes.
+thumbnailer-codes = %i input path, %u input URI, %o output PNG, %s thumbnail size in pixels
+thumbnailer-nooutput = The command has no %o, the thumbnail has nowhere to go
+thumbnailer-noinput = The command has neither %i nor %u, the file to preview isn't passed
+thumbnailer-badcode = { $code } isn't a thumbnailer field code
+hint-thumbnailer-tryexec = Program that must be installed
+hint-thumbnailer-exec = my-thumbnailer -s %s %u %o
+autostart-title = Autostart
+vendor-cosmic = COSMIC
+vendor-gnome = GNOME
+vendor-kde = KDE
+vendor-purism = Phosh (Purism)
+vendor-cosmicapplet = Panel applet
+vendor-cosmichoverpopup = Hover popup
+vendor-overflowpriority = Overflow priority
+vendor-hostwaylanddisplay = Host Wayland display
+vendor-usesnotifications = Sends notifications
+vendor-singlewindow = Single window
+vendor-fullname = Full name
+vendor-discretegpu = Run on discrete GPU
+vendor-protocols = Handled protocols
+vendor-formfactor = Form factors
+autostart-seconds = { $seconds } s
+autostart-anydesktop = Starts in every desktop environment.
+autostart-systemd = A systemd user unit can start the program instead, with logging and restarts.
+action-showsystemd = systemd unit…
+action-tounit = Convert to unit
+action-fromunit = Convert back to autostart entry
+action-onlyin = Only in { $desktop }
+
+preview-title = Preview
+preview-yourlanguage = Your language
+preview-locale = Preview locale
+
+appstream-title = AppStream
+appstream-file = Metainfo file
+appstream-summary = Summary
+appstream-name = AppStream names the application { $name }.
+appstream-icon = AppStream uses the icon { $icon }.
+appstream-id = AppStream launches { $id }, not this file.
+
+# Key explanations
+help-type = Application starts a program, Link opens a URL and Directory describes a menu folder.
+help-name = The name menus and docks show for the launcher.
+help-genericname = What kind of program this is, e.g. "Web Browser", shown next to the name by some menus.
+help-comment = A tooltip describing what the program lets you do.
+help-icon = An icon theme name without extension, or an absolute path to an image.
+help-exec = The command line started, with field codes like %f for files passed by the launcher.
+help-tryexec = A program that must exist for the launcher to be shown. If it isn't found, the whole launcher is hidden.
+help-terminal = Run the program inside a terminal emulator, for command-line programs.
+help-categories = Menu categories. At least one main category decides the menu the launcher appears in.
+help-keywords = Extra words search matches on, in addition to the name.
+help-mimetype = File types the program can open, offered in "Open with".
+help-actions = Additional entry points, like "New Window", shown in the launcher's context menu.
+help-onlyshowin = The launcher is shown only in these desktops. Can't be combined with Not shown in.
+help-notshowin = The launcher is hidden in these desktops. Can't be combined with Only shown in.
+help-startupnotify = The program tells the desktop when it has started, so a busy cursor or animation can end.
+help-startupwmclass = The window class or app id of the program's windows, so docks group them with this launcher.
+help-dbusactivatable = Start the program through D-Bus by its application id instead of running Exec.
+help-nodisplay = The program exists but isn't shown in menus. It's still available for opening files.
+help-hidden = The launcher is treated as deleted, typically used to remove a system launcher for one user.
+help-prefersnondefaultgpu = Ask the desktop to start the program on the discrete graphics card.
+help-implements = D-Bus interfaces the program implements.
+help-singlemainwindow = The program has a single main window, so desktops don't offer "New Window".
+help-url = The address a Link launcher opens.
+help-version = The Desktop Entry Specification version the file follows, not the program's version.
+help-path = The directory the program is started in.
+help-autostartenabled = Whether the session starts the program at login. Off keeps the file but skips it.
+help-autostartdelay = Seconds to wait after login before starting the program.
+help-autostartcondition = A condition like a settings key that must hold for the program to start at login.
+help-cosmicapplet = Marks the program as a COSMIC panel applet, listed in the panel settings instead of the app library.
+help-cosmichoverpopup = Which way the applet's popup opens when the panel is hovered.
+help-overflowpriority = How long the applet stays in the panel when space runs out, higher stays longer.
+help-hostwaylanddisplay = Lets the applet connect to the session's Wayland display itself instead of the panel's.
+help-usesnotifications = Lists the program in GNOME's notification settings, so its notifications can be turned off there.
+help-singlewindow = GNOME doesn't offer a New Window action for programs that only ever have one window.
+help-fullname = The name GNOME Shell shows where there's room, e.g. with the vendor in front.
+help-discretegpu = KDE's name for PrefersNonDefaultGPU, for older Plasma versions.
+help-protocols = URL schemes a KDE program opens itself instead of getting a downloaded copy, e.g. smb;sftp;
+help-formfactor = The device types a Phosh program is made for, others hide it by default.
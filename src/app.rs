@@ -1,13 +1,45 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use crate::config::Config;
+use crate::backup;
+use crate::categories;
+use crate::cli::Cli;
+use crate::config::{Config, MAX_BACKUPS, MAX_HISTORY};
+use crate::document::Document;
+use crate::elevate;
+use crate::entrydiff::{Comparison, DiffLine, line_diff};
+use crate::exec::{self, ExecBuilder, LaunchContext, LaunchOutput};
+use crate::export;
 use crate::fl;
-use crate::mimelist::{MimeCache, MimeCategory, MimeItem};
-use crate::xdghelp::{IconCache, PickKind, open_path, save_desktop_file};
-use crate::xkeys::{XKeyCategory, XKeyItem, remove_x_key};
-
-use cosmic::app::context_drawer;
+use crate::gpu::{self, Gpu};
+use crate::help;
+use crate::history::{self, Version};
+use crate::iconindex::IconIndex;
+use crate::import;
+use crate::lint;
+use crate::merge::{self, MergeState};
+use crate::mimeapps::{self, MimeApps, MimeAppsEditor, OpenTest, Source, Verdict};
+use crate::mimelist::{MimeCache, MimeCategory, is_valid_mimetype};
+use crate::normalize;
+use crate::packaging::Packaged;
+use crate::quickopen::{self, Launcher};
+use crate::systemd;
+use crate::templates::{self, Template};
+use crate::thumbnailer;
+use crate::toplevels::{self, RunningApp};
+use crate::trash;
+use crate::vendor::{self, Vendor};
+use crate::webapp::{self, Browser};
+use crate::wrappers::{self, WRAPPERS, Wrapper};
+use crate::xdghelp::{
+    self, IconCache, IdConflict, PickKind, dbus_activation_warnings, install_icon, is_icon_file,
+    open_path, save_desktop_file, save_json_file, user_override_path,
+};
+use crate::xdgmenu::{Menu, MenuEditor};
+use crate::xkeys::{XKeyItem, remove_x_key};
+
+use cosmic::app::{CosmicFlags, context_drawer};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::dbus_activation;
 use cosmic::iced::Alignment::Center;
 use cosmic::iced::alignment::Horizontal::Left;
 use cosmic::iced::alignment::{Horizontal, Vertical};
@@ -27,6 +59,7 @@ use cosmic::{cosmic_theme, theme};
 use freedesktop_desktop_entry::{DecodeError, DesktopEntry};
 use futures_util::SinkExt;
 use log::info;
+use notify::{RecursiveMode, Watcher};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
@@ -49,17 +82,97 @@ const ACTIONS_ICON: &[u8] = include_bytes!("../resources/icons/hicolor/scalable/
 const CUSTOM_ICON: &[u8] = include_bytes!("../resources/icons/hicolor/scalable/extensions.svg");
 const ADVANCED_ICON: &[u8] = include_bytes!("../resources/icons/hicolor/scalable/advanced.svg");
 
+// Removed diff lines and validation warnings.
+const WARNING_COLOR: cosmic::iced::Color = cosmic::iced::Color::from_rgb(0.85, 0.2, 0.2);
+
+// The mimetype of .desktop files, which the file manager opens with its default handler.
+const DESKTOP_MIME: &str = "application/x-desktop";
+
+// Upper limit for X-GNOME-Autostart-Delay in the spin button, in seconds.
+const MAX_AUTOSTART_DELAY: u32 = 600;
+
+// Offered when adding a translation, as written in `Key[locale]`.
+const TRANSLATION_LOCALES: &[&str] = &[
+    "ar", "bg", "ca", "cs", "da", "de", "el", "en_GB", "eo", "es", "et", "eu", "fa", "fi", "fr",
+    "ga", "gl", "he", "hi", "hr", "hu", "id", "it", "ja", "ka", "kk", "ko", "lt", "lv", "nb", "nl",
+    "nn", "pl", "pt", "pt_BR", "ro", "ru", "sk", "sl", "sr", "sv", "th", "tr", "uk", "vi", "zh_CN",
+    "zh_TW",
+];
+
 static FOCUSED_TEXT_INPUT_ID: LazyLock<widget::Id> =
     LazyLock::new(|| widget::Id::new("Focused Text Input"));
 
 macro_rules! desktop_edit_field {
-    ($key:expr, $hint:expr, $value:expr, $am_editing:expr, $self:ident) => {{
-        widget::editable_input($hint, $value, $am_editing, |_| Message::ToggleEdit($key))
+    ($key:expr, $hint:expr, $value:expr, $doc:ident.am_editing.$field:ident, $self:ident) => {{
+        row!(
+            widget::editable_input(
+                $hint,
+                $doc.staged_text(&$key).unwrap_or(&$value).to_string(),
+                $doc.am_editing.$field,
+                |_| Message::ToggleEdit($key),
+            )
+            .id(field_id(&$key))
+            .name(help::label(&$key))
+            .description(help::explain(&$key).unwrap_or_default())
             .width(Length::Fill)
             .on_input(|t| Message::SetTextEntry($key, t))
+            .on_submit(|_| Message::CommitEdit($key))
+            .on_unfocus(Message::CommitStaged($key))
+        )
+        .push_maybe($self.translation_switch($doc, $key))
+        .push_maybe(AppModel::revert_button($doc, $key))
+        .push_maybe(AppModel::remove_key_button($doc, $key))
+        .align_y(Center)
+        .spacing(5)
+        .width(Length::Fill)
+    }};
+}
+
+// Text input of a field, for moving focus to it.
+fn field_id(key: &DesktopKey) -> widget::Id {
+    widget::Id::new(format!("field-{key}"))
+}
+
+// Icon-only buttons have no text of their own, `name` is what screen readers announce.
+fn named<'a>(
+    button: widget::button::IconButton<'a, Message>,
+    name: String,
+) -> widget::Button<'a, Message> {
+    widget::Button::from(button).name(name)
+}
+
+// Persist one Config field through cosmic-config, or only in memory if there's no handler.
+macro_rules! set_config {
+    ($self:ident, $setter:ident, $field:ident, $value:expr) => {{
+        let value = $value;
+        if let Some(handler) = &$self.config_handler {
+            if let Err(e) = $self.config.$setter(handler, value) {
+                log::error!("Failed to save {}: {e}", stringify!($field));
+            }
+        } else {
+            $self.config.$field = value;
+        }
     }};
 }
 
+// What a field code expands to, for the Exec help.
+fn field_code_help(code: &str) -> String {
+    match code {
+        "%f" => fl!("fieldcode-f"),
+        "%F" => fl!("fieldcode-ff"),
+        "%u" => fl!("fieldcode-u"),
+        "%U" => fl!("fieldcode-uu"),
+        "%i" => fl!("fieldcode-i"),
+        "%c" => fl!("fieldcode-c"),
+        "%k" => fl!("fieldcode-k"),
+        _ => String::new(),
+    }
+}
+
+fn field_code_label(code: &str) -> String {
+    format!("{code}  {}", field_code_help(code))
+}
+
 // Removes all whitespace (spaces, tabs, newlines, etc.) from a string.
 macro_rules! rm_whitespace {
     ($s:expr) => {
@@ -83,48 +196,6 @@ pub enum AppError {
     Decode(#[from] DecodeError),
 }
 
-#[derive(Debug, Default)]
-struct Editing {
-    pub name: bool,
-    pub generic_name: bool,
-    pub comment: bool,
-    pub path: bool,
-    pub exec: bool,
-    pub icon: bool,
-    pub try_exec: bool,
-    pub only_shown_in: bool,
-    pub not_shown_in: bool,
-    pub keywords: bool,
-    pub categories: bool,
-    pub implements: bool,
-    pub startupwmclass: bool,
-    pub url: bool,
-}
-
-impl Editing {
-    pub fn toggle(&mut self, key: &DesktopKey) {
-        match key {
-            DesktopKey::Name => self.name ^= true,
-            DesktopKey::GenericName => self.generic_name ^= true,
-            DesktopKey::Comment => self.comment ^= true,
-            DesktopKey::Path => self.path ^= true,
-            DesktopKey::Exec => self.exec ^= true,
-            DesktopKey::Icon => self.icon ^= true,
-            DesktopKey::TryExec => self.try_exec ^= true,
-            DesktopKey::OnlyShowIn => self.only_shown_in ^= true,
-            DesktopKey::NotShowIn => self.not_shown_in ^= true,
-            DesktopKey::Keywords => self.keywords ^= true,
-            DesktopKey::Categories => self.categories ^= true,
-            DesktopKey::Implements => self.implements ^= true,
-            DesktopKey::StartupWMClass => self.startupwmclass ^= true,
-            DesktopKey::Url => self.url ^= true,
-            _ => {
-                todo!();
-            }
-        }
-    }
-}
-
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
 pub enum DesktopEntryType {
     #[default]
@@ -159,7 +230,18 @@ impl FromStr for DesktopEntryType {
 #[derive(Clone, Debug)]
 pub enum DialogKind {
     NewMimetype(String),
+    BulkMimetypes(String),
     NewXkey(XKeyItem),
+    RegisterHandler,
+    DroppedIcon(PathBuf),
+    DeleteEntry(PathBuf),
+    Revert(PathBuf),
+    SaveAsAdmin(PathBuf),
+    OverrideExists(PathBuf),
+    IdConflict(PathBuf, Vec<IdConflict>),
+    QuickOpen(String),
+    SaveTemplate(String),
+    NewWebApp(String),
 }
 
 #[derive(Clone, Debug)]
@@ -169,6 +251,43 @@ struct DialogPage {
     kind: DialogKind,
 }
 
+// Subcommand forwarded to an already running instance to open files.
+const OPEN_ACTION: &str = "open";
+
+/// Command line arguments, forwarded over D-Bus when an instance is already running.
+#[derive(Debug, Clone, Default)]
+pub struct Flags {
+    action: Option<String>,
+    args: Vec<String>,
+    cli: Cli,
+}
+
+impl Flags {
+    pub fn new(cli: Cli) -> Self {
+        let args = cli.to_args();
+        Self {
+            action: (!args.is_empty()).then(|| OPEN_ACTION.to_string()),
+            args,
+            cli,
+        }
+    }
+}
+
+impl CosmicFlags for Flags {
+    type SubCommand = String;
+    type Args = Vec<String>;
+
+    fn action(&self) -> Option<&String> {
+        self.action.as_ref()
+    }
+}
+
+impl From<Flags> for Vec<String> {
+    fn from(flags: Flags) -> Self {
+        flags.args
+    }
+}
+
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
 pub struct AppModel {
@@ -180,17 +299,77 @@ pub struct AppModel {
     key_binds: HashMap<menu::KeyBind, MenuAction>,
     // Configuration data that persists between application runs.
     config: Config,
-    nav: nav_bar::Model,
-    mime_table: table::SingleSelectModel<MimeItem, MimeCategory>,
-    xkey_table: table::SingleSelectModel<XKeyItem, XKeyCategory>,
+    // Handle for writing configuration changes back.
+    config_handler: Option<cosmic_config::Config>,
     locales: Vec<String>,
     mime_descriptions: MimeCache,
     icon_cache: IconCache,
-    current_entry: Option<DesktopEntry>,
-    current_entry_path: Option<PathBuf>,
-    current_entry_error: Option<AppError>,
-    current_entry_changed: bool,
-    am_editing: Editing,
+    // Title last set for each document window
+    window_titles: HashMap<WindowId, String>,
+    // Executables in $PATH for completing Exec, scanned when Exec is first edited
+    path_programs: Vec<String>,
+    // Whether every icon directory has been scanned, until then a name may still resolve
+    icons_scanned: bool,
+    // The entry shown in the main window.
+    main_doc: Document,
+    // Entries shown in additional windows.
+    windows: HashMap<WindowId, Document>,
+    // Window whose document receives edits, None for the main window.
+    active_window: Option<WindowId>,
+    // Windows comparing two files.
+    comparisons: HashMap<WindowId, Comparison>,
+    // First file picked for a comparison, waiting for the second.
+    compare_left: Option<PathBuf>,
+    // The mimeapps.list editor window, if open.
+    mimeapps_editor: Option<(WindowId, MimeAppsEditor)>,
+    // The application menu editor window, if open.
+    menu_editor: Option<(WindowId, MenuEditor)>,
+    // The file on disk against what Save would write, shown in the review drawer.
+    review_lines: Vec<DiffLine>,
+    // Saved versions of the open file, newest first, and the one shown
+    history: Vec<Version>,
+    history_selected: Option<usize>,
+    history_text: String,
+    // The user unit the autostart entry was converted to
+    systemd_unit: Option<systemd::Unit>,
+    // The sample file of the "which app opens this" tester and what it resolves to
+    open_test: Option<OpenTest>,
+    // Field code help popover next to Exec.
+    exec_help: bool,
+    // Key whose explanation popover is open.
+    help_key: Option<String>,
+    exec_builder: ExecBuilder,
+    test_launch: TestLaunch,
+    // Open windows for the StartupWMClass picker, None while listing.
+    running_apps: Option<Result<Vec<RunningApp>, String>>,
+    // X11 click-to-capture: Some(None) while waiting for the click, Some(Some(e)) on failure.
+    wm_probe: Option<Option<String>>,
+    // Dropdown labels, "none" followed by exec::FILE_CODES.
+    file_code_choices: Vec<String>,
+    // Dropdown labels of a boolean key: not set, true, false.
+    bool_choices: Vec<String>,
+    // "Automatic" followed by the terminal emulators found at startup.
+    terminal_choices: Vec<String>,
+    // Which of wrappers::WRAPPERS are installed, checked at startup.
+    installed_wrappers: Vec<bool>,
+    // Whether pkexec is installed for saving as administrator, checked at startup.
+    can_elevate: bool,
+    // Whether this app opens .desktop files, checked at startup and when registering.
+    desktop_handler: bool,
+    // The next save applies the normalizations even when they're off for every save.
+    normalize_next_save: bool,
+    // Latest size of the main window, saved to the config on quit.
+    window_size: Option<Size>,
+    // Graphics cards from switcheroo-control, None if it couldn't be asked.
+    gpus: Option<Vec<Gpu>>,
+    // Installed launchers for quick open, rescanned each time it opens.
+    launchers: Vec<Launcher>,
+    // Saved templates, listed under File > New.
+    templates: Vec<Template>,
+    // Installed browsers for the web app dialog, the default first, and their names.
+    web_browsers: Vec<Browser>,
+    web_browser_names: Vec<String>,
+    web_browser_choice: usize,
     dialog_data: Option<DialogPage>,
 }
 
@@ -198,16 +377,134 @@ pub struct AppModel {
 #[derive(Debug, Clone)]
 pub enum Message {
     Quit,
+    RegisterHandler,
+    OpenInWindow(PathBuf),
+    QuickOpen,
+    LaunchersScanned(Vec<Launcher>),
+    QuickOpenSelect(PathBuf),
+    FileDropped(WindowId, PathBuf),
+    UseIconPath(PathBuf),
     Save,
     SaveAs,
-    SaveFinished(Option<PathBuf>),
+    SaveAsChosen(WindowId, Option<PathBuf>),
+    SaveOverride,
+    OpenOverride(PathBuf),
+    ReviewChanges,
+    RestoreBackup,
+    Revert,
+    SaveAsAdmin,
+    AdminSaveFinished(WindowId, PathBuf, Result<(), String>),
+    SaveTemplate,
+    NewFromTemplate(usize),
+    NewWebApp,
+    BrowsersScanned(Vec<Browser>),
+    SetWebBrowser(usize),
+    WebAppNamed(String, Option<String>),
+    ExportJson,
+    ExportJsonFinished(Option<PathBuf>),
+    CopyEntry,
+    PasteEntry,
+    Pasted(Option<String>),
+    OpenContainingFolder,
+    OpenContainingFolderFinished(Result<(), String>),
+    DeleteEntry,
+    Trashed(WindowId, PathBuf, Result<(), String>),
+    Rename,
+    RenameFinished(Option<PathBuf>),
+    AddToAutostart,
+    DismissRenameWarnings,
+    DismissSaveWarnings,
+    UndoTrash,
+    SetBackupCount(u32),
+    SetSaveFolder(String),
+    SetHistoryCount(u32),
+    ShowHistory,
+    ShowSystemd,
+    ConvertToUnit,
+    ConvertFromUnit,
+    ShowOpenTest,
+    RerunOpenTest,
+    OpenTestResolved(OpenTest),
+    SelectVersion(usize),
+    RestoreVersion,
+    SetChmodOnSave(bool),
+    SetTerminal(usize),
+    AddTranslation(String, String),
+    SetCategory(String, bool),
+    SetShowIn(DesktopKey, String, bool),
+    ToggleExecHelp,
+    ToggleHelp(DesktopKey),
+    SetWrapper(usize, bool),
+    GpusDetected(Option<Vec<Gpu>>),
+    OpenExecBuilder,
+    ExecBuilderProgram(String),
+    ExecBuilderArg(usize, String),
+    ExecBuilderAddArg,
+    ExecBuilderRemoveArg(usize),
+    ExecBuilderFileCode(usize),
+    ApplyExecBuilder,
+    OpenMimeAppsEditor,
+    MimeAppsLoaded(Result<MimeAppsEditor, String>),
+    MimeAppsSetApps(usize, usize, String),
+    MimeAppsPick(usize, usize, usize),
+    MimeAppsRemove(usize, usize),
+    MimeAppsNewMime(usize, String),
+    MimeAppsAdd(usize),
+    MimeAppsSave,
+    SetVendorKey(&'static str, Option<String>),
+    OpenMenuEditor,
+    MenuEditorSelect(Vec<String>),
+    MenuEditorDirectory(String),
+    MenuEditorUseDirectory,
+    MenuEditorHidden(bool),
+    MenuEditorRuleInput(String),
+    MenuEditorAddRule(bool),
+    MenuEditorRemoveRule(bool, usize),
+    MenuEditorSubmenuInput(String),
+    MenuEditorAddSubmenu,
+    MenuEditorSave,
+    MimeFilter(String),
+    SetMimeGrouped(bool),
+    ToggleMimeGroup(String),
+    RemoveMimeGroup(String),
+    RemoveMimetypeNamed(String),
+    ListRunningApps,
+    RunningAppsListed(Result<Vec<RunningApp>, String>),
+    PickRunningApp(String),
+    ProbeWMClass,
+    WMClassProbed(Result<String, String>),
+    TestLaunch,
+    TestLaunchFinished(Result<LaunchOutput, String>),
+    OpenTerminalAtPath,
+    ListInput(DesktopKey, String),
+    AddListItem(DesktopKey),
+    RemoveListItem(DesktopKey, usize),
+    ListDragStart(DesktopKey, usize),
+    ListDragOver(DesktopKey, usize),
+    ListDragEnd,
+    SetTranslation(String, String, String),
+    RemoveTranslation(String, String),
+    PreviewLocale(Option<String>),
+    SetOtherKey(String, String, String),
+    MigrateDeprecated,
+    FixQuoting,
+    RemoveOtherKey(String, String),
+    SetChmodBits(u32, bool),
+    SetNormalizeOnSave(bool),
+    SetDeterministicOutput(bool),
+    SetNormalizeStep(u32, bool),
+    SaveNormalized,
     OpenPath(PickKind),
+    OpenRecent(usize),
+    ClearRecent,
     Key(Modifiers, keyboard::Key),
     OpenFileFinished((Option<PathBuf>, PickKind)),
     SetTextEntry(DesktopKey, String),
-    SetBoolEntry(DesktopKey, bool),
+    SetBoolEntry(DesktopKey, Option<bool>),
 
     MimeItemSelect(table::Entity),
+    SortMimetypes(MimeCategory),
+    CleanMimetypes,
     RemoveMimetype(usize),
 
     XkeyItemSelect(table::Entity),
@@ -217,16 +514,35 @@ pub enum Message {
     DialogClose(bool),
 
     CreateEntry(DesktopEntryType),
+    NewWindow,
+    WindowFocused(WindowId),
+    WindowResized(WindowId, Size),
+    NavSelect(nav_bar::Id),
+    CheckDiskChanges,
+    ReloadFromDisk,
+    KeepInMemory,
+    ResetToSystem(String, String),
+    TakeUpstream,
+    MarkMerged,
 
     CreateDialog(DialogKind),
     DestroyDialog,
 
     OpenRepositoryUrl,
-    SubscriptionChannel,
+    IconsScanned(Vec<PathBuf>),
+    IconScanFinished,
+    PathProgramsScanned(Vec<String>),
+    RevertKey(DesktopKey),
+    RemoveKey(DesktopKey),
+    CancelEdit,
+    CommitEdit(DesktopKey),
+    CommitStaged(DesktopKey),
+    EditNextField(bool),
     ToggleContextPage(ContextPage),
     UpdateConfig(Config),
     CloseWindow(window::Id),
     ToggleEdit(DesktopKey),
+    ToggleEditUntranslated,
     None,
 }
 
@@ -236,7 +552,7 @@ impl cosmic::Application for AppModel {
     type Executor = cosmic::executor::Default;
 
     /// Data that your application receives to its init method.
-    type Flags = ();
+    type Flags = Flags;
 
     /// Messages which the application and its widgets will emit.
     type Message = Message;
@@ -256,19 +572,51 @@ impl cosmic::Application for AppModel {
         Some(Message::Quit)
     }
 
+    /// Launching again while running ends up here instead of in a second process.
+    fn dbus_activation(
+        &mut self,
+        msg: dbus_activation::Message,
+    ) -> Task<cosmic::Action<Self::Message>> {
+        let cli = match msg.msg {
+            dbus_activation::Details::Activate => Cli::default(),
+            dbus_activation::Details::Open { url } => Cli {
+                paths: url.iter().map(ToString::to_string).collect(),
+                ..Cli::default()
+            },
+            dbus_activation::Details::ActivateAction { action, args } if action == OPEN_ACTION => {
+                match Cli::parse(args) {
+                    Ok(cli) => cli,
+                    Err(e) => {
+                        log::warn!("Ignoring forwarded arguments: {e}");
+                        Cli::default()
+                    }
+                }
+            }
+            dbus_activation::Details::ActivateAction { .. } => Cli::default(),
+        };
+
+        if cli.new.is_none() && cli.paths.is_empty() {
+            return match self.core.main_window_id() {
+                Some(id) => window::gain_focus(id),
+                None => Task::none(),
+            };
+        }
+        self.open_from_cli(cli)
+    }
+
     /// Initializes the application with any given flags and startup commands.
-    fn init(
-        core: cosmic::Core,
-        _flags: Self::Flags,
-    ) -> (Self, Task<cosmic::Action<Self::Message>>) {
+    fn init(core: cosmic::Core, flags: Self::Flags) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+
         // Construct the app model with the runtime's core.
         let mut app = AppModel {
             core,
             context_page: ContextPage::default(),
             key_binds: Self::key_binds(),
             // Optional configuration file for an application.
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
+            config: config_handler
+                .as_ref()
+                .map(|context| match Config::get_entry(context) {
                     Ok(config) => config,
                     Err((_errors, config)) => {
                         // for why in errors {
@@ -279,92 +627,87 @@ impl cosmic::Application for AppModel {
                     }
                 })
                 .unwrap_or_default(),
-            nav: nav_bar::Model::default(),
-            mime_table: table::Model::new(vec![MimeCategory::Name, MimeCategory::Description]),
-            xkey_table: table::Model::new(vec![XKeyCategory::Name, XKeyCategory::Value]),
+            config_handler,
             locales: freedesktop_desktop_entry::get_languages_from_env(),
             mime_descriptions: MimeCache::default(),
             icon_cache: IconCache::default(),
-            current_entry: None,
-            current_entry_path: None,
-            current_entry_error: None,
-            current_entry_changed: false,
-            am_editing: Editing::default(),
+            icons_scanned: false,
+            path_programs: Vec::new(),
+            window_titles: HashMap::new(),
+            main_doc: Document::default(),
+            windows: HashMap::new(),
+            active_window: None,
+            comparisons: HashMap::new(),
+            compare_left: None,
+            mimeapps_editor: None,
+            menu_editor: None,
+            review_lines: Vec::new(),
+            history: Vec::new(),
+            history_selected: None,
+            history_text: String::new(),
+            systemd_unit: None,
+            open_test: None,
+            exec_help: false,
+            help_key: None,
+            exec_builder: ExecBuilder::default(),
+            test_launch: TestLaunch::default(),
+            running_apps: None,
+            wm_probe: None,
+            file_code_choices: std::iter::once(fl!("execbuilder-nofiles"))
+                .chain(exec::FILE_CODES.iter().map(|c| field_code_label(c)))
+                .collect(),
+            bool_choices: vec![fl!("bool-unset"), fl!("bool-true"), fl!("bool-false")],
+            terminal_choices: std::iter::once(fl!("prefs-terminal-auto"))
+                .chain(exec::installed_terminals())
+                .collect(),
+            installed_wrappers: WRAPPERS.iter().map(Wrapper::is_installed).collect(),
+            can_elevate: elevate::available(),
+            desktop_handler: Self::is_desktop_handler(),
+            gpus: None,
+            normalize_next_save: false,
+            window_size: None,
+            launchers: Vec::new(),
+            templates: templates::list(),
+            web_browsers: Vec::new(),
+            web_browser_names: Vec::new(),
+            web_browser_choice: 0,
             dialog_data: None,
         };
 
-        app.load_entry_from_args();
-        app.create_nav_bar();
+        let mut cli = flags.cli;
+        if cli.new.is_none() {
+            // The first file goes in the main window, the rest get their own
+            let first = (!cli.paths.is_empty()).then(|| cli.paths.remove(0));
+            app.load_entry_from_args(first.as_ref());
+            Self::create_nav_bar(&mut app.main_doc);
+            if let Some(page) = NavPage::from_id(&app.config.nav_page) {
+                Self::activate_nav_page(&mut app.main_doc, page);
+            }
+        }
+        let mut tasks = vec![
+            app.open_from_cli(cli),
+            Task::perform(gpu::detect(), |r| {
+                cosmic::Action::App(Message::GpusDetected(r.ok()))
+            }),
+        ];
+
+        // Offer once to open .desktop files from the file manager
+        if !app.config.handler_prompted && !app.desktop_handler {
+            tasks.push(Task::done(cosmic::Action::App(Message::CreateDialog(
+                DialogKind::RegisterHandler,
+            ))));
+        }
 
-        (app, Task::none())
+        (app, Task::batch(tasks))
     }
 
     /// Enables the COSMIC application to create a nav bar with this model.
     fn nav_model(&self) -> Option<&nav_bar::Model> {
-        Some(&self.nav)
+        Some(&self.main_doc.nav)
     }
 
     fn header_start(&'_ self) -> Vec<Element<'_, Self::Message>> {
-        let (save, saveas) = if self.current_entry.is_some() {
-            (
-                menu::Item::Button(fl!("menu-save"), None, MenuAction::Save),
-                menu::Item::Button(fl!("menu-saveas"), None, MenuAction::SaveAs),
-            )
-        } else {
-            (
-                menu::Item::ButtonDisabled(fl!("menu-save"), None, MenuAction::Save),
-                menu::Item::ButtonDisabled(fl!("menu-saveas"), None, MenuAction::SaveAs),
-            )
-        };
-
-        let menu_bar = menu::bar(vec![
-            menu::Tree::with_children(
-                menu::root(fl!("menu-file")).apply(Element::from),
-                menu::items(
-                    &self.key_binds,
-                    vec![
-                        menu::Item::Folder(
-                            fl!("menu-new"),
-                            vec![
-                                menu::Item::Button(
-                                    fl!("menu-newapplication"),
-                                    None,
-                                    MenuAction::NewApplication,
-                                ),
-                                menu::Item::Button(fl!("menu-newlink"), None, MenuAction::NewLink),
-                                menu::Item::Button(
-                                    fl!("menu-newdirectory"),
-                                    None,
-                                    MenuAction::NewDirectory,
-                                ),
-                            ],
-                        ),
-                        menu::Item::Divider,
-                        menu::Item::Button(fl!("menu-open"), None, MenuAction::Open),
-                        save,
-                        saveas,
-                        menu::Item::Divider,
-                        menu::Item::Button(fl!("menu-quit"), None, MenuAction::Quit),
-                    ],
-                ),
-            ),
-            menu::Tree::with_children(
-                menu::root(fl!("menu-view")).apply(Element::from),
-                menu::items(
-                    &self.key_binds,
-                    vec![menu::Item::Button(
-                        fl!("menu-about"),
-                        None,
-                        MenuAction::About,
-                    )],
-                ),
-            ),
-        ])
-        .item_width(menu::ItemWidth::Uniform(200))
-        .item_height(menu::ItemHeight::Dynamic(200))
-        .spacing(4.0);
-
-        vec![menu_bar.into()]
+        vec![self.menu_bar(&self.main_doc)]
     }
 
     /// Display a context drawer if the context page is requested.
@@ -384,6 +727,66 @@ impl cosmic::Application for AppModel {
                 Message::ToggleContextPage(ContextPage::IOError(e.to_owned())),
             )
             .title(fl!("context-unabletosave")),
+            ContextPage::Preferences => context_drawer::context_drawer(
+                self.context_preferences(),
+                Message::ToggleContextPage(ContextPage::Preferences),
+            )
+            .title(fl!("menu-preferences")),
+            ContextPage::ReviewChanges => context_drawer::context_drawer(
+                self.context_review(),
+                Message::ToggleContextPage(ContextPage::ReviewChanges),
+            )
+            .title(fl!("context-review")),
+            ContextPage::Systemd => context_drawer::context_drawer(
+                self.context_systemd(),
+                Message::ToggleContextPage(ContextPage::Systemd),
+            )
+            .title(fl!("context-systemd")),
+            ContextPage::History => context_drawer::context_drawer(
+                self.context_history(),
+                Message::ToggleContextPage(ContextPage::History),
+            )
+            .title(fl!("context-history")),
+            ContextPage::Translations(key) => context_drawer::context_drawer(
+                self.context_translations(key),
+                Message::ToggleContextPage(ContextPage::Translations(key.clone())),
+            )
+            .title(fl!("context-translations", key = key.as_str())),
+            ContextPage::RunningApps => context_drawer::context_drawer(
+                self.context_running_apps(),
+                Message::ToggleContextPage(ContextPage::RunningApps),
+            )
+            .title(fl!("context-runningapps")),
+            ContextPage::TestLaunch => context_drawer::context_drawer(
+                self.context_test_launch(),
+                Message::ToggleContextPage(ContextPage::TestLaunch),
+            )
+            .title(fl!("context-testlaunch")),
+            ContextPage::ExecBuilder => context_drawer::context_drawer(
+                self.context_exec_builder(),
+                Message::ToggleContextPage(ContextPage::ExecBuilder),
+            )
+            .title(fl!("context-execbuilder")),
+            ContextPage::OpenTest => context_drawer::context_drawer(
+                self.context_open_test(),
+                Message::ToggleContextPage(ContextPage::OpenTest),
+            )
+            .title(fl!("context-opentest")),
+            ContextPage::ShowIn => context_drawer::context_drawer(
+                self.context_show_in(),
+                Message::ToggleContextPage(ContextPage::ShowIn),
+            )
+            .title(fl!("context-showin")),
+            ContextPage::Categories => context_drawer::context_drawer(
+                self.context_categories(),
+                Message::ToggleContextPage(ContextPage::Categories),
+            )
+            .title(fl!("field-categories")),
+            ContextPage::Overrides => context_drawer::context_drawer(
+                self.context_overrides(),
+                Message::ToggleContextPage(ContextPage::Overrides),
+            )
+            .title(fl!("context-overrides")),
         })
     }
 
@@ -392,82 +795,37 @@ impl cosmic::Application for AppModel {
     /// Application events will be processed through the view. Any messages emitted by
     /// events received by widgets will be passed to the update method.
     fn view(&self) -> Element<'_, Self::Message> {
-        let theme = cosmic::theme::active();
-        let padding = if self.core.is_condensed() {
-            theme.cosmic().space_s()
-        } else {
-            theme.cosmic().space_l()
-        };
+        self.document_view(&self.main_doc)
+    }
 
-        // MissingArgument not actually an error
-        let fatal_error = self
-            .current_entry_error
-            .as_ref()
-            .filter(|e| !matches!(e, AppError::MissingArgument));
+    fn view_window(&self, id: WindowId) -> Element<'_, Self::Message> {
+        if let Some(comparison) = self.comparisons.get(&id) {
+            return self.comparison_view(comparison);
+        }
 
-        match (fatal_error, self.current_entry.as_ref()) {
-            // Landing / browse
-            (None, None) => {
-                let folder = widget::icon::from_name("folder-symbolic").handle();
+        if let Some((window_id, editor)) = &self.mimeapps_editor
+            && *window_id == id
+        {
+            return Self::mimeapps_view(editor);
+        }
 
-                column!(
-                    vertical_space(),
-                    widget::text::title1(fl!("app-title"))
-                        .apply(widget::container)
-                        .width(Length::Fill)
-                        .align_x(Horizontal::Center)
-                        .align_y(Vertical::Center),
-                    widget::button::text(fl!("action-browse"))
-                        .trailing_icon(folder)
-                        .on_press(Message::OpenPath(PickKind::DesktopFile)),
-                    vertical_space()
-                )
-                .align_x(Horizontal::Center)
-                .into()
-            }
+        if let Some((window_id, editor)) = &self.menu_editor
+            && *window_id == id
+        {
+            return self.menu_editor_view(editor);
+        }
 
-            // Error
-            (Some(error), _) => column!(
-                widget::text::title1(fl!("error-parsingentry"))
-                    .apply(widget::container)
-                    .width(Length::Fill)
-                    .height(Length::Fill)
-                    .align_x(Horizontal::Center)
-                    .align_y(Vertical::Center),
-                widget::text::body(error.to_string())
-                    .apply(widget::container)
-                    .width(Length::Fill)
-                    .height(Length::Fill)
-                    .align_x(Horizontal::Center)
+        if let Some(doc) = self.windows.get(&id) {
+            return column!(
+                self.menu_bar(doc),
+                row!(
+                    widget::nav_bar(&doc.nav, Message::NavSelect),
+                    self.document_view(doc)
+                )
             )
-            .into(),
-
-            // Show entry
-            (None, Some(entry)) => {
-                match entry.type_().unwrap_or_default().to_lowercase().as_str() {
-                    "link" => self.link_view(entry, padding),
-                    "directory" => self.directory_view(entry, padding),
-                    "application" => self.application_view(entry, padding),
-                    _ => column!(
-                        widget::text::title1(fl!("error-parsingentry"))
-                            .apply(widget::container)
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .align_x(Horizontal::Center)
-                            .align_y(Vertical::Center),
-                        widget::text::body(fl!("error-parsingentry"))
-                            .apply(widget::container)
-                            .width(Length::Fill)
-                            .height(Length::Fill)
-                            .align_x(Horizontal::Center)
-                    )
-                    .into(),
-                }
-            }
+            .into();
         }
-    }
 
-    fn view_window(&self, _id: WindowId) -> Element<'_, Self::Message> {
         if let Some(dialog_data) = &self.dialog_data {
             let theme = cosmic::theme::active();
             let padding = if self.core.is_condensed() {
@@ -478,11 +836,20 @@ impl cosmic::Application for AppModel {
 
             let dialog = match &dialog_data.kind {
                 DialogKind::NewMimetype(text) => {
-                    let ok_button = if text.is_empty() {
-                        widget::button::suggested(fl!("generic-save"))
+                    let mimetype = rm_whitespace!(text);
+                    let valid = is_valid_mimetype(&mimetype);
+                    let ok_button = widget::button::suggested(fl!("generic-save"))
+                        .on_press_maybe(valid.then_some(Message::DialogClose(true)));
+
+                    // Malformed names can't be added, unknown ones only get a warning
+                    let problem = if mimetype.is_empty() {
+                        None
+                    } else if !valid {
+                        Some(fl!("mime-invalid", name = mimetype.as_str()))
+                    } else if self.mime_descriptions.lookup(&mimetype).is_none() {
+                        Some(fl!("mime-unknown", name = mimetype.as_str()))
                     } else {
-                        widget::button::suggested(fl!("generic-save"))
-                            .on_press(Message::DialogClose(true))
+                        None
                     };
 
                     widget::dialog()
@@ -493,24 +860,48 @@ impl cosmic::Application for AppModel {
                                 .on_press(Message::DialogClose(false)),
                         )
                         .control(
-                            widget::text_input("", text)
-                                .id(FOCUSED_TEXT_INPUT_ID.clone())
-                                .on_input(|t| Message::DialogEdit(DialogKind::NewMimetype(t))),
+                            column!(
+                                widget::text_input(fl!("hint-mimetype"), text)
+                                    .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                    .on_input(|t| Message::DialogEdit(DialogKind::NewMimetype(t))),
+                            )
+                            .push_maybe(problem.map(Self::warning))
+                            .spacing(padding),
                         )
                 }
-                DialogKind::NewXkey(xkey_item) => {
-                    let ok_button = if xkey_item.name.is_empty() {
-                        widget::button::suggested(fl!("generic-save"))
-                    } else {
-                        widget::button::suggested(fl!("generic-save"))
-                            .on_press(Message::DialogClose(true))
-                    };
-
-                    let name = xkey_item.name.clone();
-                    let value = xkey_item.value.clone();
+                DialogKind::BulkMimetypes(text) => {
+                    let bulk = self.doc().bulk_mimetypes(text);
+                    let ok_button = widget::button::suggested(fl!("generic-add")).on_press_maybe(
+                        (!bulk.add.is_empty()).then_some(Message::DialogClose(true)),
+                    );
+
+                    let mut summary = widget::column().push(widget::text::caption(fl!(
+                        "bulkmime-summary",
+                        add = bulk.add.len(),
+                        duplicates = bulk.duplicates
+                    )));
+                    if !bulk.invalid.is_empty() {
+                        summary = summary.push(Self::warning(fl!(
+                            "bulkmime-invalid",
+                            names = bulk.invalid.join(", ")
+                        )));
+                    }
+                    let unknown: Vec<&str> = bulk
+                        .add
+                        .iter()
+                        .filter(|m| self.mime_descriptions.lookup(m).is_none())
+                        .map(String::as_str)
+                        .collect();
+                    if !unknown.is_empty() {
+                        summary = summary.push(Self::warning(fl!(
+                            "bulkmime-unknown",
+                            names = unknown.join(", ")
+                        )));
+                    }
 
                     widget::dialog()
-                        .title(fl!("dialog-title-newxkey"))
+                        .title(fl!("dialog-title-bulkmime"))
+                        .body(fl!("bulkmime-expl"))
                         .primary_action(ok_button)
                         .secondary_action(
                             widget::button::standard(fl!("generic-cancel"))
@@ -518,53 +909,293 @@ impl cosmic::Application for AppModel {
                         )
                         .control(
                             column!(
-                                {
-                                    let x = xkey_item.clone();
-                                    column!(
-                                        widget::text::body(fl!("generic-name")).width(100),
-                                        widget::text_input("X-Custom", name.clone())
-                                            .id(FOCUSED_TEXT_INPUT_ID.clone())
-                                            .on_input(move |t| {
-                                                let mut new = x.clone();
-                                                new.name = t;
-                                                Message::DialogEdit(DialogKind::NewXkey(new))
-                                            })
-                                    )
-                                },
-                                {
-                                    let x = xkey_item.clone();
-                                    column!(
-                                        widget::text::body(fl!("generic-value")).width(100),
-                                        widget::text_input("true", value.clone()).on_input(
-                                            move |t| {
-                                                let mut new = x.clone();
-                                                new.value = t;
-                                                Message::DialogEdit(DialogKind::NewXkey(new))
-                                            }
-                                        )
-                                    )
-                                }
+                                widget::text_input("audio/flac; audio/ogg", text)
+                                    .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                    .on_input(|t| {
+                                        Message::DialogEdit(DialogKind::BulkMimetypes(t))
+                                    }),
+                                summary,
                             )
                             .spacing(padding),
                         )
                 }
-            };
+                DialogKind::DroppedIcon(path) => widget::dialog()
+                    .title(fl!("dialog-title-droppedicon"))
+                    .body(fl!("droppedicon-expl", path = path.display().to_string()))
+                    .icon(widget::icon(widget::icon::from_path(path.clone())).size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("action-installicon"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("action-usepath"))
+                            .on_press(Message::UseIconPath(path.clone())),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("generic-cancel"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::QuickOpen(query) => {
+                    let mut results = widget::column().spacing(2);
+                    for launcher in quickopen::search(&self.launchers, query) {
+                        results = results.push(
+                            widget::button::custom(
+                                row!(
+                                    widget::text::body(launcher.name.clone()).width(Length::Fill),
+                                    widget::text::caption(launcher.id.clone()),
+                                )
+                                .spacing(padding),
+                            )
+                            .class(cosmic::theme::Button::MenuItem)
+                            .width(Length::Fill)
+                            .on_press(Message::QuickOpenSelect(launcher.path.clone())),
+                        );
+                    }
 
-            widget::autosize::autosize(dialog, dialog_data.widget_id.clone()).into()
-        } else {
-            horizontal_space().into()
-        }
-    }
-    /// Register subscriptions for this application.
-    ///
+                    widget::dialog()
+                        .title(fl!("dialog-title-quickopen"))
+                        .secondary_action(
+                            widget::button::standard(fl!("generic-cancel"))
+                                .on_press(Message::DialogClose(false)),
+                        )
+                        .control(
+                            column!(
+                                widget::search_input(fl!("hint-quickopen"), query)
+                                    .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                    .on_input(|t| Message::DialogEdit(DialogKind::QuickOpen(t)))
+                                    .on_submit(|_| Message::DialogClose(true)),
+                                widget::scrollable::vertical(results).height(Length::Fill),
+                            )
+                            .spacing(padding),
+                        )
+                }
+                DialogKind::SaveTemplate(name) => {
+                    let ok_button = widget::button::suggested(fl!("generic-save")).on_press_maybe(
+                        (!name.trim().is_empty()).then_some(Message::DialogClose(true)),
+                    );
+                    let exists = self.templates.iter().any(|t| t.name == name.trim());
+
+                    widget::dialog()
+                        .title(fl!("dialog-title-savetemplate"))
+                        .body(fl!("savetemplate-expl"))
+                        .primary_action(ok_button)
+                        .secondary_action(
+                            widget::button::standard(fl!("generic-cancel"))
+                                .on_press(Message::DialogClose(false)),
+                        )
+                        .control(
+                            column!(
+                                widget::text_input(fl!("hint-template"), name)
+                                    .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                    .on_input(|t| Message::DialogEdit(DialogKind::SaveTemplate(t)))
+                                    .on_submit(|_| Message::DialogClose(true)),
+                            )
+                            .push_maybe(exists.then(|| Self::warning(fl!("template-exists"))))
+                            .spacing(padding),
+                        )
+                }
+                DialogKind::NewWebApp(url) => {
+                    let ready = !url.trim().is_empty() && !self.web_browsers.is_empty();
+                    let ok_button = widget::button::suggested(fl!("generic-create"))
+                        .on_press_maybe(ready.then_some(Message::DialogClose(true)));
+                    let browser: Element<'_, Message> = if self.web_browsers.is_empty() {
+                        Self::warning(fl!("webapp-nobrowser"))
+                    } else {
+                        widget::dropdown(
+                            &self.web_browser_names,
+                            Some(self.web_browser_choice),
+                            Message::SetWebBrowser,
+                        )
+                        .into()
+                    };
+
+                    widget::dialog()
+                        .title(fl!("dialog-title-webapp"))
+                        .body(fl!("webapp-expl"))
+                        .primary_action(ok_button)
+                        .secondary_action(
+                            widget::button::standard(fl!("generic-cancel"))
+                                .on_press(Message::DialogClose(false)),
+                        )
+                        .control(
+                            column!(
+                                widget::text_input("https://example.com", url)
+                                    .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                    .on_input(|t| Message::DialogEdit(DialogKind::NewWebApp(t))),
+                                browser,
+                            )
+                            .spacing(padding),
+                        )
+                }
+                DialogKind::DeleteEntry(path) => widget::dialog()
+                    .title(fl!("dialog-title-delete"))
+                    .body(fl!("delete-expl", path = path.display().to_string()))
+                    .primary_action(
+                        widget::button::destructive(fl!("action-movetotrash"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("generic-cancel"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::Revert(path) => widget::dialog()
+                    .title(fl!("dialog-title-revert"))
+                    .body(fl!("revert-expl", path = path.display().to_string()))
+                    .primary_action(
+                        widget::button::destructive(fl!("action-revert"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("generic-cancel"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::SaveAsAdmin(path) => widget::dialog()
+                    .title(fl!("dialog-title-saveasadmin"))
+                    .body(fl!("saveasadmin-expl", path = path.display().to_string()))
+                    .primary_action(
+                        widget::button::destructive(fl!("action-saveasadmin"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("generic-cancel"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::OverrideExists(path) => widget::dialog()
+                    .title(fl!("dialog-title-overrideexists"))
+                    .body(fl!(
+                        "overrideexists-expl",
+                        path = path.display().to_string()
+                    ))
+                    .primary_action(
+                        widget::button::destructive(fl!("action-replaceoverride"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("action-openoverride"))
+                            .on_press(Message::OpenOverride(path.clone())),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("generic-cancel"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::IdConflict(path, conflicts) => {
+                    let id = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let mut list = widget::column().spacing(2);
+                    for conflict in conflicts {
+                        let path = conflict.path.display().to_string();
+                        list = list.push(widget::text::caption(if conflict.shadowed {
+                            fl!("idconflict-shadows", path = path)
+                        } else {
+                            fl!("idconflict-shadowed", path = path)
+                        }));
+                    }
+
+                    widget::dialog()
+                        .title(fl!("dialog-title-idconflict"))
+                        .body(fl!("idconflict-expl", id = id))
+                        .primary_action(
+                            widget::button::suggested(fl!("action-saveanyway"))
+                                .on_press(Message::DialogClose(true)),
+                        )
+                        .secondary_action(
+                            widget::button::standard(fl!("generic-cancel"))
+                                .on_press(Message::DialogClose(false)),
+                        )
+                        .control(list)
+                }
+                DialogKind::RegisterHandler => widget::dialog()
+                    .title(fl!("dialog-title-handler"))
+                    .body(fl!("handler-expl"))
+                    .primary_action(
+                        widget::button::suggested(fl!("action-makedefault"))
+                            .on_press(Message::DialogClose(true)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("generic-notnow"))
+                            .on_press(Message::DialogClose(false)),
+                    ),
+                DialogKind::NewXkey(xkey_item) => {
+                    let ok_button = if xkey_item.name.is_empty() {
+                        widget::button::suggested(fl!("generic-save"))
+                    } else {
+                        widget::button::suggested(fl!("generic-save"))
+                            .on_press(Message::DialogClose(true))
+                    };
+
+                    let name = xkey_item.name.clone();
+                    let value = xkey_item.value.clone();
+
+                    widget::dialog()
+                        .title(fl!("dialog-title-newxkey"))
+                        .primary_action(ok_button)
+                        .secondary_action(
+                            widget::button::standard(fl!("generic-cancel"))
+                                .on_press(Message::DialogClose(false)),
+                        )
+                        .control(
+                            column!(
+                                {
+                                    let x = xkey_item.clone();
+                                    column!(
+                                        widget::text::body(fl!("generic-name")).width(100),
+                                        widget::text_input("X-Custom", name.clone())
+                                            .id(FOCUSED_TEXT_INPUT_ID.clone())
+                                            .on_input(move |t| {
+                                                let mut new = x.clone();
+                                                new.name = t;
+                                                Message::DialogEdit(DialogKind::NewXkey(new))
+                                            })
+                                    )
+                                },
+                                {
+                                    let x = xkey_item.clone();
+                                    column!(
+                                        widget::text::body(fl!("generic-value")).width(100),
+                                        widget::text_input("true", value.clone()).on_input(
+                                            move |t| {
+                                                let mut new = x.clone();
+                                                new.value = t;
+                                                Message::DialogEdit(DialogKind::NewXkey(new))
+                                            }
+                                        )
+                                    )
+                                }
+                            )
+                            .spacing(padding),
+                        )
+                }
+            };
+
+            widget::autosize::autosize(dialog, dialog_data.widget_id.clone()).into()
+        } else {
+            horizontal_space().into()
+        }
+    }
+    /// Register subscriptions for this application.
+    ///
     /// Subscriptions are long-running async tasks running in the background which
     /// emit messages to the application through a channel. They are started at the
     /// beginning of the application, and persist through its lifetime.
     fn subscription(&self) -> Subscription<Self::Message> {
-        struct MySubscription;
+        struct IconScanSubscription;
+        struct DiskWatchSubscription;
 
-        Subscription::batch(vec![
+        let mut subscriptions = vec![
             event::listen_with(|event, status, window_id| match event {
+                // A focused field captures Escape, which is exactly when it cancels the edit
+                event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: Key::Named(keyboard::key::Named::Escape),
+                    ..
+                }) => Some(Message::CancelEdit),
+                // Text inputs don't use Tab, it moves between fields instead
+                event::Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: Key::Named(keyboard::key::Named::Tab),
+                    modifiers,
+                    ..
+                }) => Some(Message::EditNextField(modifiers.shift())),
                 event::Event::Keyboard(keyboard::Event::KeyPressed { modifiers, key, .. }) => {
                     match status {
                         event::Status::Ignored => Some(Message::Key(modifiers, key)),
@@ -574,13 +1205,31 @@ impl cosmic::Application for AppModel {
                 event::Event::Window(cosmic::iced::window::Event::CloseRequested) => {
                     Some(Message::CloseWindow(window_id))
                 }
+                event::Event::Window(cosmic::iced::window::Event::Focused) => {
+                    Some(Message::WindowFocused(window_id))
+                }
+                event::Event::Window(cosmic::iced::window::Event::Resized(size)) => {
+                    Some(Message::WindowResized(window_id, size))
+                }
                 _ => None,
             }),
-            // Create a subscription which emits updates through a channel.
+            // Walk the icon directories in the background, one base directory at a time,
+            // so startup isn't blocked and icons resolve as they are found.
+            // Results are cached on disk, keyed by directory mtimes.
             Subscription::run_with_id(
-                std::any::TypeId::of::<MySubscription>(),
+                std::any::TypeId::of::<IconScanSubscription>(),
                 cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    _ = channel.send(Message::SubscriptionChannel).await;
+                    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+                    tokio::task::spawn_blocking(move || {
+                        IconIndex::scan_all(|paths| {
+                            _ = tx.send(paths);
+                        });
+                    });
+
+                    while let Some(paths) = rx.recv().await {
+                        _ = channel.send(Message::IconsScanned(paths)).await;
+                    }
+                    _ = channel.send(Message::IconScanFinished).await;
 
                     futures_util::future::pending().await
                 }),
@@ -595,7 +1244,73 @@ impl cosmic::Application for AppModel {
 
                     Message::UpdateConfig(update.config)
                 }),
-        ])
+        ];
+
+        // Watch open files for changes made by other programs. Their directories are
+        // watched, since many editors replace a file instead of writing to it.
+        let mut files: Vec<PathBuf> = self
+            .documents()
+            .filter_map(|doc| doc.current_entry_path.clone())
+            .collect();
+        files.sort();
+        files.dedup();
+        if !files.is_empty() {
+            subscriptions.push(Subscription::run_with_id(
+                (
+                    std::any::TypeId::of::<DiskWatchSubscription>(),
+                    files.clone(),
+                ),
+                cosmic::iced::stream::channel(4, move |mut channel| async move {
+                    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+                    let watcher =
+                        notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                            if let Ok(event) = event {
+                                _ = tx.send(event.paths);
+                            }
+                        });
+                    let mut watcher = match watcher {
+                        Ok(watcher) => watcher,
+                        Err(e) => {
+                            log::error!("Failed to watch open files: {e}");
+                            return futures_util::future::pending().await;
+                        }
+                    };
+                    let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+                    dirs.dedup();
+                    for dir in dirs {
+                        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                            log::warn!("Failed to watch {}: {e}", dir.display());
+                        }
+                    }
+
+                    while let Some(paths) = rx.recv().await {
+                        if paths.iter().any(|p| files.contains(p)) {
+                            _ = channel.send(Message::CheckDiskChanges).await;
+                        }
+                    }
+                    futures_util::future::pending().await
+                }),
+            ));
+        }
+
+        subscriptions.push(event::listen_with(|event, _, id| match event {
+            event::Event::Window(window::Event::FileDropped(path)) => {
+                Some(Message::FileDropped(id, path))
+            }
+            _ => None,
+        }));
+
+        // A chip drag ends wherever the button is released
+        if self.documents().any(|doc| doc.list_drag.is_some()) {
+            subscriptions.push(event::listen_with(|event, _, _| match event {
+                event::Event::Mouse(cosmic::iced::mouse::Event::ButtonReleased(_)) => {
+                    Some(Message::ListDragEnd)
+                }
+                _ => None,
+            }));
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     /// Handles messages emitted by the application and its widgets.
@@ -603,6 +1318,26 @@ impl cosmic::Application for AppModel {
     /// Tasks may be returned for asynchronous execution of code in the background
     /// on the application's async runtime.
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
+        let task = self.handle_message(message);
+        // Any message may have changed whether a document has unsaved changes
+        Task::batch(vec![task, self.sync_titles()])
+    }
+
+    /// Called when a nav item is selected.
+    fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
+        // Activate the page in the model.
+        self.main_doc.nav.activate(id);
+        if let Some(page) = self.main_doc.nav.data::<NavPage>(id) {
+            let page = page.id().to_string();
+            set_config!(self, set_nav_page, nav_page, page);
+        }
+
+        self.update_title()
+    }
+}
+
+impl AppModel {
+    fn handle_message(&mut self, message: Message) -> Task<cosmic::Action<Message>> {
         match message {
             Message::CreateDialog(kind) => {
                 if self.dialog_data.is_none() {
@@ -648,231 +1383,2614 @@ impl cosmic::Application for AppModel {
             }
 
             Message::Quit => {
+                if let Some(size) = self.window_size {
+                    // Sizes are whole logical pixels, fractions come from scaling
+                    set_config!(
+                        self,
+                        set_window_width,
+                        window_width,
+                        size.width.round() as u32
+                    );
+                    set_config!(
+                        self,
+                        set_window_height,
+                        window_height,
+                        size.height.round() as u32
+                    );
+                }
                 std::process::exit(0);
             }
+            Message::WindowResized(id, size) => {
+                if Some(id) == self.core.main_window_id() {
+                    self.window_size = Some(size);
+                }
+            }
             Message::SaveAs => {
-                if let Some(entry) = &self.current_entry {
-                    let kind = self.entry_type().unwrap_or_default();
-
-                    let base = entry
-                        .name(&self.locales)
-                        .map(|s| s.to_lowercase().replace(' ', "-"))
+                let doc = self.doc();
+                if let Some(entry) = &doc.current_entry {
+                    let kind = doc.entry_type().unwrap_or_default();
+
+                    // New applications get a reverse-DNS ID, other entries their name
+                    let app_id = (kind == DesktopEntryType::Application
+                        && doc.current_entry_path.is_none())
+                    .then(|| {
+                        xdghelp::suggested_app_id(
+                            entry.name(&self.locales).as_deref(),
+                            entry.startup_wm_class(),
+                            entry.exec(),
+                        )
+                    })
+                    .flatten();
+                    let base = app_id
+                        .or_else(|| {
+                            entry
+                                .name(&self.locales)
+                                .map(|s| s.to_lowercase().replace(' ', "-"))
+                        })
                         .unwrap_or_else(|| match kind {
                             DesktopEntryType::Link => fl!("filename-link"),
                             DesktopEntryType::Directory => fl!("filename-directory"),
                             _ => fl!("filename-application"),
                         });
 
-                    let ext = if kind == DesktopEntryType::Directory {
+                    let ext = if doc.thumbnailer {
+                        ".thumbnailer"
+                    } else if kind == DesktopEntryType::Directory {
                         ".directory"
                     } else {
                         ".desktop"
                     };
 
                     let suggested = format!("{base}{ext}");
+                    let folder = if kind == DesktopEntryType::Directory {
+                        xdghelp::directory_entry_folder()
+                    } else {
+                        xdghelp::save_folder(&self.config.save_folder)
+                    };
 
-                    return Task::perform(save_desktop_file(suggested, kind), |f| {
-                        cosmic::Action::App(Message::SaveFinished(f))
+                    let Some(id) = self.document_window() else {
+                        return Task::none();
+                    };
+                    return Task::perform(save_desktop_file(suggested, kind, folder), move |f| {
+                        cosmic::Action::App(Message::SaveAsChosen(id, f))
                     });
                 }
             }
-            Message::SaveFinished(res) => {
-                info!("Message::SaveFinished {res:?}");
-                if let Some(path) = res
-                    && let Some(entry) = &mut self.current_entry
-                {
-                    if let Err(e) = Self::save_desktop_entry(&path, &entry.to_string()) {
-                        info!("Error saving {e}");
+            Message::SaveAsChosen(id, res) => {
+                return self.in_window(id, |app| {
+                    // Another launcher with the same ID hides this one or is hidden by it
+                    if let Some(path) = &res {
+                        let conflicts = xdghelp::id_conflicts(path);
+                        if !conflicts.is_empty() {
+                            return app.update(Message::CreateDialog(DialogKind::IdConflict(
+                                path.clone(),
+                                conflicts,
+                            )));
+                        }
+                    }
+                    app.save_finished(res)
+                });
+            }
+            Message::RestoreBackup => {
+                let Some(path) = self.doc().current_entry_path.clone() else {
+                    return Task::none();
+                };
+                let Some(backup) = backup::latest_backup(&path) else {
+                    return Task::none();
+                };
+                match DesktopEntry::from_path::<&str>(&backup, None) {
+                    Ok(mut entry) => {
+                        // Restored content replaces the edits, saving writes it back
+                        entry.path.clone_from(&path);
+                        let (doc, locales, mimes) = self.doc_with_caches();
+                        doc.current_entry = Some(entry);
+                        doc.rebuild_tables(locales, mimes);
+                        doc.changed();
+                        Self::create_nav_bar(doc);
+                    }
+                    Err(e) => {
                         return self.update(Message::ToggleContextPage(ContextPage::IOError(
                             e.to_string(),
                         )));
                     }
-
-                    self.current_entry_changed = false;
-                    self.current_entry_error = None;
-                    self.current_entry_path = Some(path);
                 }
             }
-            Message::Save => {
-                if self.current_entry_changed
-                    && let Some(entry) = &self.current_entry
-                {
-                    if self.current_entry_path.is_none() {
-                        return self.update(Message::SaveAs);
-                    } else if entry.path.is_file() {
-                        return self.update(Message::SaveFinished(Some(entry.path.clone())));
-                    }
+            Message::NewWebApp => {
+                let locales = self.locales.clone();
+                return Task::batch(vec![
+                    self.update(Message::CreateDialog(DialogKind::NewWebApp(String::new()))),
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                webapp::installed_browsers(&locales)
+                            })
+                            .await
+                            .unwrap_or_default()
+                        },
+                        |b| cosmic::Action::App(Message::BrowsersScanned(b)),
+                    ),
+                ]);
+            }
+            Message::BrowsersScanned(browsers) => {
+                self.web_browser_names = browsers.iter().map(|b| b.name.clone()).collect();
+                self.web_browsers = browsers;
+                self.web_browser_choice = 0;
+            }
+            Message::SetWebBrowser(i) => self.web_browser_choice = i,
+            Message::WebAppNamed(placeholder, Some(site)) => {
+                // Unless the name was edited in the meantime
+                let doc = self.doc_mut();
+                let name = doc
+                    .current_entry
+                    .as_ref()
+                    .and_then(|e| e.desktop_entry("Name"));
+                if name == Some(placeholder.as_str()) {
+                    doc.set_text(DesktopKey::Name, site);
                 }
             }
-            Message::OpenPath(kind) => {
-                return Task::perform(open_path(kind), |f| {
-                    cosmic::Action::App(Message::OpenFileFinished(f))
-                });
+            Message::WebAppNamed(_, None) => {}
+            Message::SaveTemplate => {
+                let locales = self.locales.clone();
+                if let Some(entry) = &self.doc().current_entry {
+                    let name = entry.name(&locales).unwrap_or_default().into_owned();
+                    return self.update(Message::CreateDialog(DialogKind::SaveTemplate(name)));
+                }
             }
-            Message::Key(modifiers, key) => {
-                for (key_bind, action) in &self.key_binds {
-                    if key_bind.matches(modifiers, &key) {
-                        return self.update(action.message());
+            Message::NewFromTemplate(i) => {
+                let Some(template) = self.templates.get(i) else {
+                    return Task::none();
+                };
+                match templates::instantiate(&template.path) {
+                    Ok(entry) => {
+                        let (doc, locales, mimes) = self.doc_with_caches();
+                        doc.clear();
+                        doc.current_entry = Some(entry);
+                        doc.rebuild_tables(locales, mimes);
+                        doc.changed();
+                        Self::create_nav_bar(doc);
+                    }
+                    Err(e) => {
+                        return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
                     }
                 }
             }
-            Message::OpenFileFinished(path) => {
-                if let (Some(desktop_file), kind) = path {
-                    match kind {
-                        // Load file
-                        PickKind::DesktopFile => {
-                            self.load_entry_from_path(&desktop_file);
-                        }
-                        // Save Exec or Path in current desktop entry
-                        PickKind::Executable | PickKind::TryExecutable => {
-                            self.set_exec_with_args(&desktop_file, kind, None);
-                        }
-                        PickKind::Directory => {
-                            self.set_path(&desktop_file);
-                        }
-                        PickKind::IconFile => {
-                            self.set_text(DesktopKey::Icon, desktop_file.to_string_lossy());
-                        }
-                    }
+            Message::ExportJson => {
+                let doc = self.doc();
+                if doc.current_entry.is_some() {
+                    let base = doc
+                        .current_entry_path
+                        .as_deref()
+                        .and_then(Path::file_stem)
+                        .map_or_else(
+                            || fl!("filename-application"),
+                            |s| s.to_string_lossy().into(),
+                        );
+                    return Task::perform(save_json_file(format!("{base}.json")), |f| {
+                        cosmic::Action::App(Message::ExportJsonFinished(f))
+                    });
                 }
             }
-
-            Message::SetTextEntry(key, text) => {
-                self.set_text(key, text);
+            Message::ExportJsonFinished(Some(path)) => {
+                let doc = self.doc();
+                if let Some(entry) = &doc.current_entry {
+                    let json = export::to_json_string(entry, doc.current_entry_path.as_deref());
+                    if let Err(e) = std::fs::write(&path, json) {
+                        return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                            e.to_string(),
+                        )));
+                    }
+                }
             }
-
-            Message::SetBoolEntry(key, boolean) => {
-                self.set_bool(key, boolean);
+            Message::ExportJsonFinished(None) => {}
+            Message::CopyEntry => {
+                if let Some(entry) = &self.doc().current_entry {
+                    return cosmic::iced::clipboard::write(entry.to_string());
+                }
             }
-
-            Message::OpenRepositoryUrl => {
-                _ = open::that_detached(REPOSITORY);
+            Message::PasteEntry => {
+                return cosmic::iced::clipboard::read()
+                    .map(|text| cosmic::Action::App(Message::Pasted(text)));
             }
-            Message::MimeItemSelect(entity) => self.mime_table.activate(entity),
-            Message::RemoveMimetype(pos) => {
-                if let Some(entity) = self.mime_table.entity_at(pos as u16) {
-                    // Update table model
-                    self.mime_table.remove(entity);
-                    let mut mimes = Vec::new();
-                    for entity in self.mime_table.iter() {
-                        if let Some(mime) = self.mime_table.item(entity) {
-                            mimes.push(mime.name.clone());
-                        }
+            Message::Pasted(text) => {
+                match import::pasted_entry(text.as_deref().unwrap_or_default()) {
+                    Ok(entry) => {
+                        let (doc, locales, mimes) = self.doc_with_caches();
+                        doc.clear();
+                        doc.current_entry = Some(entry);
+                        doc.rebuild_tables(locales, mimes);
+                        doc.changed();
+                        Self::create_nav_bar(doc);
+                    }
+                    Err(e) => {
+                        return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
                     }
-                    // Update desktop entry from table
-                    self.set_list(DesktopKey::MimeType, &mimes);
                 }
             }
-
-            Message::XkeyItemSelect(entity) => self.xkey_table.activate(entity),
-            Message::RemoveXkey(pos) => {
-                if let Some(entity) = self.xkey_table.entity_at(pos as u16)
-                    && let (Some(entry), Some(item)) =
-                        (&mut self.current_entry, self.xkey_table.item(entity))
-                {
-                    let _ = remove_x_key(entry, "Desktop Entry", &item.name);
-                    self.current_entry_changed = true;
-                    // Update table model
-                    self.xkey_table.remove(entity);
+            Message::OpenContainingFolder => {
+                if let Some(path) = self.doc().current_entry_path.clone() {
+                    return Task::perform(xdghelp::open_containing_folder(path), |r| {
+                        cosmic::Action::App(Message::OpenContainingFolderFinished(r))
+                    });
                 }
             }
-
-            Message::DialogEdit(edit) => {
-                if let Some(dialog_data) = &mut self.dialog_data {
-                    match (&mut dialog_data.kind, &edit) {
-                        (DialogKind::NewMimetype(data), DialogKind::NewMimetype(edit)) => {
-                            data.clone_from(edit);
-                        }
-                        (DialogKind::NewXkey(data), DialogKind::NewXkey(edit)) => {
-                            data.clone_from(edit);
-                        }
-                        _ => todo!(),
-                    }
+            Message::OpenContainingFolderFinished(Ok(())) => {}
+            Message::OpenContainingFolderFinished(Err(e)) => {
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+            }
+            Message::Rename => {
+                let doc = self.doc();
+                if let Some(path) = &doc.current_entry_path {
+                    let kind = doc.entry_type().unwrap_or_default();
+                    let name = path
+                        .file_name()
+                        .map(|f| f.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    // Renaming starts where the file is now
+                    let folder = path.parent().map(Path::to_path_buf);
+                    return Task::perform(save_desktop_file(name, kind, folder), |f| {
+                        cosmic::Action::App(Message::RenameFinished(f))
+                    });
                 }
             }
+            Message::RenameFinished(Some(target)) => {
+                let Some(from) = self.doc().current_entry_path.clone() else {
+                    return Task::none();
+                };
+                if target == from {
+                    return Task::none();
+                }
+                if let Err(e) = xdghelp::move_file(&from, &target) {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                        e.to_string(),
+                    )));
+                }
 
-            Message::DialogClose(create) => {
-                if create && let Some(dialog_data) = &self.dialog_data {
-                    match &dialog_data.kind {
-                        DialogKind::NewMimetype(data) => {
-                            if !data.is_empty() {
-                                self.create_mimetype(&rm_whitespace!(data));
+                // Keep editing under the new name, unsaved edits included
+                let doc = self.doc_mut();
+                if let Some(entry) = &mut doc.current_entry {
+                    entry.path.clone_from(&target);
+                }
+                doc.current_entry_path = Some(target.clone());
+                doc.refresh_file_state();
+                let dbus = doc
+                    .current_entry
+                    .as_ref()
+                    .is_some_and(DesktopEntry::dbus_activatable);
+                doc.rename_warnings =
+                    xdghelp::file_name_warnings(&target, dbus, doc.dbus_service.as_deref());
+
+                let recent: Vec<PathBuf> = self
+                    .config
+                    .recent_files
+                    .iter()
+                    .filter(|p| **p != from)
+                    .cloned()
+                    .collect();
+                self.set_recent_files(recent);
+                self.remember_recent(&target);
+                return self.update_title();
+            }
+            Message::RenameFinished(None) => {}
+            Message::DismissRenameWarnings => self.doc_mut().rename_warnings.clear(),
+            Message::DismissSaveWarnings => self.doc_mut().save_warnings.clear(),
+            Message::AddToAutostart => {
+                let doc = self.doc();
+                let (Some(entry), Some(target)) = (
+                    &doc.current_entry,
+                    doc.current_entry_path
+                        .as_deref()
+                        .and_then(xdghelp::user_autostart_path),
+                ) else {
+                    return Task::none();
+                };
+                // The copy gets the edits as shown, saved or not
+                let result = target
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|()| std::fs::write(&target, entry.to_string()));
+                if let Err(e) = result {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                        e.to_string(),
+                    )));
+                }
+                info!("Added {} to autostart", target.display());
+                self.doc_mut().refresh_autostart();
+            }
+            Message::SaveAsAdmin => {
+                if let Some(path) = self.doc().current_entry_path.clone() {
+                    return self.update(Message::CreateDialog(DialogKind::SaveAsAdmin(path)));
+                }
+            }
+            Message::AdminSaveFinished(id, path, Ok(())) => {
+                info!("Saved {} as administrator", path.display());
+                // The written contents are only known to the elevated helper
+                if let Ok(contents) = std::fs::read_to_string(&path)
+                    && let Err(e) = history::record(&path, &contents, self.config.history_count)
+                {
+                    log::warn!("Could not record a version of {}: {e}", path.display());
+                }
+                return self.in_window(id, |app| {
+                    let doc = app.doc_mut();
+                    if doc.current_entry_path.as_ref() == Some(&path) {
+                        doc.current_entry_changed = false;
+                        doc.refresh_disk_mtime();
+                    }
+                    Task::none()
+                });
+            }
+            Message::AdminSaveFinished(_, _, Err(e)) => {
+                log::error!("Saving as administrator failed: {e}");
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+            }
+            Message::Revert => {
+                let doc = self.doc();
+                let Some(path) = doc.current_entry_path.clone() else {
+                    return Task::none();
+                };
+                // Nothing would be lost, no need to ask
+                if !doc.current_entry_changed {
+                    return self.update(Message::ReloadFromDisk);
+                }
+                return self.update(Message::CreateDialog(DialogKind::Revert(path)));
+            }
+            Message::DeleteEntry => {
+                if let Some(path) = self.doc().current_entry_path.clone() {
+                    return self.update(Message::CreateDialog(DialogKind::DeleteEntry(path)));
+                }
+            }
+            Message::Trashed(id, path, Ok(())) => {
+                info!("Moved {} to the trash", path.display());
+                return self.in_window(id, |app| {
+                    let doc = app.doc_mut();
+                    doc.clear();
+                    doc.trashed = Some(path);
+                    Task::none()
+                });
+            }
+            Message::Trashed(_, _, Err(e)) => {
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+            }
+            Message::UndoTrash => {
+                if let Some(path) = self.doc_mut().trashed.take() {
+                    match trash::restore(&path) {
+                        Ok(()) => self.load_entry_from_path(&path),
+                        Err(e) => {
+                            return self
+                                .update(Message::ToggleContextPage(ContextPage::IOError(e)));
+                        }
+                    }
+                }
+            }
+            Message::SetBackupCount(count) => {
+                set_config!(self, set_backup_count, backup_count, count);
+            }
+            Message::SetSaveFolder(folder) => {
+                set_config!(self, set_save_folder, save_folder, PathBuf::from(folder));
+            }
+            Message::SetHistoryCount(count) => {
+                set_config!(self, set_history_count, history_count, count);
+            }
+            Message::ShowHistory => {
+                self.history = self
+                    .doc()
+                    .current_entry_path
+                    .as_deref()
+                    .map(history::versions)
+                    .unwrap_or_default();
+                self.history_selected = None;
+                self.history_text.clear();
+                if self.context_page != ContextPage::History || !self.core.window.show_context {
+                    return self.update(Message::ToggleContextPage(ContextPage::History));
+                }
+            }
+            Message::ShowSystemd => {
+                self.systemd_unit = self
+                    .doc()
+                    .current_entry_path
+                    .as_deref()
+                    .and_then(systemd::unit_path)
+                    .and_then(|unit| systemd::read_unit(&unit));
+                if self.context_page != ContextPage::Systemd || !self.core.window.show_context {
+                    return self.update(Message::ToggleContextPage(ContextPage::Systemd));
+                }
+            }
+            Message::ConvertToUnit => {
+                let doc = self.doc();
+                let (Some(entry), Some(path)) = (&doc.current_entry, &doc.current_entry_path)
+                else {
+                    return Task::none();
+                };
+                let Some(unit) = systemd::unit_path(path) else {
+                    return Task::none();
+                };
+                let written = systemd::unit_text(entry, path, &self.locales)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| {
+                        let enabled = systemd::autostart_enabled(entry);
+                        systemd::write_unit(&unit, &text, enabled).map_err(|e| e.to_string())
+                    });
+                if let Err(e) = written {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+                }
+                info!("Converted to {}", unit.display());
+                self.systemd_unit = systemd::read_unit(&unit);
+                // The unit starts the program now, the entry would start it a second time
+                self.doc_mut()
+                    .set_other_key("Desktop Entry", "Hidden", Some("true"));
+                return self.update(Message::Save);
+            }
+            Message::ConvertFromUnit => {
+                let Some(unit_path) = self
+                    .doc()
+                    .current_entry_path
+                    .as_deref()
+                    .and_then(systemd::unit_path)
+                else {
+                    return Task::none();
+                };
+                let Some(unit) = systemd::read_unit(&unit_path) else {
+                    return Task::none();
+                };
+                let delay = (unit.delay > 0).then(|| unit.delay.to_string());
+                let working_directory = unit.working_directory.as_deref().map(exec::escape_value);
+                let doc = self.doc_mut();
+                doc.set_text(DesktopKey::Exec, unit.exec());
+                let unnamed = doc
+                    .current_entry
+                    .as_ref()
+                    .is_some_and(|e| e.desktop_entry("Name").is_none_or(str::is_empty));
+                if unnamed && let Some(description) = &unit.description {
+                    doc.set_text(DesktopKey::Name, description.clone());
+                }
+                let keys = [
+                    ("Path", working_directory.as_deref()),
+                    ("X-GNOME-Autostart-Delay", delay.as_deref()),
+                    // A disabled unit comes back as a hidden entry
+                    ("Hidden", (!unit.enabled).then_some("true")),
+                    ("X-GNOME-Autostart-enabled", None),
+                ];
+                for (key, value) in keys {
+                    doc.set_other_key("Desktop Entry", key, value);
+                }
+                if let Err(e) = systemd::remove_unit(&unit_path) {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                        e.to_string(),
+                    )));
+                }
+                info!("Converted {} back", unit_path.display());
+                self.systemd_unit = None;
+                return self.update(Message::Save);
+            }
+            Message::ShowOpenTest => {
+                if self.context_page != ContextPage::OpenTest || !self.core.window.show_context {
+                    return self.update(Message::ToggleContextPage(ContextPage::OpenTest));
+                }
+            }
+            // mimeapps.list may have been edited since
+            Message::RerunOpenTest => {
+                if let Some(test) = &self.open_test {
+                    return Self::run_open_test(test.file.clone(), test.mime.clone());
+                }
+            }
+            Message::OpenTestResolved(test) => self.open_test = Some(test),
+            Message::SelectVersion(index) => {
+                let Some(version) = self.history.get(index) else {
+                    return Task::none();
+                };
+                match std::fs::read_to_string(&version.file) {
+                    Ok(text) => {
+                        self.history_selected = Some(index);
+                        self.history_text = text;
+                    }
+                    Err(e) => {
+                        return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                            e.to_string(),
+                        )));
+                    }
+                }
+            }
+            Message::RestoreVersion => {
+                let Some(path) = self.doc().current_entry_path.clone() else {
+                    return Task::none();
+                };
+                match DesktopEntry::from_str(&path, &self.history_text, None::<&[&str]>) {
+                    Ok(entry) => {
+                        // Like a restored backup, the version is written back on save
+                        let (doc, locales, mimes) = self.doc_with_caches();
+                        doc.current_entry = Some(entry);
+                        doc.rebuild_tables(locales, mimes);
+                        doc.changed();
+                        Self::create_nav_bar(doc);
+                    }
+                    Err(e) => {
+                        return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                            e.to_string(),
+                        )));
+                    }
+                }
+            }
+            Message::ListInput(key, text) => {
+                self.doc_mut().list_input.insert(key.to_string(), text);
+            }
+            Message::AddListItem(key) => {
+                let doc = self.doc_mut();
+                let input = doc.list_input.remove(&*key.key_str()).unwrap_or_default();
+                let mut items = doc.list(&key);
+                // Pasting "a;b;c" adds all three
+                for item in input.split(';').map(str::trim).filter(|i| !i.is_empty()) {
+                    if !items.iter().any(|i| i == item) {
+                        items.push(item.to_string());
+                    }
+                }
+                doc.set_list_items(key, &items);
+            }
+            Message::RemoveListItem(key, index) => {
+                let doc = self.doc_mut();
+                let mut items = doc.list(&key);
+                if index < items.len() {
+                    items.remove(index);
+                    doc.set_list_items(key, &items);
+                }
+            }
+            Message::ListDragStart(key, index) => {
+                self.doc_mut().list_drag = Some((key.to_string(), index));
+            }
+            Message::ListDragOver(key, index) => {
+                let doc = self.doc_mut();
+                if let Some((dragged, from)) = &doc.list_drag
+                    && *dragged == key.key_str()
+                    && *from != index
+                {
+                    let from = *from;
+                    let mut items = doc.list(&key);
+                    if from < items.len() && index < items.len() {
+                        let item = items.remove(from);
+                        items.insert(index, item);
+                        doc.list_drag = Some((key.to_string(), index));
+                        doc.set_list_items(key, &items);
+                    }
+                }
+            }
+            Message::ListDragEnd => {
+                for doc in self.documents_mut() {
+                    doc.list_drag = None;
+                }
+            }
+            Message::OpenMimeAppsEditor => {
+                if let Some((id, _)) = &self.mimeapps_editor {
+                    return window::gain_focus(*id);
+                }
+                return Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(MimeAppsEditor::open)
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|r| r.map_err(|e| e.to_string()))
+                    },
+                    |r| cosmic::Action::App(Message::MimeAppsLoaded(r)),
+                );
+            }
+            Message::MimeAppsLoaded(Ok(editor)) => {
+                // Asked for twice while loading
+                if let Some((id, _)) = &self.mimeapps_editor {
+                    return window::gain_focus(*id);
+                }
+                return self.open_mimeapps_editor(editor);
+            }
+            Message::MimeAppsLoaded(Err(e)) => {
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+            }
+            Message::MimeAppsSetApps(section, row, apps) => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    editor.set_apps(section, row, apps);
+                }
+            }
+            Message::MimeAppsPick(section, row, choice) => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    let app = editor.file.sections[section]
+                        .get(row)
+                        .and_then(|(mime, _)| editor.handlers.get(mime.as_str()))
+                        .and_then(|apps| apps.get(choice))
+                        .cloned();
+                    if let Some(app) = app {
+                        editor.pick(section, row, &app);
+                    }
+                }
+            }
+            Message::MimeAppsRemove(section, row) => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    editor.remove(section, row);
+                }
+            }
+            Message::MimeAppsNewMime(section, mime) => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    editor.new_mime[section] = mime;
+                }
+            }
+            Message::MimeAppsAdd(section) => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    editor.add(section);
+                }
+            }
+            Message::MimeAppsSave => {
+                if let Some((_, editor)) = &mut self.mimeapps_editor {
+                    editor.save();
+                    if editor.error.is_none() {
+                        // The default application column reflects the new file
+                        self.mime_descriptions.reload_handlers();
+                        let (locales, mimes) = (&self.locales, &self.mime_descriptions);
+                        for doc in
+                            std::iter::once(&mut self.main_doc).chain(self.windows.values_mut())
+                        {
+                            doc.rebuild_tables(locales, mimes);
+                        }
+                    }
+                }
+            }
+            Message::SetVendorKey(key, value) => {
+                self.doc_mut()
+                    .set_other_key("Desktop Entry", key, value.as_deref());
+            }
+            Message::OpenMenuEditor => {
+                if let Some((id, _)) = &self.menu_editor {
+                    return window::gain_focus(*id);
+                }
+                return self.open_menu_editor();
+            }
+            Message::MenuEditorSelect(at) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.selected = at;
+                }
+            }
+            Message::MenuEditorDirectory(directory) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.set_directory(directory);
+                }
+            }
+            Message::MenuEditorUseDirectory => {
+                // A .directory file's ID is its file name
+                let id = self
+                    .doc()
+                    .current_entry_path
+                    .as_deref()
+                    .filter(|p| p.extension().is_some_and(|e| e == "directory"))
+                    .and_then(Path::file_name)
+                    .map(|n| n.to_string_lossy().into_owned());
+                if let Some((_, editor)) = &mut self.menu_editor
+                    && let Some(id) = id
+                {
+                    editor.set_directory(id);
+                }
+            }
+            Message::MenuEditorHidden(hidden) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.set_hidden(hidden);
+                }
+            }
+            Message::MenuEditorRuleInput(text) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.rule_input = text;
+                }
+            }
+            Message::MenuEditorAddRule(include) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.add_rule(include);
+                }
+            }
+            Message::MenuEditorRemoveRule(include, index) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.remove_rule(include, index);
+                }
+            }
+            Message::MenuEditorSubmenuInput(text) => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.submenu_input = text;
+                }
+            }
+            Message::MenuEditorAddSubmenu => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.add_submenu();
+                }
+            }
+            Message::MenuEditorSave => {
+                if let Some((_, editor)) = &mut self.menu_editor {
+                    editor.save();
+                }
+            }
+            Message::QuickOpen => {
+                let locales = self.locales.clone();
+                return Task::batch(vec![
+                    self.update(Message::CreateDialog(DialogKind::QuickOpen(String::new()))),
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || quickopen::scan(&locales))
+                                .await
+                                .unwrap_or_default()
+                        },
+                        |l| cosmic::Action::App(Message::LaunchersScanned(l)),
+                    ),
+                ]);
+            }
+            Message::LaunchersScanned(launchers) => self.launchers = launchers,
+            Message::QuickOpenSelect(path) => {
+                self.load_entry_from_path(&path);
+                return self.update(Message::DestroyDialog);
+            }
+
+            Message::ListRunningApps => {
+                self.running_apps = None;
+                self.wm_probe = None;
+                self.context_page = ContextPage::RunningApps;
+                self.core.window.show_context = true;
+                return Task::perform(
+                    async {
+                        tokio::task::spawn_blocking(toplevels::running_apps)
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()))
+                    },
+                    |r| cosmic::Action::App(Message::RunningAppsListed(r)),
+                );
+            }
+            Message::RunningAppsListed(apps) => self.running_apps = Some(apps),
+            Message::PickRunningApp(app_id) => {
+                self.doc_mut().set_text(DesktopKey::StartupWMClass, app_id);
+                self.core.window.show_context = false;
+            }
+            Message::ProbeWMClass => {
+                // Start the app so there's a window to click, an already running one works too
+                let started = self
+                    .prepare_test_launch()
+                    .and_then(|(args, dir)| exec::spawn(&args, dir));
+                if let Err(e) = started {
+                    info!("Not launching before WM_CLASS probe: {e}");
+                }
+                self.wm_probe = Some(None);
+                return Task::perform(exec::probe_wm_class(), |r| {
+                    cosmic::Action::App(Message::WMClassProbed(r))
+                });
+            }
+            Message::WMClassProbed(result) => match result {
+                Ok(class) => {
+                    self.wm_probe = None;
+                    return self.update(Message::PickRunningApp(class));
+                }
+                Err(e) => self.wm_probe = Some(Some(e)),
+            },
+            Message::TestLaunch => {
+                let prepared = self.prepare_test_launch();
+                self.context_page = ContextPage::TestLaunch;
+                self.core.window.show_context = true;
+                match prepared {
+                    Ok((args, dir)) => {
+                        self.test_launch = TestLaunch::Running(exec::join(&args));
+                        return Task::perform(exec::run(args, dir), |r| {
+                            cosmic::Action::App(Message::TestLaunchFinished(r))
+                        });
+                    }
+                    Err(e) => self.test_launch = TestLaunch::Finished(String::new(), Err(e)),
+                }
+            }
+            Message::TestLaunchFinished(result) => {
+                if let TestLaunch::Running(command) = std::mem::take(&mut self.test_launch) {
+                    self.test_launch = TestLaunch::Finished(command, result);
+                }
+            }
+            Message::OpenTerminalAtPath => {
+                let opened = self
+                    .doc()
+                    .working_dir()
+                    .ok_or_else(|| fl!("terminal-nopath"))
+                    .and_then(|dir| {
+                        let shell = exec::terminal_shell(&self.config.terminal)
+                            .ok_or_else(|| fl!("testlaunch-noterminal"))?;
+                        exec::spawn(&shell, Some(dir))
+                    });
+                if let Err(e) = opened {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(e)));
+                }
+            }
+            Message::ToggleExecHelp => self.exec_help = !self.exec_help,
+            Message::SetWrapper(index, on) => {
+                let exec = self
+                    .doc()
+                    .current_entry
+                    .as_ref()
+                    .and_then(DesktopEntry::exec)
+                    .unwrap_or_default()
+                    .to_string();
+                if let Some(wrapper) = WRAPPERS.get(index)
+                    && let Ok(exec) = wrappers::set_wrapped(&exec, wrapper, on)
+                {
+                    self.doc_mut().set_text(DesktopKey::Exec, exec);
+                }
+            }
+            Message::GpusDetected(gpus) => self.gpus = gpus,
+            Message::ToggleHelp(key) => {
+                let key = key.to_string();
+                self.help_key = (self.help_key.as_ref() != Some(&key)).then_some(key);
+            }
+            Message::OpenExecBuilder => {
+                let exec = self
+                    .doc()
+                    .current_entry
+                    .as_ref()
+                    .and_then(DesktopEntry::exec)
+                    .unwrap_or_default()
+                    .to_string();
+                self.exec_builder = ExecBuilder::from_exec(&exec);
+                self.context_page = ContextPage::ExecBuilder;
+                self.core.window.show_context = true;
+            }
+            Message::ExecBuilderProgram(program) => self.exec_builder.program = program,
+            Message::ExecBuilderArg(index, arg) => self.exec_builder.set_arg(index, arg),
+            Message::ExecBuilderAddArg => self.exec_builder.args.push(String::new()),
+            Message::ExecBuilderRemoveArg(index) => self.exec_builder.remove_arg(index),
+            // Choice 0 is "no files"
+            Message::ExecBuilderFileCode(choice) => {
+                self.exec_builder.file_code = choice.checked_sub(1);
+            }
+            Message::ApplyExecBuilder => {
+                let exec = self.exec_builder.to_exec();
+                self.doc_mut().set_text(DesktopKey::Exec, exec);
+                self.exec_builder.unparsed = None;
+            }
+            Message::SetShowIn(key, desktop, on) => {
+                let doc = self.doc_mut();
+                let current = doc.list(&key);
+                let current: Vec<&str> = current.iter().map(String::as_str).collect();
+                doc.set_list_items(key, &categories::toggled(&current, &desktop, on));
+            }
+            Message::SetCategory(category, on) => {
+                let doc = self.doc_mut();
+                let list = doc.current_entry.as_ref().map(|e| {
+                    categories::toggled(&e.categories().unwrap_or_default(), &category, on)
+                });
+                if let Some(list) = list {
+                    doc.set_list(DesktopKey::Categories, &list);
+                }
+            }
+            Message::AddTranslation(key, locale) => {
+                let doc = self.doc_mut();
+                if !doc.translations(&key).iter().any(|(l, _)| *l == locale) {
+                    doc.set_translation(&key, &locale, Some(""));
+                }
+            }
+            Message::SetTranslation(key, locale, value) => {
+                self.doc_mut().set_translation(&key, &locale, Some(&value));
+            }
+            Message::RemoveTranslation(key, locale) => {
+                self.doc_mut().set_translation(&key, &locale, None);
+            }
+            Message::PreviewLocale(locale) => self.doc_mut().preview_locale = locale,
+            Message::SetOtherKey(group, key, value) => {
+                self.doc_mut().set_other_key(&group, &key, Some(&value));
+            }
+            Message::RemoveOtherKey(group, key) => {
+                self.doc_mut().set_other_key(&group, &key, None);
+            }
+            Message::MigrateDeprecated => self.doc_mut().migrate_deprecated(),
+            Message::FixQuoting => self.doc_mut().fix_quoting(),
+            // Choice 0 is automatic
+            Message::SetTerminal(choice) => {
+                let terminal = match choice {
+                    0 => String::new(),
+                    _ => self
+                        .terminal_choices
+                        .get(choice)
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                set_config!(self, set_terminal, terminal, terminal);
+            }
+            Message::SetDeterministicOutput(on) => {
+                set_config!(self, set_deterministic_output, deterministic_output, on);
+            }
+            Message::SetNormalizeOnSave(on) => {
+                set_config!(self, set_normalize_on_save, normalize_on_save, on);
+            }
+            Message::SetNormalizeStep(step, on) => {
+                let steps = if on {
+                    self.config.normalize_steps | step
+                } else {
+                    self.config.normalize_steps & !step
+                };
+                set_config!(self, set_normalize_steps, normalize_steps, steps);
+            }
+            Message::SaveNormalized => {
+                self.normalize_next_save = true;
+                let doc = self.doc();
+                let regenerated = matches!(doc.packaged, Some(Packaged::Snap(_)));
+                // Unchanged files are written too, normalizing is the change
+                if !doc.read_only
+                    && !regenerated
+                    && let Some(entry) = &doc.current_entry
+                    && entry.path.is_file()
+                {
+                    return self.save_finished(Some(entry.path.clone()));
+                }
+                self.doc_mut().changed();
+                return self.update(Message::Save);
+            }
+            Message::SetChmodOnSave(on) => {
+                set_config!(self, set_chmod_on_save, chmod_on_save, on);
+            }
+            Message::SetChmodBits(bits, on) => {
+                let bits = if on {
+                    self.config.chmod_bits | bits
+                } else {
+                    self.config.chmod_bits & !bits
+                };
+                set_config!(self, set_chmod_bits, chmod_bits, bits);
+            }
+            Message::ReviewChanges => {
+                // Text still being typed is part of what Save writes
+                self.doc_mut().finish_edits();
+                let doc = self.doc();
+                // The drawer compares against the file as it is now
+                let disk_text = doc
+                    .current_entry_path
+                    .as_ref()
+                    .and_then(|p| std::fs::read_to_string(p).ok())
+                    .unwrap_or_default();
+                let new_text = self
+                    .text_to_write(doc, self.normalize_next_save)
+                    .map(|(_, text)| text)
+                    .unwrap_or_default();
+                self.review_lines = line_diff(&disk_text, &new_text);
+                if self.context_page != ContextPage::ReviewChanges || !self.core.window.show_context
+                {
+                    return self.update(Message::ToggleContextPage(ContextPage::ReviewChanges));
+                }
+            }
+            Message::SaveOverride => {
+                let Some(target) = self
+                    .doc()
+                    .current_entry_path
+                    .as_deref()
+                    .and_then(user_override_path)
+                else {
+                    return Task::none();
+                };
+                // Replacing an earlier override would lose it and its merge base
+                if target.exists() {
+                    return self.update(Message::CreateDialog(DialogKind::OverrideExists(target)));
+                }
+                return self.save_override(target);
+            }
+            Message::OpenOverride(path) => {
+                self.load_entry_from_path(&path);
+                return self.update(Message::DestroyDialog);
+            }
+            Message::Save => {
+                // Text still being typed counts as a change to save
+                self.doc_mut().finish_edits();
+                let doc = self.doc();
+                // snapd regenerates its launchers, edits only last in an override
+                let regenerated = matches!(doc.packaged, Some(Packaged::Snap(_)));
+                if (doc.read_only || regenerated) && doc.current_entry.is_some() {
+                    return self.update(Message::SaveOverride);
+                }
+                if doc.current_entry_changed
+                    && let Some(entry) = &doc.current_entry
+                {
+                    if doc.current_entry_path.is_none() {
+                        return self.update(Message::SaveAs);
+                    } else if entry.path.is_file() {
+                        return self.save_finished(Some(entry.path.clone()));
+                    }
+                }
+            }
+            Message::OpenPath(kind) => {
+                // Editing a .directory file, the next one opened likely is one too
+                let kind = if kind == PickKind::DesktopFile
+                    && self.doc().entry_type() == Some(DesktopEntryType::Directory)
+                {
+                    PickKind::DirectoryEntry
+                } else {
+                    kind
+                };
+                return Task::perform(open_path(kind), |f| {
+                    cosmic::Action::App(Message::OpenFileFinished(f))
+                });
+            }
+            Message::OpenRecent(i) => {
+                if let Some(path) = self.config.recent_files.get(i).cloned() {
+                    self.load_entry_from_path(&path);
+                }
+            }
+            Message::ClearRecent => {
+                self.set_recent_files(Vec::new());
+            }
+            Message::Key(modifiers, key) => {
+                for (key_bind, action) in &self.key_binds {
+                    if key_bind.matches(modifiers, &key) {
+                        return self.update(action.message());
+                    }
+                }
+            }
+            Message::OpenFileFinished(path) => {
+                if let (Some(desktop_file), kind) = path {
+                    match kind {
+                        // Load file
+                        PickKind::DesktopFile | PickKind::DirectoryEntry => {
+                            self.load_entry_from_path(&desktop_file);
+                        }
+                        // Save Exec or Path in current desktop entry
+                        PickKind::Executable | PickKind::TryExecutable => {
+                            self.doc_mut().set_program(&desktop_file, kind);
+                        }
+                        PickKind::Directory => {
+                            self.doc_mut().set_path(&desktop_file);
+                        }
+                        PickKind::SaveFolder => {
+                            set_config!(self, set_save_folder, save_folder, desktop_file);
+                        }
+                        PickKind::IconFile => {
+                            self.doc_mut()
+                                .set_text(DesktopKey::Icon, desktop_file.to_string_lossy());
+                        }
+                        // First file of a comparison picked, ask for the second
+                        PickKind::CompareLeft => {
+                            self.compare_left = Some(desktop_file);
+                            return self.update(Message::OpenPath(PickKind::CompareRight));
+                        }
+                        PickKind::CompareRight => {
+                            if let Some(left) = self.compare_left.take() {
+                                return self.open_comparison(left, desktop_file);
                             }
                         }
-                        DialogKind::NewXkey(data) => {
-                            if !data.name.is_empty() {
-                                self.create_xkey(&data.clone());
+                        PickKind::SampleFile => {
+                            let mime = self.mime_descriptions.guess(&desktop_file);
+                            return Self::run_open_test(desktop_file, mime);
+                        }
+                        PickKind::UrlShortcut | PickKind::JsonFile => {
+                            let imported = if kind == PickKind::JsonFile {
+                                import::json_entry(&desktop_file)
+                            } else {
+                                import::url_shortcut(&desktop_file)
+                            };
+                            match imported {
+                                Ok(entry) => {
+                                    let (doc, locales, mimes) = self.doc_with_caches();
+                                    doc.clear();
+                                    doc.current_entry = Some(entry);
+                                    doc.rebuild_tables(locales, mimes);
+                                    doc.changed();
+                                    Self::create_nav_bar(doc);
+                                }
+                                Err(e) => {
+                                    return self.update(Message::ToggleContextPage(
+                                        ContextPage::IOError(e),
+                                    ));
+                                }
                             }
                         }
                     }
                 }
-                return self.update(Message::DestroyDialog);
             }
 
-            Message::CreateEntry(new_kind) => {
-                self.clear_all();
-                let name = match new_kind {
-                    DesktopEntryType::Application => fl!("my-application"),
-                    DesktopEntryType::Link => fl!("my-link"),
-                    DesktopEntryType::Directory => fl!("my-directory"),
-                };
-                self.current_entry = Some(DesktopEntry::from_appid(name));
-                self.set_text(DesktopKey::Type, new_kind.to_string());
-                self.create_nav_bar();
-            }
+            Message::SetTextEntry(key, text) => {
+                let (doc, locales, _) = self.doc_with_caches();
+                // Fields being edited only write to the entry once committed
+                if doc.staged_text(&key).is_some() {
+                    doc.stage(&key, text);
+                } else {
+                    doc.set_field_text(key, text, locales);
+                }
+            }
+
+            Message::SetBoolEntry(key, boolean) => match boolean {
+                Some(value) => self.doc_mut().set_bool(key, value),
+                None => self.doc_mut().remove_key(&key),
+            },
+
+            Message::OpenRepositoryUrl => {
+                _ = open::that_detached(REPOSITORY);
+            }
+            Message::MimeItemSelect(entity) => self.doc_mut().mime_table.activate(entity),
+            Message::SortMimetypes(category) => self.doc_mut().sort_mimetypes(category),
+            Message::CleanMimetypes => {
+                let (doc, _, mimes) = self.doc_with_caches();
+                doc.clean_mimetypes(mimes);
+            }
+            Message::RemoveMimetype(pos) => {
+                let doc = self.doc_mut();
+                // By name, the table may only show the filtered rows
+                let name = doc
+                    .mime_table
+                    .entity_at(pos as u16)
+                    .and_then(|entity| doc.mime_table.item(entity))
+                    .map(|item| item.name.clone());
+                if let Some(name) = name {
+                    doc.remove_mimetype(&name);
+                }
+            }
+
+            Message::SetMimeGrouped(grouped) => self.doc_mut().mime_grouped = grouped,
+            Message::ToggleMimeGroup(group) => {
+                let collapsed = &mut self.doc_mut().mime_collapsed;
+                if !collapsed.remove(&group) {
+                    collapsed.insert(group);
+                }
+            }
+            Message::RemoveMimeGroup(group) => {
+                let (doc, _, mimes) = self.doc_with_caches();
+                doc.remove_mime_group(&group, mimes);
+            }
+            Message::RemoveMimetypeNamed(name) => self.doc_mut().remove_mimetype(&name),
+            Message::MimeFilter(filter) => {
+                let (doc, _, mimes) = self.doc_with_caches();
+                doc.mime_filter = filter;
+                doc.rebuild_mime_table(mimes);
+            }
+
+            Message::XkeyItemSelect(entity) => self.doc_mut().xkey_table.activate(entity),
+            Message::RemoveXkey(pos) => {
+                let doc = self.doc_mut();
+                if let Some(entity) = doc.xkey_table.entity_at(pos as u16)
+                    && let (Some(entry), Some(item)) =
+                        (&mut doc.current_entry, doc.xkey_table.item(entity))
+                {
+                    let _ = remove_x_key(entry, "Desktop Entry", &item.name);
+                    doc.current_entry_changed = true;
+                    // Update table model
+                    doc.xkey_table.remove(entity);
+                }
+            }
+
+            Message::DialogEdit(edit) => {
+                if let Some(dialog_data) = &mut self.dialog_data {
+                    match (&mut dialog_data.kind, &edit) {
+                        (DialogKind::NewMimetype(data), DialogKind::NewMimetype(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        (DialogKind::BulkMimetypes(data), DialogKind::BulkMimetypes(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        (DialogKind::NewXkey(data), DialogKind::NewXkey(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        (DialogKind::QuickOpen(data), DialogKind::QuickOpen(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        (DialogKind::SaveTemplate(data), DialogKind::SaveTemplate(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        (DialogKind::NewWebApp(data), DialogKind::NewWebApp(edit)) => {
+                            data.clone_from(edit);
+                        }
+                        _ => todo!(),
+                    }
+                }
+            }
+
+            Message::DialogClose(create) => {
+                if let Some(dialog_data) = &self.dialog_data
+                    && matches!(dialog_data.kind, DialogKind::RegisterHandler)
+                {
+                    set_config!(self, set_handler_prompted, handler_prompted, true);
+                }
+                if !create
+                    && let Some(dialog_data) = &self.dialog_data
+                    && matches!(dialog_data.kind, DialogKind::IdConflict(..))
+                {
+                    self.normalize_next_save = false;
+                }
+                if create && let Some(dialog_data) = &self.dialog_data {
+                    match &dialog_data.kind {
+                        DialogKind::NewMimetype(data) => {
+                            let mimetype = rm_whitespace!(data);
+                            if is_valid_mimetype(&mimetype) {
+                                let (doc, _, mimes) = self.doc_with_caches();
+                                doc.create_mimetype(&mimetype, mimes);
+                            }
+                        }
+                        DialogKind::BulkMimetypes(data) => {
+                            let add = self.doc().bulk_mimetypes(data).add;
+                            let (doc, _, mimes) = self.doc_with_caches();
+                            doc.add_mimetypes(&add, mimes);
+                        }
+                        DialogKind::NewXkey(data) => {
+                            if !data.name.is_empty() {
+                                let data = data.clone();
+                                self.doc_mut().create_xkey(&data);
+                            }
+                        }
+                        DialogKind::DroppedIcon(path) => match install_icon(path) {
+                            Ok((name, installed)) => {
+                                self.icon_cache.add_paths(&[installed]);
+                                self.doc_mut().set_text(DesktopKey::Icon, name);
+                            }
+                            Err(e) => {
+                                return Task::batch(vec![
+                                    self.update(Message::DestroyDialog),
+                                    self.update(Message::ToggleContextPage(ContextPage::IOError(
+                                        e.to_string(),
+                                    ))),
+                                ]);
+                            }
+                        },
+                        DialogKind::QuickOpen(query) => {
+                            // Enter opens the best match
+                            if let Some(best) = quickopen::search(&self.launchers, query).first() {
+                                let path = best.path.clone();
+                                return self.update(Message::QuickOpenSelect(path));
+                            }
+                        }
+                        DialogKind::DeleteEntry(path) => {
+                            let path = path.clone();
+                            let Some(id) = self.document_window() else {
+                                return self.update(Message::DestroyDialog);
+                            };
+                            return Task::batch(vec![
+                                self.update(Message::DestroyDialog),
+                                Task::perform(trash::trash(path.clone()), move |r| {
+                                    cosmic::Action::App(Message::Trashed(id, path.clone(), r))
+                                }),
+                            ]);
+                        }
+                        DialogKind::SaveTemplate(name) => {
+                            let name = name.trim().to_string();
+                            let saved = self
+                                .doc()
+                                .current_entry
+                                .as_ref()
+                                .filter(|_| !name.is_empty())
+                                .map(|entry| templates::save(&name, entry));
+                            let Some(saved) = saved else {
+                                return self.update(Message::DestroyDialog);
+                            };
+                            self.templates = templates::list();
+                            if let Err(e) = saved {
+                                return Task::batch(vec![
+                                    self.update(Message::DestroyDialog),
+                                    self.update(Message::ToggleContextPage(ContextPage::IOError(
+                                        e.to_string(),
+                                    ))),
+                                ]);
+                            }
+                        }
+                        DialogKind::NewWebApp(url) => {
+                            let url = webapp::normalize_url(url);
+                            if let Some(browser) = self.web_browsers.get(self.web_browser_choice) {
+                                let exec = browser.exec_for(&url);
+                                let name = webapp::placeholder_name(&url);
+                                let doc = self.doc_mut();
+                                doc.clear();
+                                doc.current_entry = Some(DesktopEntry::from_appid(name.clone()));
+                                doc.set_text(
+                                    DesktopKey::Type,
+                                    DesktopEntryType::Application.to_string(),
+                                );
+                                doc.set_text(DesktopKey::Name, name.clone());
+                                doc.set_text(DesktopKey::Exec, exec);
+                                doc.set_text(DesktopKey::Icon, "web-browser");
+                                doc.set_text(DesktopKey::Categories, "Network;");
+                                Self::create_nav_bar(doc);
+                                return Task::batch(vec![
+                                    self.update(Message::DestroyDialog),
+                                    Task::perform(webapp::fetch_site_name(url), move |site| {
+                                        cosmic::Action::App(Message::WebAppNamed(
+                                            name.clone(),
+                                            site,
+                                        ))
+                                    }),
+                                ]);
+                            }
+                        }
+                        DialogKind::SaveAsAdmin(path) => {
+                            let path = path.clone();
+                            let Some(id) = self.document_window() else {
+                                return self.update(Message::DestroyDialog);
+                            };
+                            let contents = self.save_contents(false);
+                            return Task::batch(vec![
+                                self.update(Message::DestroyDialog),
+                                Task::perform(
+                                    elevate::write_file(path.clone(), contents),
+                                    move |r| {
+                                        cosmic::Action::App(Message::AdminSaveFinished(
+                                            id,
+                                            path.clone(),
+                                            r,
+                                        ))
+                                    },
+                                ),
+                            ]);
+                        }
+                        DialogKind::IdConflict(path, _) => {
+                            let path = path.clone();
+                            return Task::batch(vec![
+                                self.update(Message::DestroyDialog),
+                                self.save_finished(Some(path)),
+                            ]);
+                        }
+                        DialogKind::OverrideExists(path) => {
+                            let path = path.clone();
+                            return Task::batch(vec![
+                                self.update(Message::DestroyDialog),
+                                self.save_override(path),
+                            ]);
+                        }
+                        DialogKind::Revert(_) => {
+                            return Task::batch(vec![
+                                self.update(Message::DestroyDialog),
+                                self.update(Message::ReloadFromDisk),
+                            ]);
+                        }
+                        DialogKind::RegisterHandler => {
+                            return Task::batch(vec![
+                                self.update(Message::RegisterHandler),
+                                self.update(Message::DestroyDialog),
+                            ]);
+                        }
+                    }
+                }
+                return self.update(Message::DestroyDialog);
+            }
+
+            Message::UseIconPath(path) => {
+                self.doc_mut()
+                    .set_text(DesktopKey::Icon, path.to_string_lossy());
+                return self.update(Message::DestroyDialog);
+            }
+
+            Message::RegisterHandler => {
+                let id = format!("{}.desktop", Self::APP_ID);
+                if let Err(e) = mimeapps::set_user_default(DESKTOP_MIME, &id) {
+                    log::error!("Failed to register as {DESKTOP_MIME} handler: {e}");
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                        e.to_string(),
+                    )));
+                }
+                self.desktop_handler = Self::is_desktop_handler();
+            }
+
+            Message::CreateEntry(new_kind) => {
+                self.dialog_data = None;
+                let name = match new_kind {
+                    DesktopEntryType::Application => fl!("my-application"),
+                    DesktopEntryType::Link => fl!("my-link"),
+                    DesktopEntryType::Directory => fl!("my-directory"),
+                };
+                let doc = self.doc_mut();
+                doc.clear();
+                doc.current_entry = Some(DesktopEntry::from_appid(name));
+                doc.set_text(DesktopKey::Type, new_kind.to_string());
+                Self::create_nav_bar(doc);
+            }
+
+            Message::NewWindow => {
+                let mut settings = window::Settings {
+                    size: Size::new(1024.0, 768.0),
+                    min_size: Some(Size::new(360.0, 300.0)),
+                    ..Default::default()
+                };
+                settings.platform_specific.application_id = Self::APP_ID.to_string();
+
+                let (id, command) = window::open(settings);
+                let mut doc = Document::default();
+                Self::create_nav_bar(&mut doc);
+                self.windows.insert(id, doc);
+                self.active_window = Some(id);
+
+                return Task::batch(vec![
+                    command.map(|_id| cosmic::Action::None),
+                    self.set_window_title(fl!("app-title"), id),
+                ]);
+            }
+
+            Message::OpenInWindow(path) => {
+                // An untouched main window takes the file, otherwise it gets its own
+                if self.main_doc.current_entry.is_some() {
+                    let task = self.update(Message::NewWindow);
+                    self.load_entry_from_path(&path);
+                    return task;
+                }
+                self.active_window = None;
+                self.load_entry_from_path(&path);
+                if let Some(id) = self.core.main_window_id() {
+                    return window::gain_focus(id);
+                }
+            }
+
+            Message::FileDropped(id, path) => {
+                // Act on the window the file was dropped on
+                if !self.activate_window(id) {
+                    return Task::none();
+                }
+
+                if exec::is_executable(&path) {
+                    let task = self.open_from_cli(Cli {
+                        new: Some(DesktopEntryType::Application),
+                        name: Some(exec::name_from_program(&path)),
+                        exec: Some(exec::quote_literal(&path.display().to_string())),
+                        ..Cli::default()
+                    });
+                    // Scripts usually print to, or ask on, a terminal
+                    if exec::is_shell_script(&path) {
+                        self.doc_mut().set_bool(DesktopKey::Terminal, true);
+                    }
+                    return task;
+                }
+                if is_icon_file(&path) && self.doc().current_entry.is_some() {
+                    return self.update(Message::CreateDialog(DialogKind::DroppedIcon(path)));
+                }
+                info!("Ignoring dropped file {}", path.display());
+            }
+
+            Message::WindowFocused(id) => {
+                // Dialogs belong to the window that opened them, don't switch away
+                self.activate_window(id);
+            }
+
+            Message::NavSelect(id) => {
+                self.doc_mut().nav.activate(id);
+            }
+
+            Message::CheckDiskChanges => {
+                for doc in std::iter::once(&mut self.main_doc).chain(self.windows.values_mut()) {
+                    doc.check_disk();
+                }
+            }
+
+            Message::ReloadFromDisk => {
+                if let Some(path) = self.doc().current_entry_path.clone() {
+                    self.load_entry_from_path(&path);
+                }
+            }
+
+            Message::ResetToSystem(group, key) => {
+                let (doc, locales, mimes) = self.doc_with_caches();
+                doc.reset_to_system(&group, &key);
+                doc.rebuild_tables(locales, mimes);
+            }
+            Message::TakeUpstream => {
+                let (doc, locales, mimes) = self.doc_with_caches();
+                doc.take_upstream();
+                doc.rebuild_tables(locales, mimes);
+            }
+            Message::MarkMerged => {
+                // Upstream changes seen so far no longer show as new
+                let doc = self.doc();
+                let (Some(path), Some(system)) = (&doc.current_entry_path, &doc.system_entry)
+                else {
+                    return Task::none();
+                };
+                let path = path.clone();
+                let result = std::fs::read_to_string(&system.path)
+                    .and_then(|text| merge::record_base(&path, &text));
+                if let Err(e) = result {
+                    return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                        e.to_string(),
+                    )));
+                }
+                self.doc_mut().override_base = merge::load_base(&path);
+            }
+
+            Message::KeepInMemory => {
+                let doc = self.doc_mut();
+                doc.refresh_disk_mtime();
+                // Our version no longer matches the file
+                doc.changed();
+            }
+
+            Message::IconsScanned(paths) => {
+                // Newly resolvable names are picked up by the next view
+                self.icon_cache.add_paths(&paths);
+            }
+
+            Message::IconScanFinished => {
+                self.icons_scanned = true;
+            }
+
+            Message::ToggleContextPage(context_page) => {
+                if self.context_page == context_page {
+                    // Close the context drawer if the toggled context page is the same.
+                    self.core.window.show_context = !self.core.window.show_context;
+                } else {
+                    // Open the context drawer to display the requested context page.
+                    self.context_page = context_page;
+                    self.core.window.show_context = true;
+                }
+            }
+
+            Message::UpdateConfig(config) => {
+                self.config = config;
+            }
+
+            Message::CloseWindow(id) => {
+                if Some(id) == self.core.main_window_id() {
+                    return self.update(Message::Quit);
+                }
+                if self.mimeapps_editor.as_ref().is_some_and(|(w, _)| *w == id) {
+                    self.mimeapps_editor = None;
+                    return window::close(id);
+                }
+                if self.menu_editor.as_ref().is_some_and(|(w, _)| *w == id) {
+                    self.menu_editor = None;
+                    return window::close(id);
+                }
+                if self.windows.remove(&id).is_some() || self.comparisons.remove(&id).is_some() {
+                    if self.active_window == Some(id) {
+                        self.active_window = None;
+                    }
+                    return window::close(id);
+                }
+            }
+
+            Message::ToggleEdit(field) => {
+                let is_exec = matches!(field, DesktopKey::Exec);
+                let (doc, locales, _) = self.doc_with_caches();
+                doc.toggle_edit(&field, locales);
+                if is_exec && self.doc().am_editing.exec && self.path_programs.is_empty() {
+                    return Task::perform(
+                        async {
+                            tokio::task::spawn_blocking(exec::path_programs)
+                                .await
+                                .unwrap_or_default()
+                        },
+                        |p| cosmic::Action::App(Message::PathProgramsScanned(p)),
+                    );
+                }
+            }
+            Message::PathProgramsScanned(programs) => self.path_programs = programs,
+            Message::RevertKey(key) => self.doc_mut().revert_key(&key),
+            Message::RemoveKey(key) => self.doc_mut().remove_key(&key),
+            Message::CancelEdit => self.doc_mut().cancel_edit(),
+            Message::CommitEdit(key) => {
+                let (doc, locales, _) = self.doc_with_caches();
+                if doc.am_editing.is_editing(&key) {
+                    doc.toggle_edit(&key, locales);
+                }
+            }
+            Message::CommitStaged(key) => self.doc_mut().commit_staged(&key),
+            Message::ToggleEditUntranslated => {
+                let doc = self.doc_mut();
+                doc.edit_untranslated = !doc.edit_untranslated;
+            }
+            Message::EditNextField(backwards) => {
+                if self.dialog_data.is_some() {
+                    return Task::none();
+                }
+                let Some(key) = self.doc_mut().finish_for_next(backwards) else {
+                    return Task::none();
+                };
+                let focus = widget::text_input::focus(field_id(&key));
+                if self.doc().am_editing.is_editing(&key) {
+                    return focus;
+                }
+                return Task::batch([self.update(Message::ToggleEdit(key)), focus]);
+            }
+            Message::None => (),
+        }
+        Task::none()
+    }
+
+    // "• file.desktop — Desktop Entry Editor", the dot while there are unsaved changes.
+    fn document_title(doc: &Document) -> String {
+        let Some(entry) = &doc.current_entry else {
+            return fl!("app-title");
+        };
+        let name = doc
+            .current_entry_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or_else(
+                || entry.name::<&str>(&[]).unwrap_or_default().into_owned(),
+                |f| f.to_string_lossy().into_owned(),
+            );
+        let title = fl!("title-document", name = name);
+        if doc.current_entry_changed {
+            fl!("title-modified", title = title)
+        } else {
+            title
+        }
+    }
+
+    // Retitle document windows whose title is out of date.
+    fn sync_titles(&mut self) -> Task<cosmic::Action<Message>> {
+        let mut titles: Vec<(WindowId, String)> = self
+            .windows
+            .iter()
+            .map(|(id, doc)| (*id, Self::document_title(doc)))
+            .collect();
+        if let Some(id) = self.core.main_window_id() {
+            titles.push((id, Self::document_title(&self.main_doc)));
+        }
+
+        self.window_titles
+            .retain(|id, _| titles.iter().any(|(open, _)| open == id));
+        let mut tasks = Vec::new();
+        for (id, title) in titles {
+            if self.window_titles.get(&id) != Some(&title) {
+                self.window_titles.insert(id, title.clone());
+                tasks.push(self.set_window_title(title, id));
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    fn document_view<'a>(&'a self, doc: &'a Document) -> Element<'a, Message> {
+        let mut page = widget::column();
+
+        if doc.read_only && doc.current_entry.is_some() {
+            let mut actions = vec![];
+            if self.can_elevate {
+                actions.push(
+                    widget::button::standard(fl!("action-saveasadmin"))
+                        .on_press(Message::SaveAsAdmin)
+                        .into(),
+                );
+            }
+            actions.push(
+                widget::button::suggested(fl!("action-saveoverride"))
+                    .on_press(Message::SaveOverride)
+                    .into(),
+            );
+            page = page.push(Self::banner(fl!("banner-readonly"), actions));
+        }
+
+        if let Some(packaged) = &doc.packaged {
+            page = page.push(Self::banner(
+                Self::packaged_text(packaged),
+                vec![
+                    widget::button::suggested(fl!("action-saveoverride"))
+                        .on_press(Message::SaveOverride)
+                        .into(),
+                ],
+            ));
+        }
+
+        let deprecated = doc
+            .current_entry
+            .as_ref()
+            .map(lint::deprecated_keys)
+            .unwrap_or_default();
+        if !deprecated.is_empty() {
+            let keys: Vec<&str> = deprecated.iter().map(|(key, _)| *key).collect();
+            page = page.push(Self::banner(
+                fl!("banner-deprecated", keys = keys.join(", ")),
+                vec![
+                    widget::button::standard(fl!("action-migratedeprecated"))
+                        .on_press(Message::MigrateDeprecated)
+                        .into(),
+                ],
+            ));
+        }
+
+        let quoting = doc.quoting_fixes();
+        if !quoting.is_empty() {
+            let mut keys: Vec<&str> = quoting.iter().map(|(_, key, _)| *key).collect();
+            keys.dedup();
+            page = page.push(Self::banner(
+                fl!("banner-quoting", keys = keys.join(", ")),
+                vec![
+                    widget::button::standard(fl!("action-fixquoting"))
+                        .on_press(Message::FixQuoting)
+                        .into(),
+                ],
+            ));
+        }
+
+        if let Some(system) = &doc.system_entry {
+            let count = doc.override_diffs().len();
+            page = page.push(Self::banner(
+                fl!("banner-overrides", path = system.path.display().to_string()),
+                vec![
+                    widget::button::standard(fl!("action-showdifferences", count = count))
+                        .on_press(Message::ToggleContextPage(ContextPage::Overrides))
+                        .into(),
+                ],
+            ));
+        }
+
+        if !doc.rename_warnings.is_empty() {
+            page = page.push(Self::banner(
+                doc.rename_warnings.join("\n"),
+                vec![
+                    widget::button::standard(fl!("action-dismiss"))
+                        .on_press(Message::DismissRenameWarnings)
+                        .into(),
+                ],
+            ));
+        }
+
+        if !doc.save_warnings.is_empty() {
+            page = page.push(Self::banner(
+                doc.save_warnings.join("\n"),
+                vec![
+                    widget::button::standard(fl!("action-dismiss"))
+                        .on_press(Message::DismissSaveWarnings)
+                        .into(),
+                ],
+            ));
+        }
+
+        if let Some(copy) = &doc.autostart_copy {
+            page = page.push(Self::banner(
+                fl!("banner-autostart", path = copy.display().to_string()),
+                vec![
+                    widget::button::standard(fl!("action-open"))
+                        .on_press(Message::OpenInWindow(copy.clone()))
+                        .into(),
+                ],
+            ));
+        }
+
+        if let Some(path) = &doc.trashed {
+            page = page.push(Self::banner(
+                fl!("banner-trashed", path = path.display().to_string()),
+                vec![
+                    widget::button::standard(fl!("action-undo"))
+                        .on_press(Message::UndoTrash)
+                        .into(),
+                ],
+            ));
+        }
+
+        if doc.disk_changed {
+            page = page.push(Self::banner(
+                fl!("banner-changedondisk"),
+                vec![
+                    widget::button::standard(fl!("action-keepmine"))
+                        .on_press(Message::KeepInMemory)
+                        .into(),
+                    widget::button::suggested(fl!("action-reload"))
+                        .on_press(Message::ReloadFromDisk)
+                        .into(),
+                ],
+            ));
+        }
+
+        page.push(self.document_content(doc)).into()
+    }
+
+    // Which package the launcher came from, and that updates replace it.
+    fn packaged_text(packaged: &Packaged) -> String {
+        match packaged {
+            Packaged::Flatpak(app) => {
+                let mut details = Vec::new();
+                if let Some(branch) = &app.branch {
+                    details.push(fl!("flatpak-branch", branch = branch.as_str()));
+                }
+                if let Some(origin) = &app.origin {
+                    details.push(fl!("flatpak-origin", origin = origin.as_str()));
+                }
+                let app_name = if details.is_empty() {
+                    app.id.clone()
+                } else {
+                    format!("{} ({})", app.id, details.join(", "))
+                };
+                fl!("banner-flatpak", app = app_name)
+            }
+            Packaged::Snap(snap) => {
+                let snap_name = match &snap.revision {
+                    Some(revision) => fl!(
+                        "snap-revision",
+                        name = snap.name.as_str(),
+                        revision = revision.as_str()
+                    ),
+                    None => snap.name.clone(),
+                };
+                fl!("banner-snap", snap = snap_name)
+            }
+        }
+    }
+
+    fn open_comparison(&mut self, left: PathBuf, right: PathBuf) -> Task<cosmic::Action<Message>> {
+        let comparison = match Comparison::load(left, right) {
+            Ok(c) => c,
+            Err(e) => {
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                    e.to_string(),
+                )));
+            }
+        };
+
+        let mut settings = window::Settings {
+            size: Size::new(1024.0, 640.0),
+            min_size: Some(Size::new(360.0, 300.0)),
+            ..Default::default()
+        };
+        settings.platform_specific.application_id = Self::APP_ID.to_string();
+
+        let (id, command) = window::open(settings);
+        self.comparisons.insert(id, comparison);
+
+        Task::batch(vec![
+            command.map(|_id| cosmic::Action::None),
+            self.set_window_title(fl!("compare-title"), id),
+        ])
+    }
+
+    // Find out which application opens `file`, off the UI thread since it reads
+    // every mimeapps.list and installed launcher.
+    fn run_open_test(file: PathBuf, mime: String) -> Task<cosmic::Action<Message>> {
+        Task::perform(
+            async move { tokio::task::spawn_blocking(move || OpenTest::new(file, mime)).await },
+            |test| match test {
+                Ok(test) => cosmic::Action::App(Message::OpenTestResolved(test)),
+                Err(_) => cosmic::Action::None,
+            },
+        )
+    }
+
+    fn open_mimeapps_editor(&mut self, editor: MimeAppsEditor) -> Task<cosmic::Action<Message>> {
+        let mut settings = window::Settings {
+            size: Size::new(900.0, 640.0),
+            min_size: Some(Size::new(360.0, 300.0)),
+            ..Default::default()
+        };
+        settings.platform_specific.application_id = Self::APP_ID.to_string();
+
+        let (id, command) = window::open(settings);
+        self.mimeapps_editor = Some((id, editor));
+
+        Task::batch(vec![
+            command.map(|_id| cosmic::Action::None),
+            self.set_window_title(fl!("mimeapps-title"), id),
+        ])
+    }
+
+    fn mimeapps_view(editor: &MimeAppsEditor) -> Element<'_, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = theme::active().cosmic().spacing;
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+        let titles = [
+            fl!("mimeapps-defaults"),
+            fl!("mimeapps-added"),
+            fl!("mimeapps-removed"),
+        ];
+
+        let mut page = widget::column().spacing(space_m).padding(space_m).push(
+            row!(
+                widget::text::body(editor.file.path.display().to_string()).width(Length::Fill),
+                widget::button::suggested(fl!("menu-save"))
+                    .on_press_maybe(editor.changed.then_some(Message::MimeAppsSave)),
+            )
+            .align_y(Center)
+            .spacing(space_xxs),
+        );
+        if let Some(e) = &editor.error {
+            page = page.push(Self::warning(e.clone()));
+        }
+
+        for (section, (title, rows)) in titles.into_iter().zip(&editor.file.sections).enumerate() {
+            let mut list = list::ListColumn::new();
+            for (row_index, (mime, apps)) in rows.iter().enumerate() {
+                let mut line = widget::row()
+                    .push(widget::text::body(mime.clone()).width(Length::FillPortion(2)))
+                    .push(
+                        widget::text_input(fl!("hint-mimeapps"), apps)
+                            .on_input(move |a| Message::MimeAppsSetApps(section, row_index, a))
+                            .width(Length::FillPortion(3)),
+                    );
+                // Installed applications declaring this type
+                if let Some(candidates) = editor.handlers.get(mime.as_str()) {
+                    line = line.push(widget::dropdown(candidates, None, move |i| {
+                        Message::MimeAppsPick(section, row_index, i)
+                    }));
+                }
+                list = list.add(
+                    line.push(
+                        named(
+                            widget::button::icon(delete.clone()),
+                            fl!("a11y-remove", item = mime.clone()),
+                        )
+                        .on_press(Message::MimeAppsRemove(section, row_index)),
+                    )
+                    .align_y(Center)
+                    .spacing(space_xxs),
+                );
+            }
+            list = list.add(
+                row!(
+                    widget::text_input(fl!("hint-mimetype"), &editor.new_mime[section])
+                        .on_input(move |m| Message::MimeAppsNewMime(section, m))
+                        .on_submit(move |_| Message::MimeAppsAdd(section))
+                        .width(Length::Fill),
+                    widget::button::standard(fl!("action-add"))
+                        .on_press(Message::MimeAppsAdd(section)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+
+            page = page.push(column!(widget::text::heading(title), list).spacing(space_xxs));
+        }
+
+        widget::scrollable::vertical(page).into()
+    }
+
+    fn open_menu_editor(&mut self) -> Task<cosmic::Action<Message>> {
+        let editor = match MenuEditor::open() {
+            Ok(editor) => editor,
+            Err(e) => {
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                    e.to_string(),
+                )));
+            }
+        };
+
+        let mut settings = window::Settings {
+            size: Size::new(900.0, 640.0),
+            min_size: Some(Size::new(360.0, 300.0)),
+            ..Default::default()
+        };
+        settings.platform_specific.application_id = Self::APP_ID.to_string();
+
+        let (id, command) = window::open(settings);
+        self.menu_editor = Some((id, editor));
+
+        Task::batch(vec![
+            command.map(|_id| cosmic::Action::None),
+            self.set_window_title(fl!("xdgmenu-title"), id),
+        ])
+    }
+
+    // The menu tree, one button per menu indented by depth.
+    fn menu_tree<'a>(
+        menu: &Menu,
+        at: &mut Vec<String>,
+        selected: &[String],
+        list: &mut Vec<Element<'a, Message>>,
+    ) {
+        let cosmic_theme::Spacing { space_m, .. } = theme::active().cosmic().spacing;
+        let label = if menu.deleted == Some(true) {
+            fl!("xdgmenu-hidden", name = menu.name.as_str())
+        } else {
+            menu.name.clone()
+        };
+        let button = if at.as_slice() == selected {
+            widget::button::suggested(label)
+        } else {
+            widget::button::text(label).on_press(Message::MenuEditorSelect(at.clone()))
+        };
+        let indent = space_m * at.len() as u16;
+        list.push(
+            widget::container(button)
+                .padding(cosmic::iced::Padding::ZERO.left(indent))
+                .into(),
+        );
+
+        for submenu in &menu.submenus {
+            at.push(submenu.name.clone());
+            Self::menu_tree(submenu, at, selected, list);
+            at.pop();
+        }
+    }
+
+    fn menu_editor_view<'a>(&'a self, editor: &'a MenuEditor) -> Element<'a, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = theme::active().cosmic().spacing;
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+        let tree = editor.tree();
+
+        let mut page = widget::column().spacing(space_m).padding(space_m).push(
+            row!(
+                widget::text::body(editor.path.display().to_string()).width(Length::Fill),
+                widget::button::suggested(fl!("menu-save"))
+                    .on_press_maybe(editor.changed.then_some(Message::MenuEditorSave)),
+            )
+            .align_y(Center)
+            .spacing(space_xxs),
+        );
+        if let Some(e) = &editor.error {
+            page = page.push(Self::warning(e.clone()));
+        }
+
+        let mut menus = Vec::new();
+        Self::menu_tree(&tree, &mut Vec::new(), &editor.selected, &mut menus);
+        let menus = widget::scrollable::vertical(widget::column::with_children(menus))
+            .width(Length::FillPortion(1));
+
+        let Some(menu) = tree.at(&editor.selected) else {
+            return page.push(menus).into();
+        };
+
+        // Only an open .directory file can be attached
+        let directory_file = self
+            .doc()
+            .current_entry_path
+            .as_deref()
+            .is_some_and(|p| p.extension().is_some_and(|e| e == "directory"));
+        let mut details = widget::column()
+            .spacing(space_xxs)
+            .push(widget::text::heading(menu.name.clone()))
+            .push(widget::text::caption(fl!("xdgmenu-expl")))
+            .push(widget::settings::item(
+                fl!("xdgmenu-hide"),
+                widget::toggler(menu.deleted == Some(true)).on_toggle(Message::MenuEditorHidden),
+            ))
+            .push(widget::text::body(fl!("xdgmenu-directory")))
+            .push(
+                row!(
+                    widget::text_input(
+                        fl!("hint-directory"),
+                        menu.directory.clone().unwrap_or_default()
+                    )
+                    .on_input(Message::MenuEditorDirectory)
+                    .width(Length::Fill),
+                    widget::button::standard(fl!("action-useopendirectory"))
+                        .on_press_maybe(directory_file.then_some(Message::MenuEditorUseDirectory)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+
+        for (include, title, rules) in [
+            (true, fl!("xdgmenu-include"), &menu.include),
+            (false, fl!("xdgmenu-exclude"), &menu.exclude),
+        ] {
+            let mut list = list::ListColumn::new();
+            for (i, rule) in rules.iter().enumerate() {
+                let label = rule.label();
+                list = list.add(
+                    row!(
+                        widget::text::body(label.clone()).width(Length::Fill),
+                        named(
+                            widget::button::icon(delete.clone()),
+                            fl!("a11y-remove", item = label),
+                        )
+                        .on_press(Message::MenuEditorRemoveRule(include, i)),
+                    )
+                    .align_y(Center)
+                    .spacing(space_xxs),
+                );
+            }
+            if rules.is_empty() {
+                list = list.add(widget::text::caption(fl!("xdgmenu-norules")));
+            }
+            details = details.push(widget::text::body(title)).push(list);
+        }
+
+        details = details
+            .push(
+                row!(
+                    widget::text_input(fl!("hint-menurule"), &editor.rule_input)
+                        .on_input(Message::MenuEditorRuleInput)
+                        .on_submit(|_| Message::MenuEditorAddRule(true))
+                        .width(Length::Fill),
+                    widget::button::standard(fl!("action-include"))
+                        .on_press(Message::MenuEditorAddRule(true)),
+                    widget::button::standard(fl!("action-exclude"))
+                        .on_press(Message::MenuEditorAddRule(false)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            )
+            .push(
+                row!(
+                    widget::text_input(fl!("hint-submenu"), &editor.submenu_input)
+                        .on_input(Message::MenuEditorSubmenuInput)
+                        .on_submit(|_| Message::MenuEditorAddSubmenu)
+                        .width(Length::Fill),
+                    widget::button::standard(fl!("action-addsubmenu"))
+                        .on_press(Message::MenuEditorAddSubmenu),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+
+        page.push(
+            row!(
+                menus,
+                widget::scrollable::vertical(details).width(Length::FillPortion(2)),
+            )
+            .spacing(space_m)
+            .height(Length::Fill),
+        )
+        .into()
+    }
+
+    fn comparison_view<'a>(&'a self, comparison: &'a Comparison) -> Element<'a, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = theme::active().cosmic().spacing;
+
+        let header = row!(
+            widget::text::heading(fl!("compare-key")).width(Length::FillPortion(1)),
+            widget::text::heading(comparison.left.display().to_string())
+                .width(Length::FillPortion(2)),
+            widget::text::heading(comparison.right.display().to_string())
+                .width(Length::FillPortion(2)),
+        )
+        .spacing(space_xxs);
+
+        let mut list = list::ListColumn::new();
+        for diff in &comparison.diffs {
+            // Marker as in a unified diff: + only right, - only left, ~ changed
+            let (marker, class) = if diff.is_added() {
+                ("+", theme::Text::Accent)
+            } else if diff.is_removed() {
+                ("-", theme::Text::Accent)
+            } else {
+                ("~", theme::Text::Default)
+            };
+            let key = if diff.group == "Desktop Entry" {
+                format!("{marker} {}", diff.key)
+            } else {
+                format!("{marker} [{}] {}", diff.group, diff.key)
+            };
+            let unset = || fl!("value-unset");
+
+            list = list.add(
+                row!(
+                    widget::text::body(key)
+                        .class(class)
+                        .width(Length::FillPortion(1)),
+                    widget::text::body(diff.left.clone().unwrap_or_else(unset))
+                        .width(Length::FillPortion(2)),
+                    widget::text::body(diff.right.clone().unwrap_or_else(unset))
+                        .width(Length::FillPortion(2)),
+                )
+                .spacing(space_xxs),
+            );
+        }
+
+        let summary = widget::text::caption(fl!(
+            "compare-summary",
+            different = comparison.diffs.len(),
+            identical = comparison.identical
+        ));
+
+        column!(
+            header,
+            widget::scrollable(list).height(Length::Fill),
+            summary
+        )
+        .spacing(space_xxs)
+        .padding(space_m)
+        .into()
+    }
+
+    // A notice above the page with buttons for resolving it.
+    fn warning<'a>(text: String) -> Element<'a, Message> {
+        widget::text::caption(text)
+            .class(theme::Text::Color(WARNING_COLOR))
+            .into()
+    }
+
+    // Chips for the items of a list key, removable and reordered by dragging,
+    // with a field adding more.
+    fn list_editor<'a>(doc: &'a Document, key: DesktopKey, hint: String) -> Element<'a, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let close = widget::icon::from_name("window-close-symbolic").handle();
+        let name = key.to_string();
+        let dragged = doc
+            .list_drag
+            .as_ref()
+            .filter(|(k, _)| *k == name)
+            .map(|(_, i)| *i);
+
+        let chips = doc
+            .list(&key)
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let remove = fl!("a11y-remove", item = item.clone());
+                let chip = row!(
+                    widget::text::body(item),
+                    named(widget::button::icon(close.clone()).extra_small(), remove)
+                        .on_press(Message::RemoveListItem(key.clone(), i)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs)
+                .padding(cosmic::iced::Padding::ZERO.left(8))
+                .apply(widget::container)
+                .class(if dragged == Some(i) {
+                    cosmic::style::Container::Primary
+                } else {
+                    cosmic::style::Container::Card
+                });
+
+                // Press and drag a chip over another to reorder
+                cosmic::iced::widget::mouse_area(chip)
+                    .on_press(Message::ListDragStart(key.clone(), i))
+                    .on_enter(Message::ListDragOver(key.clone(), i))
+                    .into()
+            })
+            .collect();
+
+        let input = doc.list_input.get(&name).map_or("", String::as_str);
+        let submit = key.clone();
+        column!(
+            widget::flex_row(chips)
+                .row_spacing(space_xxs)
+                .column_spacing(space_xxs),
+            widget::text_input(hint, input)
+                .on_input(move |t| Message::ListInput(key.clone(), t))
+                .on_submit(move |_| Message::AddListItem(submit.clone())),
+        )
+        .spacing(space_xxs)
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn show_in_editor<'a>(key: DesktopKey, doc: &'a Document) -> Element<'a, Message> {
+        // Once, under the second of the two rows
+        let conflict = matches!(key, DesktopKey::NotShowIn) && doc.show_in_conflict();
+        column!(Self::list_editor(doc, key, fl!("hint-desktops")))
+            .push_maybe(conflict.then(|| Self::warning(fl!("showin-conflict"))))
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn categories_editor<'a>(doc: &'a Document, entry: &DesktopEntry) -> Element<'a, Message> {
+        let categories = entry.categories().unwrap_or_default();
+        let categories: Vec<&str> = categories.into_iter().filter(|c| !c.is_empty()).collect();
+
+        let mut col = widget::column().width(Length::Fill).push(Self::list_editor(
+            doc,
+            DesktopKey::Categories,
+            fl!("hint-categories"),
+        ));
+        if !categories.iter().any(|c| categories::is_main(c)) {
+            col = col.push(Self::warning(fl!("categories-nomain")));
+        }
+        let unknown: Vec<&str> = categories
+            .iter()
+            .copied()
+            .filter(|c| categories::is_unknown(c))
+            .collect();
+        if !unknown.is_empty() {
+            col = col.push(Self::warning(fl!(
+                "categories-unknown",
+                names = unknown.join(", ")
+            )));
+        }
+        col.into()
+    }
+
+    fn banner<'a>(text: String, actions: Vec<Element<'a, Message>>) -> Element<'a, Message> {
+        widget::row()
+            .push(widget::text::body(text))
+            .push(horizontal_space())
+            .extend(actions)
+            .align_y(Center)
+            .spacing(8)
+            .padding(8)
+            .apply(widget::container)
+            .class(cosmic::style::Container::Card)
+            .into()
+    }
+
+    fn document_content<'a>(&'a self, doc: &'a Document) -> Element<'a, Message> {
+        let theme = cosmic::theme::active();
+        let padding = if self.core.is_condensed() {
+            theme.cosmic().space_s()
+        } else {
+            theme.cosmic().space_l()
+        };
+
+        // MissingArgument not actually an error
+        let fatal_error = doc
+            .current_entry_error
+            .as_ref()
+            .filter(|e| !matches!(e, AppError::MissingArgument));
+
+        match (fatal_error, doc.current_entry.as_ref()) {
+            // Landing / browse
+            (None, None) => {
+                let folder = widget::icon::from_name("folder-symbolic").handle();
+
+                column!(
+                    vertical_space(),
+                    widget::text::title1(fl!("app-title"))
+                        .apply(widget::container)
+                        .width(Length::Fill)
+                        .align_x(Horizontal::Center)
+                        .align_y(Vertical::Center),
+                    widget::button::text(fl!("action-browse"))
+                        .trailing_icon(folder)
+                        .on_press(Message::OpenPath(PickKind::DesktopFile)),
+                    vertical_space()
+                )
+                .align_x(Horizontal::Center)
+                .into()
+            }
+
+            // Error
+            (Some(error), _) => column!(
+                widget::text::title1(fl!("error-parsingentry"))
+                    .apply(widget::container)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .align_x(Horizontal::Center)
+                    .align_y(Vertical::Center),
+                widget::text::body(error.to_string())
+                    .apply(widget::container)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .align_x(Horizontal::Center)
+            )
+            .into(),
 
-            Message::SubscriptionChannel => {
-                // For example purposes only.
-            }
+            (None, Some(entry)) if doc.thumbnailer => self.thumbnailer_view(doc, entry, padding),
 
-            Message::ToggleContextPage(context_page) => {
-                if self.context_page == context_page {
-                    // Close the context drawer if the toggled context page is the same.
-                    self.core.window.show_context = !self.core.window.show_context;
-                } else {
-                    // Open the context drawer to display the requested context page.
-                    self.context_page = context_page;
-                    self.core.window.show_context = true;
+            // Show entry
+            (None, Some(entry)) => {
+                match entry.type_().unwrap_or_default().to_lowercase().as_str() {
+                    "link" => self.link_view(doc, entry, padding),
+                    "directory" => self.directory_view(doc, entry, padding),
+                    "application" => self.application_view(doc, entry, padding),
+                    _ => column!(
+                        widget::text::title1(fl!("error-parsingentry"))
+                            .apply(widget::container)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .align_x(Horizontal::Center)
+                            .align_y(Vertical::Center),
+                        widget::text::body(fl!("error-parsingentry"))
+                            .apply(widget::container)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .align_x(Horizontal::Center)
+                    )
+                    .into(),
                 }
             }
+        }
+    }
 
-            Message::UpdateConfig(config) => {
-                self.config = config;
-            }
+    fn menu_bar<'a>(&'a self, doc: &'a Document) -> Element<'a, Message> {
+        let restore = if doc.has_backup {
+            menu::Item::Button(fl!("menu-restorebackup"), None, MenuAction::RestoreBackup)
+        } else {
+            menu::Item::ButtonDisabled(fl!("menu-restorebackup"), None, MenuAction::RestoreBackup)
+        };
 
-            Message::CloseWindow(id) => {
-                if Some(id) == self.core.main_window_id() {
-                    return self.update(Message::Quit);
-                }
-            }
+        let save_template = if doc.current_entry.is_some() {
+            menu::Item::Button(fl!("menu-savetemplate"), None, MenuAction::SaveTemplate)
+        } else {
+            menu::Item::ButtonDisabled(fl!("menu-savetemplate"), None, MenuAction::SaveTemplate)
+        };
 
-            Message::ToggleEdit(field) => self.am_editing.toggle(&field),
-            Message::None => (),
+        let mut from_template: Vec<menu::Item<MenuAction, String>> = self
+            .templates
+            .iter()
+            .enumerate()
+            .map(|(i, t)| menu::Item::Button(t.name.clone(), None, MenuAction::NewFromTemplate(i)))
+            .collect();
+        if from_template.is_empty() {
+            from_template.push(menu::Item::ButtonDisabled(
+                fl!("menu-notemplates"),
+                None,
+                MenuAction::None,
+            ));
         }
-        Task::none()
-    }
 
-    /// Called when a nav item is selected.
-    fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
-        // Activate the page in the model.
-        self.nav.activate(id);
+        let (revert, history, open_folder) = if doc.current_entry_path.is_some() {
+            (
+                menu::Item::Button(fl!("menu-revert"), None, MenuAction::Revert),
+                menu::Item::Button(fl!("menu-history"), None, MenuAction::History),
+                menu::Item::Button(
+                    fl!("menu-openfolder"),
+                    None,
+                    MenuAction::OpenContainingFolder,
+                ),
+            )
+        } else {
+            (
+                menu::Item::ButtonDisabled(fl!("menu-revert"), None, MenuAction::Revert),
+                menu::Item::ButtonDisabled(fl!("menu-history"), None, MenuAction::History),
+                menu::Item::ButtonDisabled(
+                    fl!("menu-openfolder"),
+                    None,
+                    MenuAction::OpenContainingFolder,
+                ),
+            )
+        };
 
-        self.update_title()
-    }
-}
+        let can_autostart = doc.entry_type() == Some(DesktopEntryType::Application)
+            && doc.autostart_copy.is_none()
+            && doc
+                .current_entry_path
+                .as_deref()
+                .is_some_and(|p| !xdghelp::is_autostart_file(p));
+        let autostart = if can_autostart {
+            menu::Item::Button(fl!("menu-autostart"), None, MenuAction::AddToAutostart)
+        } else {
+            menu::Item::ButtonDisabled(fl!("menu-autostart"), None, MenuAction::AddToAutostart)
+        };
 
-impl AppModel {
-    pub fn update_title(&mut self) -> Task<cosmic::Action<Message>> {
-        let window_title = fl!("app-title");
-        if let Some(id) = self.core.main_window_id() {
-            self.set_window_title(window_title, id)
+        let (rename, delete) = if doc.current_entry_path.is_some() && !doc.read_only {
+            (
+                menu::Item::Button(fl!("menu-rename"), None, MenuAction::Rename),
+                menu::Item::Button(fl!("menu-delete"), None, MenuAction::DeleteEntry),
+            )
+        } else {
+            (
+                menu::Item::ButtonDisabled(fl!("menu-rename"), None, MenuAction::Rename),
+                menu::Item::ButtonDisabled(fl!("menu-delete"), None, MenuAction::DeleteEntry),
+            )
+        };
+
+        let (save, saveas, save_normalized, review, export_json) = if doc.current_entry.is_some() {
+            (
+                if doc.current_entry_changed {
+                    menu::Item::Button(fl!("menu-save"), None, MenuAction::Save)
+                } else {
+                    menu::Item::ButtonDisabled(fl!("menu-save"), None, MenuAction::Save)
+                },
+                menu::Item::Button(fl!("menu-saveas"), None, MenuAction::SaveAs),
+                menu::Item::Button(fl!("menu-savenormalized"), None, MenuAction::SaveNormalized),
+                menu::Item::Button(fl!("menu-review"), None, MenuAction::ReviewChanges),
+                menu::Item::Button(fl!("menu-exportjson"), None, MenuAction::ExportJson),
+            )
+        } else {
+            (
+                menu::Item::ButtonDisabled(fl!("menu-save"), None, MenuAction::Save),
+                menu::Item::ButtonDisabled(fl!("menu-saveas"), None, MenuAction::SaveAs),
+                menu::Item::ButtonDisabled(
+                    fl!("menu-savenormalized"),
+                    None,
+                    MenuAction::SaveNormalized,
+                ),
+                menu::Item::ButtonDisabled(fl!("menu-review"), None, MenuAction::ReviewChanges),
+                menu::Item::ButtonDisabled(fl!("menu-exportjson"), None, MenuAction::ExportJson),
+            )
+        };
+
+        let can_launch = doc.entry_type() == Some(DesktopEntryType::Application)
+            && doc
+                .current_entry
+                .as_ref()
+                .and_then(DesktopEntry::exec)
+                .is_some();
+        let test_launch = if can_launch {
+            menu::Item::Button(fl!("menu-testlaunch"), None, MenuAction::TestLaunch)
+        } else {
+            menu::Item::ButtonDisabled(fl!("menu-testlaunch"), None, MenuAction::TestLaunch)
+        };
+
+        let copy = if doc.current_entry.is_some() {
+            menu::Item::Button(fl!("menu-copy"), None, MenuAction::CopyEntry)
+        } else {
+            menu::Item::ButtonDisabled(fl!("menu-copy"), None, MenuAction::CopyEntry)
+        };
+
+        let mut recent: Vec<menu::Item<MenuAction, String>> = self
+            .config
+            .recent_files
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let label = path.file_name().map_or_else(
+                    || path.display().to_string(),
+                    |f| f.to_string_lossy().into(),
+                );
+                menu::Item::Button(label, None, MenuAction::OpenRecent(i))
+            })
+            .collect();
+        if !recent.is_empty() {
+            recent.push(menu::Item::Divider);
+            recent.push(menu::Item::Button(
+                fl!("menu-clearrecent"),
+                None,
+                MenuAction::ClearRecent,
+            ));
         } else {
-            Task::none()
+            recent.push(menu::Item::ButtonDisabled(
+                fl!("menu-clearrecent"),
+                None,
+                MenuAction::ClearRecent,
+            ));
         }
+
+        let menu_bar = menu::bar(vec![
+            menu::Tree::with_children(
+                menu::root(fl!("menu-file")).apply(Element::from),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        menu::Item::Folder(
+                            fl!("menu-new"),
+                            vec![
+                                menu::Item::Button(
+                                    fl!("menu-newapplication"),
+                                    None,
+                                    MenuAction::NewApplication,
+                                ),
+                                menu::Item::Button(fl!("menu-newlink"), None, MenuAction::NewLink),
+                                menu::Item::Button(
+                                    fl!("menu-newdirectory"),
+                                    None,
+                                    MenuAction::NewDirectory,
+                                ),
+                                menu::Item::Button(
+                                    fl!("menu-newwebapp"),
+                                    None,
+                                    MenuAction::NewWebApp,
+                                ),
+                                menu::Item::Divider,
+                                menu::Item::Folder(fl!("menu-fromtemplate"), from_template),
+                            ],
+                        ),
+                        menu::Item::Button(fl!("menu-newwindow"), None, MenuAction::NewWindow),
+                        menu::Item::Divider,
+                        menu::Item::Button(fl!("menu-open"), None, MenuAction::Open),
+                        menu::Item::Button(fl!("menu-quickopen"), None, MenuAction::QuickOpen),
+                        menu::Item::Folder(fl!("menu-recent"), recent),
+                        menu::Item::Button(fl!("menu-compare"), None, MenuAction::Compare),
+                        menu::Item::Button(fl!("menu-mimeapps"), None, MenuAction::MimeAppsEditor),
+                        menu::Item::Button(fl!("menu-xdgmenu"), None, MenuAction::MenuEditor),
+                        menu::Item::Button(fl!("menu-opentest"), None, MenuAction::OpenTest),
+                        review,
+                        save,
+                        saveas,
+                        save_normalized,
+                        save_template,
+                        revert,
+                        restore,
+                        history,
+                        menu::Item::Folder(
+                            fl!("menu-import"),
+                            vec![
+                                menu::Item::Button(
+                                    fl!("menu-importurl"),
+                                    None,
+                                    MenuAction::ImportUrl,
+                                ),
+                                menu::Item::Button(
+                                    fl!("menu-importjson"),
+                                    None,
+                                    MenuAction::ImportJson,
+                                ),
+                            ],
+                        ),
+                        menu::Item::Folder(fl!("menu-export"), vec![export_json]),
+                        rename,
+                        open_folder,
+                        delete,
+                        autostart,
+                        menu::Item::Divider,
+                        test_launch,
+                        menu::Item::Divider,
+                        menu::Item::Button(fl!("menu-quit"), None, MenuAction::Quit),
+                    ],
+                ),
+            ),
+            menu::Tree::with_children(
+                menu::root(fl!("menu-edit")).apply(Element::from),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        copy,
+                        menu::Item::Button(fl!("menu-paste"), None, MenuAction::PasteEntry),
+                    ],
+                ),
+            ),
+            menu::Tree::with_children(
+                menu::root(fl!("menu-view")).apply(Element::from),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        menu::Item::Button(fl!("menu-preferences"), None, MenuAction::Preferences),
+                        menu::Item::Button(fl!("menu-about"), None, MenuAction::About),
+                    ],
+                ),
+            ),
+        ])
+        .item_width(menu::ItemWidth::Uniform(200))
+        .item_height(menu::ItemHeight::Dynamic(200))
+        .spacing(4.0);
+
+        menu_bar.into()
+    }
+
+    pub fn update_title(&mut self) -> Task<cosmic::Action<Message>> {
+        // Forget the titles so all are set again
+        self.window_titles.clear();
+        self.sync_titles()
     }
 
     fn link_view<'a>(
         &'a self,
+        doc: &'a Document,
         entry: &'a DesktopEntry,
         padding: u16,
     ) -> Element<'a, crate::app::Message> {
@@ -886,8 +4004,8 @@ impl AppModel {
         };
 
         let active_tab_content: Element<'_, crate::app::Message> =
-            match self.nav.position(self.nav.active()) {
-                Some(0) => self.link_view_general(entry, padding),
+            match doc.nav.position(doc.nav.active()) {
+                Some(0) => self.link_view_general(doc, entry, padding),
                 Some(1) => placeholder_row(NavPage::Mimetypes),
                 Some(2) => placeholder_row(NavPage::Actions),
                 Some(3) => placeholder_row(NavPage::Custom),
@@ -902,10 +4020,11 @@ impl AppModel {
 
     fn link_view_general<'a>(
         &'a self,
+        doc: &'a Document,
         entry: &'a DesktopEntry,
         padding: u16,
     ) -> Element<'a, crate::app::Message> {
-        let icon_button = container(self.get_icon_button())
+        let icon_button = container(self.get_icon_button(doc))
             .width(60)
             .height(60)
             .align_y(Center)
@@ -917,7 +4036,7 @@ impl AppModel {
 
         let location = format!(
             "Location: {}",
-            self.current_entry_path
+            doc.current_entry_path
                 .clone()
                 .unwrap_or_default()
                 .to_string_lossy()
@@ -925,126 +4044,191 @@ impl AppModel {
 
         let content = list::ListColumn::new()
             .add(
-                row!(
-                    widget::text(fl!("field-name")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Name,
-                        fl!("hint-name-link"),
-                        entry.name(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.name,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-name"), DesktopKey::Name, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Name,
+                            fl!("hint-name-link"),
+                            doc.field_text(&DesktopKey::Name, locales),
+                            doc.am_editing.name,
+                            self
+                        ),
+                        Self::translate_button(DesktopKey::Name)
                     )
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-genericname"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::GenericName,
-                        fl!("hint-genericname"),
-                        entry.generic_name(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.generic_name,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-genericname"), DesktopKey::GenericName, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::GenericName,
+                            fl!("hint-genericname"),
+                            doc.field_text(&DesktopKey::GenericName, locales),
+                            doc.am_editing.generic_name,
+                            self
+                        ),
+                        Self::translate_button(DesktopKey::GenericName)
                     )
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-icon")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Icon,
-                        fl!("hint-icon"),
-                        entry.icon().unwrap_or_default(),
-                        self.am_editing.icon,
-                        self
+                column!(
+                    self.field_row(
+                        self.field_label(fl!("field-icon"), DesktopKey::Icon, label_w),
+                        row!(
+                            desktop_edit_field!(
+                                DesktopKey::Icon,
+                                fl!("hint-icon"),
+                                entry.icon().unwrap_or_default(),
+                                doc.am_editing.icon,
+                                self
+                            )
+                            .width(Length::Fill),
+                            named(
+                                widget::button::icon(folder.clone()),
+                                fl!("a11y-browse", field = fl!("field-icon")),
+                            )
+                            .on_press(Message::OpenPath(PickKind::IconFile))
+                        )
+                        .align_y(Center)
+                        .spacing(5),
                     )
-                    .width(Length::Fill),
-                    widget::button::icon(folder.clone())
-                        .on_press(Message::OpenPath(PickKind::IconFile))
                 )
-                .align_y(Center)
+                .push_maybe(self.missing_icon(entry))
                 .spacing(5),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-comment"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Comment,
-                        fl!("hint-comment"),
-                        entry.comment(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.comment,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-comment"), DesktopKey::Comment, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Comment,
+                            fl!("hint-comment"),
+                            doc.field_text(&DesktopKey::Comment, locales),
+                            doc.am_editing.comment,
+                            self
+                        )
+                        .width(Length::Fill),
+                        Self::translate_button(DesktopKey::Comment)
                     )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-url")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
+                self.field_row(
+                    self.field_label(fl!("field-url"), DesktopKey::Url, label_w),
+                    row!(desktop_edit_field!(
                         DesktopKey::Url,
                         fl!("hint-url"),
                         entry.url().unwrap_or_default(),
-                        self.am_editing.url,
+                        doc.am_editing.url,
                         self
-                    ),
-                )
-                .align_y(Center)
-                .spacing(5),
+                    ),)
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-hide")).align_x(Left).width(label_w),
+                    self.field_label(fl!("field-hide"), DesktopKey::NoDisplay, label_w),
                     horizontal_space(),
-                    widget::toggler(entry.no_display())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::NoDisplay, b)),
+                    self.bool_choice(doc, DesktopKey::NoDisplay, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-keywords"))
-                        .align_x(Left)
-                        .width(label_w),
+                self.field_row(
+                    self.field_label(fl!("field-keywords"), DesktopKey::Keywords, label_w),
+                    row!(
+                        Self::list_editor(doc, DesktopKey::Keywords, fl!("hint-keywords")),
+                        Self::translate_button(DesktopKey::Keywords)
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            );
+
+        column!(
+            Element::from(icon_button),
+            Element::from(content),
+            Element::from(widget::text(location))
+        )
+        .padding(padding)
+        .spacing(padding)
+        .into()
+    }
+
+    fn thumbnailer_view<'a>(
+        &'a self,
+        doc: &'a Document,
+        entry: &'a DesktopEntry,
+        padding: u16,
+    ) -> Element<'a, crate::app::Message> {
+        let active_tab_content: Element<'_, crate::app::Message> =
+            match doc.nav.active_data::<NavPage>() {
+                Some(NavPage::Mimetypes) => self.view_tab_mimetypes(doc),
+                _ => self.thumbnailer_view_general(doc, entry),
+            };
+
+        column!(active_tab_content)
+            .padding(padding)
+            .spacing(padding)
+            .into()
+    }
+
+    fn thumbnailer_view_general<'a>(
+        &'a self,
+        doc: &'a Document,
+        entry: &'a DesktopEntry,
+    ) -> Element<'a, crate::app::Message> {
+        let label_w = 160;
+        let exec = entry.exec().unwrap_or_default();
+        let warnings = thumbnailer::exec_warnings(exec)
+            .into_iter()
+            .map(Self::warning);
+
+        let content = list::ListColumn::new()
+            .add(widget::text::caption(fl!("thumbnailer-expl")))
+            .add(self.field_row(
+                self.field_label(fl!("field-tryexec"), DesktopKey::TryExec, label_w),
+                desktop_edit_field!(
+                    DesktopKey::TryExec,
+                    fl!("hint-thumbnailer-tryexec"),
+                    entry.try_exec().unwrap_or_default(),
+                    doc.am_editing.try_exec,
+                    self
+                ),
+            ))
+            .add(
+                column!(self.field_row(
+                    self.field_label(fl!("field-command"), DesktopKey::Exec, label_w),
                     desktop_edit_field!(
-                        DesktopKey::Keywords,
-                        fl!("hint-keywords"),
-                        entry
-                            .keywords(locales)
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.keywords,
+                        DesktopKey::Exec,
+                        fl!("hint-thumbnailer-exec"),
+                        exec,
+                        doc.am_editing.exec,
                         self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
+                    ),
+                ))
+                .push(widget::text::caption(fl!("thumbnailer-codes")))
+                .extend(warnings)
                 .spacing(5),
             );
 
-        column!(
-            Element::from(icon_button),
-            Element::from(content),
-            Element::from(widget::text(location))
-        )
-        .padding(padding)
-        .spacing(padding)
-        .into()
+        widget::scrollable::vertical(content).into()
     }
 
     fn directory_view<'a>(
         &'a self,
+        doc: &'a Document,
         entry: &'a DesktopEntry,
         padding: u16,
     ) -> Element<'a, crate::app::Message> {
@@ -1058,8 +4242,8 @@ impl AppModel {
         };
 
         let active_tab_content: Element<'_, crate::app::Message> =
-            match self.nav.position(self.nav.active()) {
-                Some(0) => self.directory_view_general(entry, padding),
+            match doc.nav.position(doc.nav.active()) {
+                Some(0) => self.directory_view_general(doc, entry, padding),
                 Some(1) => placeholder_row(NavPage::Mimetypes),
                 Some(2) => placeholder_row(NavPage::Actions),
                 Some(3) => placeholder_row(NavPage::Custom),
@@ -1074,10 +4258,11 @@ impl AppModel {
 
     fn directory_view_general<'a>(
         &'a self,
+        doc: &'a Document,
         entry: &'a DesktopEntry,
         padding: u16,
     ) -> Element<'a, crate::app::Message> {
-        let icon_button = container(self.get_icon_button())
+        let icon_button = container(self.get_icon_button(doc))
             .width(60)
             .height(60)
             .align_y(Center)
@@ -1089,7 +4274,7 @@ impl AppModel {
 
         let location = format!(
             "Location: {}",
-            self.current_entry_path
+            doc.current_entry_path
                 .clone()
                 .unwrap_or_default()
                 .to_string_lossy()
@@ -1097,119 +4282,109 @@ impl AppModel {
 
         let content = list::ListColumn::new()
             .add(
-                row!(
-                    widget::text(fl!("field-name")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Name,
-                        fl!("hint-name-directory"),
-                        entry.name(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.name,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-name"), DesktopKey::Name, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Name,
+                            fl!("hint-name-directory"),
+                            doc.field_text(&DesktopKey::Name, locales),
+                            doc.am_editing.name,
+                            self
+                        ),
+                        Self::translate_button(DesktopKey::Name)
                     )
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-icon")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Icon,
-                        fl!("hint-icon"),
-                        entry.icon().unwrap_or_default(),
-                        self.am_editing.icon,
-                        self
+                column!(
+                    self.field_row(
+                        self.field_label(fl!("field-icon"), DesktopKey::Icon, label_w),
+                        row!(
+                            desktop_edit_field!(
+                                DesktopKey::Icon,
+                                fl!("hint-icon"),
+                                entry.icon().unwrap_or_default(),
+                                doc.am_editing.icon,
+                                self
+                            )
+                            .width(Length::Fill),
+                            named(
+                                widget::button::icon(folder.clone()),
+                                fl!("a11y-browse", field = fl!("field-icon")),
+                            )
+                            .on_press(Message::OpenPath(PickKind::IconFile))
+                        )
+                        .align_y(Center)
+                        .spacing(5),
                     )
-                    .width(Length::Fill),
-                    widget::button::icon(folder.clone())
-                        .on_press(Message::OpenPath(PickKind::IconFile))
                 )
-                .align_y(Center)
+                .push_maybe(self.missing_icon(entry))
                 .spacing(5),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-comment"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Comment,
-                        fl!("hint-comment"),
-                        entry.comment(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.comment,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-comment"), DesktopKey::Comment, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Comment,
+                            fl!("hint-comment"),
+                            doc.field_text(&DesktopKey::Comment, locales),
+                            doc.am_editing.comment,
+                            self
+                        )
+                        .width(Length::Fill),
+                        Self::translate_button(DesktopKey::Comment)
                     )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-keywords"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Keywords,
-                        fl!("hint-keywords"),
-                        entry
-                            .keywords(locales)
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.keywords,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-keywords"), DesktopKey::Keywords, label_w),
+                    row!(
+                        Self::list_editor(doc, DesktopKey::Keywords, fl!("hint-keywords")),
+                        Self::translate_button(DesktopKey::Keywords)
                     )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-hide")).align_x(Left).width(label_w),
+                    self.field_label(fl!("field-hide"), DesktopKey::NoDisplay, label_w),
                     horizontal_space(),
-                    widget::toggler(entry.no_display())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::NoDisplay, b)),
+                    self.bool_choice(doc, DesktopKey::NoDisplay, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-onlyshownin"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::OnlyShowIn,
-                        fl!("hint-onlyshownin"),
-                        entry
-                            .only_show_in()
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.only_shown_in,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-onlyshownin"), DesktopKey::OnlyShowIn, label_w),
+                    row!(
+                        Self::show_in_editor(DesktopKey::OnlyShowIn, doc),
+                        widget::button::standard(fl!("action-choose"))
+                            .on_press(Message::ToggleContextPage(ContextPage::ShowIn)),
                     )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-notshownin"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::NotShowIn,
-                        fl!("hint-notshownin"),
-                        entry.not_show_in().map(|v| v.join(";")).unwrap_or_default(),
-                        self.am_editing.not_shown_in,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-notshownin"), DesktopKey::NotShowIn, label_w),
+                    row!(
+                        Self::show_in_editor(DesktopKey::NotShowIn, doc),
+                        widget::button::standard(fl!("action-choose"))
+                            .on_press(Message::ToggleContextPage(ContextPage::ShowIn)),
                     )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+                    .align_y(Center)
+                    .spacing(5),
+                ),
             );
 
         column!(
@@ -1224,21 +4399,22 @@ impl AppModel {
 
     fn application_view<'a>(
         &'a self,
+        doc: &'a Document,
         entry: &'a DesktopEntry,
         padding: u16,
     ) -> Element<'a, crate::app::Message> {
         let active_tab_content: Element<'_, crate::app::Message> =
-            match self.nav.position(self.nav.active()) {
-                Some(0) => self.view_tab_general(entry),
-                Some(1) => self.view_tab_mimetypes(),
+            match doc.nav.position(doc.nav.active()) {
+                Some(0) => self.view_tab_general(doc, entry),
+                Some(1) => self.view_tab_mimetypes(doc),
                 Some(2) => row!(
                     horizontal_space(),
                     widget::text::body("😵‍💫"),
                     horizontal_space()
                 )
                 .into(),
-                Some(3) => self.view_tab_xkeys(),
-                _ => self.view_tab_advanced(entry),
+                Some(3) => self.view_tab_xkeys(doc),
+                _ => self.view_tab_advanced(doc, entry),
             };
 
         column!(Element::from(active_tab_content))
@@ -1247,8 +4423,8 @@ impl AppModel {
             .into()
     }
 
-    fn view_tab_mimetypes<'a>(&'a self) -> Element<'a, crate::app::Message> {
-        let remove_button = if let Some(pos) = self.mime_table.position(self.mime_table.active()) {
+    fn view_tab_mimetypes<'a>(&'a self, doc: &'a Document) -> Element<'a, crate::app::Message> {
+        let remove_button = if let Some(pos) = doc.mime_table.position(doc.mime_table.active()) {
             widget::button::text("Remove").on_press(Message::RemoveMimetype(pos as usize))
         } else {
             widget::button::text("Remove")
@@ -1257,19 +4433,67 @@ impl AppModel {
         let add_button = widget::button::text("Add").on_press(Message::CreateDialog(
             DialogKind::NewMimetype(String::new()),
         ));
+        let bulk_button = widget::button::text(fl!("action-addseveral")).on_press(
+            Message::CreateDialog(DialogKind::BulkMimetypes(String::new())),
+        );
 
         let mut positions = HashMap::new();
-        for (pos, item) in self.mime_table.iter().enumerate() {
-            if let Some(data) = self.mime_table.item(item) {
+        for (pos, item) in doc.mime_table.iter().enumerate() {
+            if let Some(data) = doc.mime_table.item(item) {
                 positions.insert(data.name.clone(), pos);
             }
         }
 
+        let total = doc.mimetypes().len();
+        let shown = doc.mime_table.iter().count();
+        let (duplicates, empty) = doc.mimetype_problems();
+        let cleanup = (duplicates + empty > 0).then(|| {
+            row!(
+                Self::warning(fl!("mime-problems", duplicates = duplicates, empty = empty)),
+                widget::button::standard(fl!("action-cleanlist")).on_press(Message::CleanMimetypes),
+            )
+            .align_y(Center)
+            .spacing(8)
+        });
+        let filter = row!(
+            widget::search_input(fl!("hint-mimefilter"), &doc.mime_filter)
+                .on_input(Message::MimeFilter)
+                .on_clear(Message::MimeFilter(String::new()))
+                .width(Length::Fill),
+            widget::text::caption(if doc.mime_filter.is_empty() {
+                fl!("mimefilter-all", total = total)
+            } else {
+                fl!("mimefilter-count", shown = shown, total = total)
+            }),
+            widget::checkbox(fl!("mime-groupbytype"), doc.mime_grouped)
+                .on_toggle(Message::SetMimeGrouped),
+        )
+        .push_maybe(cleanup)
+        .align_y(Center)
+        .spacing(8)
+        .width(900);
+
+        if doc.mime_grouped {
+            return row!(
+                horizontal_space(),
+                column!(
+                    filter,
+                    Self::mime_groups_view(doc),
+                    row!(add_button, bulk_button, horizontal_space()).width(900)
+                )
+                .spacing(8),
+                horizontal_space()
+            )
+            .into();
+        }
+
         row!(
             horizontal_space(),
             column!(
-                widget::table(&self.mime_table)
+                filter,
+                widget::table(&doc.mime_table)
                     .on_item_left_click(Message::MimeItemSelect)
+                    .on_category_activate(Message::SortMimetypes)
                     .item_context(move |item| {
                         let pos = positions.get(&item.name).unwrap_or(&0);
 
@@ -1299,24 +4523,70 @@ impl AppModel {
                             ],
                         ))
                     })
-                    .width(500),
-                row!(remove_button, add_button, horizontal_space()).width(500)
+                    .width(900),
+                row!(remove_button, add_button, bulk_button, horizontal_space()).width(900)
             ),
             horizontal_space()
         )
         .apply(Element::from)
     }
 
-    fn view_tab_xkeys<'a>(&'a self) -> Element<'a, crate::app::Message> {
-        let remove_button = if let Some(pos) = self.xkey_table.position(self.xkey_table.active()) {
+    fn mime_groups_view(doc: &Document) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+
+        let mut list = list::ListColumn::new();
+        for (group, items) in doc.mime_groups() {
+            let collapsed = doc.mime_collapsed.contains(&group);
+            let expander = if collapsed {
+                "pan-end-symbolic"
+            } else {
+                "pan-down-symbolic"
+            };
+            list = list.add(
+                row!(
+                    widget::button::icon(widget::icon::from_name(expander))
+                        .on_press(Message::ToggleMimeGroup(group.clone())),
+                    widget::text::heading(format!("{group}/*")),
+                    widget::text::caption(fl!("mime-groupcount", count = items.len())),
+                    horizontal_space(),
+                    widget::button::standard(fl!("action-removeall"))
+                        .on_press(Message::RemoveMimeGroup(group.clone())),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+            if collapsed {
+                continue;
+            }
+            for item in items {
+                list = list.add(
+                    row!(
+                        widget::text::body(item.name.clone()).width(260),
+                        widget::text::body(item.description.clone()).width(Length::Fill),
+                        widget::text::caption(item.globs.clone()),
+                        widget::button::icon(delete.clone())
+                            .on_press(Message::RemoveMimetypeNamed(item.name.clone())),
+                    )
+                    .align_y(Center)
+                    .spacing(space_xxs)
+                    .padding(cosmic::iced::Padding::ZERO.left(32)),
+                );
+            }
+        }
+        list.apply(widget::container).width(900).into()
+    }
+
+    fn view_tab_xkeys<'a>(&'a self, doc: &'a Document) -> Element<'a, crate::app::Message> {
+        let remove_button = if let Some(pos) = doc.xkey_table.position(doc.xkey_table.active()) {
             widget::button::text("Remove").on_press(Message::RemoveXkey(pos as usize))
         } else {
             widget::button::text("Remove")
         };
 
         let mut positions = HashMap::new();
-        for (pos, item) in self.xkey_table.iter().enumerate() {
-            if let Some(data) = self.xkey_table.item(item) {
+        for (pos, item) in doc.xkey_table.iter().enumerate() {
+            if let Some(data) = doc.xkey_table.item(item) {
                 positions.insert(data.name.clone(), pos);
             }
         }
@@ -1328,487 +4598,1909 @@ impl AppModel {
         row!(
             horizontal_space(),
             column!(
-                widget::table(&self.xkey_table)
+                widget::table(&doc.xkey_table)
                     .on_item_left_click(Message::XkeyItemSelect)
                     .item_context(move |item| {
                         let pos = positions.get(&item.name).unwrap_or(&0);
 
-                        Some(widget::menu::items(
-                            &HashMap::new(),
-                            vec![widget::menu::Item::Button(
-                                format!("Remove {}", item.name),
-                                None,
-                                MenuAction::RemoveXkey(*pos),
-                            )],
-                        ))
-                    })
-                    .category_context(|category| {
-                        Some(widget::menu::items(
-                            &HashMap::new(),
-                            vec![
-                                widget::menu::Item::Button(
-                                    format!("Action on {} category", category),
-                                    None,
-                                    MenuAction::None,
-                                ),
-                                widget::menu::Item::Button(
-                                    format!("Other action on {} category", category),
-                                    None,
-                                    MenuAction::None,
-                                ),
-                            ],
-                        ))
-                    })
-                    .width(500),
-                row!(remove_button, add_button, horizontal_space()).width(500)
+                        Some(widget::menu::items(
+                            &HashMap::new(),
+                            vec![widget::menu::Item::Button(
+                                format!("Remove {}", item.name),
+                                None,
+                                MenuAction::RemoveXkey(*pos),
+                            )],
+                        ))
+                    })
+                    .category_context(|category| {
+                        Some(widget::menu::items(
+                            &HashMap::new(),
+                            vec![
+                                widget::menu::Item::Button(
+                                    format!("Action on {} category", category),
+                                    None,
+                                    MenuAction::None,
+                                ),
+                                widget::menu::Item::Button(
+                                    format!("Other action on {} category", category),
+                                    None,
+                                    MenuAction::None,
+                                ),
+                            ],
+                        ))
+                    })
+                    .width(500),
+                row!(remove_button, add_button, horizontal_space()).width(500),
+                Self::other_keys_view(doc),
+            )
+            .spacing(10),
+            horizontal_space()
+        )
+        .apply(Element::from)
+    }
+
+    // Keys without a field of their own, editable as plain text.
+    fn other_keys_view<'a>(doc: &Document) -> Element<'a, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+        let keys = doc.other_keys();
+
+        let mut section = column!(widget::text::heading(fl!("otherkeys-title")))
+            .spacing(space_xxs)
+            .width(500);
+        if keys.is_empty() {
+            return section
+                .push(widget::text::caption(fl!("otherkeys-none")))
+                .into();
+        }
+        section = section.push(widget::text::caption(fl!("otherkeys-expl")));
+
+        for (group, key, value) in keys {
+            let label = if group == "Desktop Entry" {
+                key.clone()
+            } else {
+                format!("[{group}] {key}")
+            };
+            let (edit_group, edit_key) = (group.clone(), key.clone());
+            section = section.push(
+                row!(
+                    widget::text(label).width(200),
+                    widget::text_input("", value).on_input(move |v| {
+                        Message::SetOtherKey(edit_group.clone(), edit_key.clone(), v)
+                    }),
+                    widget::button::icon(delete.clone())
+                        .on_press(Message::RemoveOtherKey(group, key)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+        }
+        section.into()
+    }
+
+    fn view_tab_general<'a>(
+        &'a self,
+        doc: &'a Document,
+        appdata: &'a DesktopEntry,
+    ) -> Element<'a, crate::app::Message> {
+        let label_w = 160;
+        let locales = &self.locales;
+        let folder = widget::icon::from_name("folder-symbolic").handle();
+
+        let location = format!(
+            "Location: {}",
+            doc.current_entry_path
+                .clone()
+                .unwrap_or_default()
+                .to_string_lossy()
+        );
+        let list = list::ListColumn::new()
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-name"), DesktopKey::Name, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Name,
+                            fl!("hint-name-application"),
+                            doc.field_text(&DesktopKey::Name, locales),
+                            doc.am_editing.name,
+                            self
+                        ),
+                        Self::translate_button(DesktopKey::Name)
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                column!(
+                    self.field_row(
+                        self.field_label(fl!("field-icon"), DesktopKey::Icon, label_w),
+                        row!(
+                            desktop_edit_field!(
+                                DesktopKey::Icon,
+                                fl!("hint-icon"),
+                                appdata.icon().unwrap_or_default(),
+                                doc.am_editing.icon,
+                                self
+                            )
+                            .width(Length::Fill),
+                            named(
+                                widget::button::icon(folder.clone()),
+                                fl!("a11y-browse", field = fl!("field-icon")),
+                            )
+                            .on_press(Message::OpenPath(PickKind::IconFile))
+                        )
+                        .align_y(Center)
+                        .spacing(5),
+                    )
+                )
+                .push_maybe(self.missing_icon(appdata))
+                .spacing(5),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-comment"), DesktopKey::Comment, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Comment,
+                            fl!("hint-comment"),
+                            doc.field_text(&DesktopKey::Comment, locales),
+                            doc.am_editing.comment,
+                            self
+                        )
+                        .width(Length::Fill),
+                        Self::translate_button(DesktopKey::Comment)
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-command"), DesktopKey::Exec, label_w),
+                    row!(
+                        column!(
+                            desktop_edit_field!(
+                                DesktopKey::Exec,
+                                fl!("hint-exec"),
+                                appdata.exec().unwrap_or_default(),
+                                doc.am_editing.exec,
+                                self
+                            ),
+                            self.exec_completions(doc, appdata),
+                            Self::exec_warnings(doc),
+                        )
+                        .width(Length::Fill),
+                        self.exec_help_button(),
+                        widget::tooltip(
+                            named(
+                                widget::button::icon(widget::icon::from_name(
+                                    "applications-engineering-symbolic"
+                                )),
+                                fl!("context-execbuilder"),
+                            )
+                            .on_press(Message::OpenExecBuilder),
+                            widget::text(fl!("context-execbuilder")),
+                            widget::tooltip::Position::Bottom,
+                        ),
+                        named(
+                            widget::button::icon(folder.clone()),
+                            fl!("a11y-browse", field = fl!("field-command")),
+                        )
+                        .on_press(Message::OpenPath(PickKind::Executable)),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    widget::text(fl!("field-wrappers"))
+                        .align_x(Left)
+                        .width(label_w),
+                    row!(self.wrapper_checkboxes(appdata),)
+                        .align_y(Center)
+                        .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-workpath"), DesktopKey::Path, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::Path,
+                            fl!("hint-path"),
+                            appdata.path().unwrap_or_default(),
+                            doc.am_editing.path,
+                            self
+                        ),
+                        widget::tooltip(
+                            named(
+                                widget::button::icon(widget::icon::from_name(
+                                    "utilities-terminal-symbolic"
+                                )),
+                                fl!("action-openterminal"),
+                            )
+                            .on_press_maybe(
+                                doc.working_dir_exists
+                                    .then_some(Message::OpenTerminalAtPath)
+                            ),
+                            widget::text(fl!("action-openterminal")),
+                            widget::tooltip::Position::Bottom,
+                        ),
+                        named(
+                            widget::button::icon(folder),
+                            fl!("a11y-browse", field = fl!("field-workpath")),
+                        )
+                        .on_press(Message::OpenPath(PickKind::Directory)),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                row!(
+                    self.field_label(fl!("field-runinterm"), DesktopKey::Terminal, label_w),
+                    horizontal_space(),
+                    self.bool_choice(doc, DesktopKey::Terminal, true),
+                )
+                .align_y(Center)
+                .spacing(5),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(
+                        fl!("field-nondefaultgpu"),
+                        DesktopKey::PrefersNonDefaultGPU,
+                        label_w,
+                    ),
+                    row!(
+                        self.gpu_info(),
+                        self.bool_choice(
+                            doc,
+                            DesktopKey::PrefersNonDefaultGPU,
+                            self.gpu_choice_possible(appdata),
+                        ),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                row!(
+                    self.field_label(fl!("field-hide"), DesktopKey::NoDisplay, label_w),
+                    horizontal_space(),
+                    self.bool_choice(doc, DesktopKey::NoDisplay, true),
+                )
+                .align_y(Center)
+                .spacing(5),
+            );
+
+        let icon_button = container(self.get_icon_button(doc))
+            .width(60)
+            .height(60)
+            .align_y(Center)
+            .align_x(Center);
+
+        let header = row!(
+            icon_button,
+            horizontal_space(),
+            self.preview_card(doc, appdata)
+        )
+        .align_y(Center)
+        .spacing(20);
+        let c = column!(header, list)
+            .push_maybe(Self::appstream_section(doc, appdata))
+            .push(widget::text(location))
+            .spacing(20);
+        widget::scrollable(c).into()
+    }
+
+    // The matching AppStream component and where the launcher disagrees with it.
+    fn appstream_section<'a>(doc: &Document, entry: &DesktopEntry) -> Option<Element<'a, Message>> {
+        let component = doc.appstream.as_ref()?;
+        let mut section = widget::settings::section()
+            .title(fl!("appstream-title"))
+            .add(widget::settings::item(
+                fl!("appstream-file"),
+                widget::text::body(component.path.display().to_string()),
+            ));
+        if let Some(name) = &component.name {
+            section = section.add(widget::settings::item(
+                fl!("generic-name"),
+                widget::text::body(name.clone()),
+            ));
+        }
+        if let Some(summary) = &component.summary {
+            section = section.add(widget::settings::item(
+                fl!("appstream-summary"),
+                widget::text::body(summary.clone()),
+            ));
+        }
+
+        let desktop_id = doc.desktop_id().unwrap_or_default();
+        let warnings = component
+            .mismatches(entry, &desktop_id)
+            .into_iter()
+            .map(Self::warning);
+        Some(
+            widget::column()
+                .push(section)
+                .extend(warnings)
+                .spacing(5)
+                .into(),
+        )
+    }
+
+    // How the launcher shows in an app grid, with a locale switcher for checking translations.
+    fn preview_card<'a>(
+        &'a self,
+        doc: &'a Document,
+        appdata: &'a DesktopEntry,
+    ) -> Element<'a, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let (name, comment) = match &doc.preview_locale {
+            Some(locale) => (
+                doc.localized("Name", locale),
+                doc.localized("Comment", locale),
             ),
-            horizontal_space()
+            None => (
+                appdata.name(&self.locales).map(|n| n.into_owned()),
+                appdata.comment(&self.locales).map(|c| c.into_owned()),
+            ),
+        };
+
+        // Cycle through the user's locale and every translated one
+        let choices: Vec<Option<String>> = std::iter::once(None)
+            .chain(
+                doc.translated_locales(&["Name", "Comment"])
+                    .into_iter()
+                    .map(Some),
+            )
+            .collect();
+        let current = choices
+            .iter()
+            .position(|c| *c == doc.preview_locale)
+            .unwrap_or(0);
+        let previous = choices[(current + choices.len() - 1) % choices.len()].clone();
+        let next = choices[(current + 1) % choices.len()].clone();
+
+        let card = column!(
+            widget::icon(self.entry_icon(doc)).size(64),
+            widget::text::body(name.unwrap_or_default())
+                .align_x(Horizontal::Center)
+                .width(Length::Fill),
         )
-        .apply(Element::from)
+        .push_maybe(comment.filter(|c| !c.is_empty()).map(|c| {
+            widget::text::caption(c)
+                .align_x(Horizontal::Center)
+                .width(Length::Fill)
+        }))
+        .align_x(Alignment::Center)
+        .spacing(space_xxs)
+        .padding(space_s)
+        .width(200)
+        .apply(widget::container)
+        .class(cosmic::theme::Container::Card);
+
+        let switcher = row!(
+            widget::button::icon(widget::icon::from_name("go-previous-symbolic"))
+                .on_press_maybe((choices.len() > 1).then(|| Message::PreviewLocale(previous))),
+            // Any locale can be typed in, translated or not, to see what it falls back to
+            widget::text_input(
+                fl!("preview-yourlanguage"),
+                doc.preview_locale.as_deref().unwrap_or_default(),
+            )
+            .on_input(|t| {
+                let locale = t.trim();
+                Message::PreviewLocale((!locale.is_empty()).then(|| locale.to_string()))
+            })
+            .name(fl!("preview-locale"))
+            .width(Length::Fill),
+            widget::button::icon(widget::icon::from_name("go-next-symbolic"))
+                .on_press_maybe((choices.len() > 1).then(|| Message::PreviewLocale(next))),
+        )
+        .align_y(Center)
+        .width(200);
+
+        column!(widget::text::caption(fl!("preview-title")), card, switcher)
+            .align_x(Alignment::Center)
+            .spacing(space_xxs)
+            .into()
     }
 
-    fn view_tab_general<'a>(
+    fn view_tab_advanced<'a>(
         &'a self,
+        doc: &'a Document,
         appdata: &'a DesktopEntry,
     ) -> Element<'a, crate::app::Message> {
         let label_w = 160;
         let locales = &self.locales;
         let folder = widget::icon::from_name("folder-symbolic").handle();
 
-        let location = format!(
-            "Location: {}",
-            self.current_entry_path
-                .clone()
-                .unwrap_or_default()
-                .to_string_lossy()
-        );
-        let list = list::ListColumn::new()
+        let mut list = list::ListColumn::new()
             .add(
-                row!(
-                    widget::text(fl!("field-name")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Name,
-                        fl!("hint-name-application"),
-                        appdata.name(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.name,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-genericname"), DesktopKey::GenericName, label_w),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::GenericName,
+                            fl!("hint-genericname"),
+                            doc.field_text(&DesktopKey::GenericName, locales),
+                            doc.am_editing.generic_name,
+                            self
+                        )
+                        .width(Length::Fill),
+                        Self::translate_button(DesktopKey::GenericName)
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                column!(
+                    self.field_row(
+                        self.field_label(fl!("field-tryexec"), DesktopKey::TryExec, label_w),
+                        row!(
+                            desktop_edit_field!(
+                                DesktopKey::TryExec,
+                                fl!("hint-tryexec"),
+                                appdata.try_exec().unwrap_or_default(),
+                                doc.am_editing.try_exec,
+                                self
+                            ),
+                            named(
+                                widget::button::icon(folder.clone()),
+                                fl!("a11y-browse", field = fl!("field-tryexec")),
+                            )
+                            .on_press(Message::OpenPath(PickKind::TryExecutable)),
+                        )
+                        .align_y(Center)
+                        .spacing(5),
                     )
                 )
-                .align_y(Center)
+                .push_maybe(Self::try_exec_status(doc, appdata))
                 .spacing(5),
             )
             .add(
-                row!(
-                    widget::text(fl!("field-icon")).align_x(Left).width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Icon,
-                        fl!("hint-icon"),
-                        appdata.icon().unwrap_or_default(),
-                        self.am_editing.icon,
-                        self
+                self.field_row(
+                    self.field_label(fl!("field-onlyshownin"), DesktopKey::OnlyShowIn, label_w),
+                    row!(
+                        Self::show_in_editor(DesktopKey::OnlyShowIn, doc),
+                        widget::button::standard(fl!("action-choose"))
+                            .on_press(Message::ToggleContextPage(ContextPage::ShowIn)),
                     )
-                    .width(Length::Fill),
-                    widget::button::icon(folder.clone())
-                        .on_press(Message::OpenPath(PickKind::IconFile))
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-notshownin"), DesktopKey::NotShowIn, label_w),
+                    row!(
+                        Self::show_in_editor(DesktopKey::NotShowIn, doc),
+                        widget::button::standard(fl!("action-choose"))
+                            .on_press(Message::ToggleContextPage(ContextPage::ShowIn)),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-keywords"), DesktopKey::Keywords, label_w),
+                    row!(
+                        Self::list_editor(doc, DesktopKey::Keywords, fl!("hint-keywords")),
+                        Self::translate_button(DesktopKey::Keywords)
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-categories"), DesktopKey::Categories, label_w),
+                    row!(
+                        Self::categories_editor(doc, appdata),
+                        widget::button::standard(fl!("action-choose"))
+                            .on_press(Message::ToggleContextPage(ContextPage::Categories)),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(fl!("field-implements"), DesktopKey::Implements, label_w),
+                    row!(Self::list_editor(
+                        doc,
+                        DesktopKey::Implements,
+                        fl!("hint-implements")
+                    ),)
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                self.field_row(
+                    self.field_label(
+                        fl!("field-startupwmclass"),
+                        DesktopKey::StartupWMClass,
+                        label_w,
+                    ),
+                    row!(
+                        desktop_edit_field!(
+                            DesktopKey::StartupWMClass,
+                            "",
+                            appdata.startup_wm_class().unwrap_or_default(),
+                            doc.am_editing.startupwmclass,
+                            self
+                        )
+                        .width(Length::Fill),
+                        widget::button::standard(fl!("action-pickrunning"))
+                            .on_press(Message::ListRunningApps),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            )
+            .add(
+                row!(
+                    self.field_label(
+                        fl!("field-startupnotify"),
+                        DesktopKey::StartupNotify,
+                        label_w,
+                    ),
+                    horizontal_space(),
+                    self.bool_choice(doc, DesktopKey::StartupNotify, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-comment"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Comment,
-                        fl!("hint-comment"),
-                        appdata.comment(locales).unwrap_or_default().into_owned(),
-                        self.am_editing.comment,
-                        self
-                    )
-                    .width(Length::Fill)
+                    self.field_label(fl!("field-hidden"), DesktopKey::Hidden, label_w),
+                    horizontal_space(),
+                    self.bool_choice(doc, DesktopKey::Hidden, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-command"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Exec,
-                        fl!("hint-exec"),
-                        appdata.exec().unwrap_or_default(),
-                        self.am_editing.exec,
-                        self
+                    self.field_label(
+                        fl!("field-singlemainwindow"),
+                        DesktopKey::SingleMainWindow,
+                        label_w,
                     ),
-                    widget::button::icon(folder.clone())
-                        .on_press(Message::OpenPath(PickKind::Executable)),
+                    horizontal_space(),
+                    self.bool_choice(doc, DesktopKey::SingleMainWindow, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-workpath"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Path,
-                        fl!("hint-path"),
-                        appdata.path().unwrap_or_default(),
-                        self.am_editing.path,
-                        self
-                    ),
-                    widget::button::icon(folder).on_press(Message::OpenPath(PickKind::Directory)),
+                    column!(
+                        self.field_label(
+                            fl!("field-dbusactivation"),
+                            DesktopKey::DBusActivatable,
+                            label_w,
+                        ),
+                        Self::dbus_warnings(doc, appdata),
+                    )
+                    .width(Length::Fill),
+                    self.bool_choice(doc, DesktopKey::DBusActivatable, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
+            .add(self.version_row(appdata, label_w));
+
+        for vendor in Vendor::ALL {
+            list = self.vendor_rows(list, vendor, appdata, label_w);
+        }
+
+        if doc
+            .current_entry_path
+            .as_deref()
+            .is_some_and(xdghelp::is_autostart_file)
+        {
+            list = self.autostart_rows(list, doc, appdata, label_w);
+        }
+
+        let ctrl = widget::scrollable::vertical(list);
+        ctrl.into()
+    }
+
+    // The spec version the entry targets, with the keys it uses that the version lacks.
+    fn version_row<'a>(&'a self, appdata: &'a DesktopEntry, label_w: u16) -> Element<'a, Message> {
+        let selected = appdata
+            .desktop_entry("Version")
+            .and_then(|v| lint::SPEC_VERSIONS.iter().position(|s| *s == v.trim()));
+        let warnings = lint::version_lints(appdata)
+            .into_iter()
+            .map(|l| Self::warning(l.message));
+
+        row!(
+            column!(self.field_label(fl!("field-version"), DesktopKey::Version, label_w))
+                .extend(warnings)
+                .width(Length::Fill),
+            widget::dropdown(lint::SPEC_VERSIONS, selected, |i| {
+                Message::SetTextEntry(DesktopKey::Version, lint::SPEC_VERSIONS[i].to_string())
+            }),
+        )
+        .align_y(Center)
+        .spacing(5)
+        .into()
+    }
+
+    // The keys of one desktop's extensions that have their own widgets.
+    fn vendor_rows<'a>(
+        &'a self,
+        list: list::ListColumn<'a, Message>,
+        vendor: Vendor,
+        appdata: &'a DesktopEntry,
+        label_w: u16,
+    ) -> list::ListColumn<'a, Message> {
+        let mut list = list.add(widget::text::heading(vendor.title()));
+        for key in vendor::KEYS.iter().filter(|k| k.vendor == vendor) {
+            let name = key.key;
+            let value = appdata.desktop_entry(name);
+            let label = self.field_label(key.label(), DesktopKey::Unknown(name.into()), label_w);
+            let input: Element<'a, Message> = match key.widget {
+                // Off removes the key, absent and false mean the same
+                vendor::Widget::Toggle => widget::toggler(value == Some("true"))
+                    .on_toggle(move |on| Message::SetVendorKey(name, on.then(|| "true".into())))
+                    .name(key.label())
+                    .into(),
+                vendor::Widget::Choice(choices) => widget::dropdown(
+                    choices,
+                    choices.iter().position(|c| Some(*c) == value),
+                    move |i| Message::SetVendorKey(name, Some(choices[i].to_string())),
+                )
+                .into(),
+                vendor::Widget::Flags(flags) => {
+                    let mut checks = widget::row().spacing(theme::active().cosmic().space_s());
+                    for flag in flags.iter().copied() {
+                        let set = value.is_some_and(|v| v.split(';').any(|f| f == flag));
+                        checks = checks.push(widget::checkbox(flag, set).on_toggle(move |on| {
+                            Message::SetVendorKey(name, vendor::toggle_flag(value, flag, on))
+                        }));
+                    }
+                    checks.into()
+                }
+                vendor::Widget::Text => widget::text_input(name, value.unwrap_or_default())
+                    .on_input(move |t| Message::SetVendorKey(name, (!t.is_empty()).then_some(t)))
+                    .name(key.label())
+                    .into(),
+            };
+            list = list.add(match key.widget {
+                vendor::Widget::Text => self.field_row(label, input),
+                _ => row!(label, horizontal_space(), input)
+                    .align_y(Center)
+                    .spacing(5)
+                    .into(),
+            });
+        }
+        list
+    }
+
+    // Keys only session managers read, shown for files in an autostart directory.
+    fn autostart_rows<'a>(
+        &'a self,
+        list: list::ListColumn<'a, Message>,
+        doc: &'a Document,
+        appdata: &'a DesktopEntry,
+        label_w: u16,
+    ) -> list::ListColumn<'a, Message> {
+        let delay: u32 = appdata
+            .desktop_entry("X-GNOME-Autostart-Delay")
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0);
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let mut list = list
+            .add(widget::text::heading(fl!("autostart-title")))
             .add(
                 row!(
-                    widget::text(fl!("field-runinterm"))
-                        .align_x(Left)
-                        .width(label_w),
+                    self.field_label(
+                        fl!("field-autostartenabled"),
+                        DesktopKey::AutostartEnabled,
+                        label_w,
+                    ),
                     horizontal_space(),
-                    widget::toggler(appdata.terminal())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::Terminal, b)),
+                    self.bool_choice(doc, DesktopKey::AutostartEnabled, true),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
                 row!(
-                    widget::text(fl!("field-nondefaultgpu"))
-                        .align_x(Left)
-                        .width(label_w),
+                    self.field_label(
+                        fl!("field-autostartdelay"),
+                        DesktopKey::AutostartDelay,
+                        label_w,
+                    ),
                     horizontal_space(),
-                    widget::toggler(appdata.prefers_non_default_gpu())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::PrefersNonDefaultGPU, b)),
+                    widget::spin_button(
+                        fl!("autostart-seconds", seconds = delay),
+                        delay,
+                        1,
+                        0,
+                        MAX_AUTOSTART_DELAY,
+                        |d| Message::SetTextEntry(DesktopKey::AutostartDelay, d.to_string()),
+                    ),
                 )
                 .align_y(Center)
                 .spacing(5),
             )
             .add(
+                self.field_row(
+                    self.field_label(
+                        fl!("field-autostartcondition"),
+                        DesktopKey::AutostartCondition,
+                        label_w,
+                    ),
+                    row!(desktop_edit_field!(
+                        DesktopKey::AutostartCondition,
+                        fl!("hint-autostartcondition"),
+                        appdata
+                            .desktop_entry("AutostartCondition")
+                            .unwrap_or_default(),
+                        doc.am_editing.autostart_condition,
+                        self
+                    ),)
+                    .align_y(Center)
+                    .spacing(5),
+                ),
+            );
+
+        list = list.add(
+            row!(
+                widget::text::caption(fl!("autostart-systemd")).width(Length::Fill),
+                widget::button::standard(fl!("action-showsystemd")).on_press(Message::ShowSystemd),
+            )
+            .align_y(Center)
+            .spacing(5),
+        );
+
+        // Autostart files are commonly meant for one desktop only
+        if !desktop.is_empty() && doc.list(&DesktopKey::OnlyShowIn).is_empty() {
+            list = list.add(
                 row!(
-                    widget::text(fl!("field-hide")).align_x(Left).width(label_w),
-                    horizontal_space(),
-                    widget::toggler(appdata.no_display())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::NoDisplay, b)),
+                    widget::text::caption(fl!("autostart-anydesktop")).width(Length::Fill),
+                    widget::button::standard(fl!("action-onlyin", desktop = desktop.as_str()))
+                        .on_press(Message::SetShowIn(DesktopKey::OnlyShowIn, desktop, true)),
                 )
                 .align_y(Center)
                 .spacing(5),
             );
+        }
+        list
+    }
+
+    pub fn context_about(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let icon = widget::svg(widget::svg::Handle::from_memory(APP_ICON));
+
+        let title = widget::text::title3(fl!("app-title"));
+
+        let link = widget::button::link(REPOSITORY)
+            .on_press(Message::OpenRepositoryUrl)
+            .padding(0);
+
+        let version = format!("Version {}.", env!("CARGO_PKG_VERSION"));
+
+        widget::column()
+            .push(icon)
+            .push(title)
+            .push(link)
+            .push(widget::text::body(version))
+            .align_x(Alignment::Center)
+            .spacing(space_xxs)
+            .into()
+    }
+
+    pub fn context_preferences(&'_ self) -> Element<'_, Message> {
+        widget::settings::view_column(vec![
+            widget::settings::section()
+                .title(fl!("prefs-saving"))
+                .add(widget::settings::item(
+                    fl!("prefs-savefolder"),
+                    row!(
+                        widget::text_input(
+                            "~/.local/share/applications",
+                            self.config.save_folder.to_string_lossy(),
+                        )
+                        .on_input(Message::SetSaveFolder),
+                        named(
+                            widget::button::icon(widget::icon::from_name("folder-symbolic")),
+                            fl!("a11y-browse", field = fl!("prefs-savefolder")),
+                        )
+                        .on_press(Message::OpenPath(PickKind::SaveFolder)),
+                    )
+                    .align_y(Center)
+                    .spacing(5),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-backups"),
+                    widget::spin_button(
+                        self.config.backup_count.to_string(),
+                        self.config.backup_count,
+                        1,
+                        0,
+                        MAX_BACKUPS,
+                        Message::SetBackupCount,
+                    ),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-history"),
+                    widget::spin_button(
+                        self.config.history_count.to_string(),
+                        self.config.history_count,
+                        1,
+                        0,
+                        MAX_HISTORY,
+                        Message::SetHistoryCount,
+                    ),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-deterministic"),
+                    widget::toggler(self.config.deterministic_output)
+                        .on_toggle(Message::SetDeterministicOutput),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-chmod"),
+                    widget::toggler(self.config.chmod_on_save).on_toggle_maybe(
+                        (!self.config.deterministic_output).then_some(Message::SetChmodOnSave),
+                    ),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-chmod-bits"),
+                    self.chmod_checkboxes(),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-normalize"),
+                    widget::toggler(self.config.normalize_on_save)
+                        .on_toggle(Message::SetNormalizeOnSave),
+                ))
+                .add(widget::settings::item(
+                    fl!("prefs-normalize-steps"),
+                    self.normalize_checkboxes(),
+                ))
+                .into(),
+            widget::settings::section()
+                .title(fl!("prefs-testing"))
+                .add(widget::settings::item(
+                    fl!("prefs-terminal"),
+                    widget::dropdown(
+                        &self.terminal_choices,
+                        self.terminal_choice(),
+                        Message::SetTerminal,
+                    ),
+                ))
+                .into(),
+            widget::settings::section()
+                .title(fl!("prefs-integration"))
+                .add(widget::settings::item(
+                    fl!("prefs-handler"),
+                    if self.desktop_handler {
+                        Element::from(widget::text::body(fl!("prefs-handler-active")))
+                    } else {
+                        widget::button::standard(fl!("action-makedefault"))
+                            .on_press(Message::RegisterHandler)
+                            .into()
+                    },
+                ))
+                .into(),
+        ])
+        .into()
+    }
+
+    // The configured terminal as an index into terminal_choices, None if no longer installed.
+    fn terminal_choice(&self) -> Option<usize> {
+        if self.config.terminal.is_empty() {
+            return Some(0);
+        }
+        self.terminal_choices
+            .iter()
+            .skip(1)
+            .position(|t| *t == self.config.terminal)
+            .map(|i| i + 1)
+    }
+
+    // The card PrefersNonDefaultGPU picks, or that there is no other card.
+    fn gpu_info(&'_ self) -> Element<'_, Message> {
+        let text = match self.gpus.as_deref() {
+            None => String::new(),
+            Some(gpus) => match gpu::non_default(gpus) {
+                Some(gpu) => fl!("gpu-discrete", name = gpu.name.as_str()),
+                None => fl!("gpu-single"),
+            },
+        };
+        widget::text::caption(text)
+            .align_x(Horizontal::Right)
+            .width(Length::Fill)
+            .into()
+    }
+
+    // The toggle stays usable when the GPUs are unknown, or to switch it back off.
+    fn gpu_choice_possible(&self, appdata: &DesktopEntry) -> bool {
+        appdata.prefers_non_default_gpu()
+            || self
+                .gpus
+                .as_deref()
+                .is_none_or(|gpus| gpu::non_default(gpus).is_some())
+    }
+
+    // One checkbox per known wrapper, usable once installed or to remove it again.
+    fn wrapper_checkboxes(&'_ self, appdata: &DesktopEntry) -> Element<'_, Message> {
+        let exec = appdata.exec().unwrap_or_default();
+        let parses = exec::split(exec).is_ok();
+
+        let mut row = widget::row().spacing(theme::active().cosmic().space_s());
+        for (index, wrapper) in WRAPPERS.iter().enumerate() {
+            let checked = wrappers::is_wrapped(exec, wrapper);
+            let installed = self.installed_wrappers.get(index).copied().unwrap_or(false);
+            let mut checkbox = widget::checkbox(wrapper.program, checked);
+            if parses && (installed || checked) {
+                checkbox = checkbox.on_toggle(move |on| Message::SetWrapper(index, on));
+            }
+            if installed {
+                row = row.push(checkbox);
+            } else {
+                row = row.push(widget::tooltip(
+                    checkbox,
+                    widget::text(fl!("wrapper-notinstalled", program = wrapper.program)),
+                    widget::tooltip::Position::Bottom,
+                ));
+            }
+        }
+        row.into()
+    }
+
+    // One checkbox per normalization, also used by Save normalized when not on for every save.
+    fn normalize_checkboxes(&'_ self) -> Element<'_, Message> {
+        let steps = [
+            (fl!("prefs-normalize-order"), normalize::SORT_KEYS),
+            (fl!("prefs-normalize-empty"), normalize::DROP_EMPTY),
+            (fl!("prefs-normalize-booleans"), normalize::BOOLEANS),
+        ];
+
+        let mut column = widget::column().spacing(cosmic::theme::active().cosmic().space_xxs());
+        for (label, step) in steps {
+            let checked = self.config.normalize_steps & step == step;
+            column = column.push(
+                widget::checkbox(label, checked)
+                    .on_toggle(move |on| Message::SetNormalizeStep(step, on)),
+            );
+        }
+        column.into()
+    }
+
+    // One checkbox per permission class, each adding that class's read/execute bits.
+    fn chmod_checkboxes(&'_ self) -> Element<'_, Message> {
+        let enabled = self.config.chmod_on_save && !self.config.deterministic_output;
+        let classes = [
+            (fl!("prefs-chmod-owner"), 0o700),
+            (fl!("prefs-chmod-group"), 0o050),
+            (fl!("prefs-chmod-others"), 0o005),
+        ];
+
+        let mut row = widget::row().spacing(cosmic::theme::active().cosmic().space_s());
+        for (label, bits) in classes {
+            let checked = self.config.chmod_bits & bits == bits;
+            let mut checkbox = widget::checkbox(label, checked);
+            if enabled {
+                checkbox = checkbox.on_toggle(move |on| Message::SetChmodBits(bits, on));
+            }
+            row = row.push(checkbox);
+        }
+        row.into()
+    }
+
+    pub fn context_review(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        if self.doc().current_entry.is_none() {
+            return horizontal_space().into();
+        }
+
+        let changed = self
+            .review_lines
+            .iter()
+            .any(|l| !matches!(l, DiffLine::Same(_)));
+
+        let mut diff = widget::column();
+        for line in &self.review_lines {
+            diff = diff.push(match line {
+                DiffLine::Same(l) => widget::text::monotext(format!("  {l}")),
+                DiffLine::Added(l) => {
+                    widget::text::monotext(format!("+ {l}")).class(theme::Text::Accent)
+                }
+                DiffLine::Removed(l) => widget::text::monotext(format!("- {l}"))
+                    .class(theme::Text::Color(WARNING_COLOR)),
+            });
+        }
 
-        let icon_button = container(self.get_icon_button())
-            .width(60)
-            .height(60)
-            .align_y(Center)
-            .align_x(Center);
+        let save = if changed {
+            widget::button::suggested(fl!("menu-save")).on_press(Message::Save)
+        } else {
+            widget::button::suggested(fl!("menu-save"))
+        };
 
-        let c = column!(icon_button, list, widget::text(location)).spacing(20);
-        widget::scrollable(c).into()
+        column!(
+            widget::text::body(if changed {
+                fl!("context-review-expl")
+            } else {
+                fl!("context-review-nochanges")
+            }),
+            widget::scrollable(diff).height(Length::Fill),
+            row!(horizontal_space(), save),
+        )
+        .spacing(space_xxs)
+        .into()
     }
 
-    fn view_tab_advanced<'a>(
+    pub fn context_history(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        if self.history.is_empty() {
+            return widget::text::body(fl!("context-history-none")).into();
+        }
+
+        let mut versions = widget::column().spacing(space_xxs);
+        for (i, version) in self.history.iter().enumerate() {
+            let label = version.label();
+            versions = versions.push(if self.history_selected == Some(i) {
+                widget::button::suggested(label)
+            } else {
+                widget::button::standard(label).on_press(Message::SelectVersion(i))
+            });
+        }
+
+        let mut page =
+            column!(widget::text::body(fl!("context-history-expl")), versions).spacing(space_xxs);
+
+        // The selected version against the current edits, + lines come back on restore
+        if self.history_selected.is_some()
+            && let Some(entry) = &self.doc().current_entry
+        {
+            let mut diff = widget::column();
+            for line in line_diff(&entry.to_string(), &self.history_text) {
+                diff = diff.push(match line {
+                    DiffLine::Same(l) => widget::text::monotext(format!("  {l}")),
+                    DiffLine::Added(l) => {
+                        widget::text::monotext(format!("+ {l}")).class(theme::Text::Accent)
+                    }
+                    DiffLine::Removed(l) => widget::text::monotext(format!("- {l}"))
+                        .class(theme::Text::Color(WARNING_COLOR)),
+                });
+            }
+            page = page
+                .push(widget::scrollable(diff).height(Length::Fill))
+                .push(row!(
+                    horizontal_space(),
+                    widget::button::suggested(fl!("action-restoreversion"))
+                        .on_press(Message::RestoreVersion)
+                ));
+        }
+        page.into()
+    }
+
+    // Not set, true or false for a boolean key; leaving it unset removes the key.
+    fn bool_choice<'a>(
         &'a self,
-        appdata: &'a DesktopEntry,
-    ) -> Element<'a, crate::app::Message> {
-        let label_w = 160;
-        let locales = &self.locales;
-        let folder = widget::icon::from_name("folder-symbolic").handle();
+        doc: &Document,
+        key: DesktopKey,
+        enabled: bool,
+    ) -> Element<'a, Message> {
+        // Nothing selected for a value that's neither true nor false
+        let selected = match doc.bool_value(&key) {
+            _ if !doc.has_key(&key) => Some(0),
+            Some(true) => Some(1),
+            Some(false) => Some(2),
+            None => None,
+        };
+        if !enabled {
+            let choice = selected.map_or("", |i| self.bool_choices[i].as_str());
+            return widget::text(choice).into();
+        }
+        widget::dropdown(&self.bool_choices, selected, move |i| {
+            Message::SetBoolEntry(key.clone(), [None, Some(true), Some(false)][i])
+        })
+        .into()
+    }
 
-        let list = list::ListColumn::new()
-            .add(
-                row!(
-                    widget::text(fl!("field-genericname"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::GenericName,
-                        fl!("hint-genericname"),
-                        appdata
-                            .generic_name(locales)
-                            .unwrap_or_default()
-                            .into_owned(),
-                        self.am_editing.generic_name,
-                        self
-                    )
-                    .width(Length::Fill)
+    // Undo button for a field whose value differs from the file on disk.
+    fn revert_button<'a>(doc: &Document, key: DesktopKey) -> Option<Element<'a, Message>> {
+        if !doc.key_modified(&key) {
+            return None;
+        }
+        Some(
+            widget::tooltip(
+                named(
+                    widget::button::icon(widget::icon::from_name("edit-undo-symbolic")),
+                    fl!("action-revertkey"),
                 )
-                .align_y(Center)
-                .spacing(5),
+                .on_press(Message::RevertKey(key)),
+                widget::text(fl!("action-revertkey")),
+                widget::tooltip::Position::Bottom,
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-tryexec"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::TryExec,
-                        fl!("hint-tryexec"),
-                        appdata.try_exec().unwrap_or_default(),
-                        self.am_editing.try_exec,
-                        self
-                    ),
-                    widget::button::icon(folder.clone())
-                        .on_press(Message::OpenPath(PickKind::TryExecutable)),
+            .into(),
+        )
+    }
+
+    // Deleting the key differs from clearing the field, which leaves `Key=` behind.
+    fn remove_key_button<'a>(doc: &Document, key: DesktopKey) -> Option<Element<'a, Message>> {
+        if !doc.has_key(&key) {
+            return None;
+        }
+        let label = fl!("action-removekey", key = key.to_string());
+        Some(
+            widget::tooltip(
+                named(
+                    widget::button::icon(widget::icon::from_name("edit-delete-symbolic")),
+                    label.clone(),
                 )
-                .align_y(Center)
-                .spacing(5),
+                .on_press(Message::RemoveKey(key)),
+                widget::text(label),
+                widget::tooltip::Position::Bottom,
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-onlyshownin"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::OnlyShowIn,
-                        fl!("hint-onlyshownin"),
-                        appdata
-                            .only_show_in()
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.only_shown_in,
-                        self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+            .into(),
+        )
+    }
+
+    // Which variant a translated field edits, the translation in the user's language
+    // or the untranslated value, and a click switches to the other.
+    fn translation_switch<'a>(
+        &'a self,
+        doc: &Document,
+        key: DesktopKey,
+    ) -> Option<Element<'a, Message>> {
+        let locale = doc.shown_translation(&key, &self.locales)?;
+        let (label, tooltip) = if doc.edit_untranslated {
+            (
+                fl!("field-untranslated"),
+                fl!("action-edittranslation", locale = locale),
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-notshownin"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::NotShowIn,
-                        fl!("hint-notshownin"),
-                        appdata
-                            .not_show_in()
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.not_shown_in,
-                        self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+        } else {
+            (
+                locale.clone(),
+                fl!("action-edituntranslated", locale = locale),
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-keywords"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Keywords,
-                        fl!("hint-keywords"),
-                        appdata
-                            .keywords(locales)
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.keywords,
-                        self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+        };
+        Some(
+            widget::tooltip(
+                widget::button::text(label).on_press(Message::ToggleEditUntranslated),
+                widget::text(tooltip),
+                widget::tooltip::Position::Bottom,
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-categories"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Categories,
-                        fl!("hint-categories"),
-                        appdata
-                            .categories()
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.categories,
-                        self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+            .into(),
+        )
+    }
+
+    fn translate_button<'a>(key: DesktopKey) -> Element<'a, Message> {
+        widget::tooltip(
+            named(
+                widget::button::icon(widget::icon::from_name(
+                    "preferences-desktop-locale-symbolic",
+                )),
+                fl!("action-translations"),
             )
-            .add(
+            .on_press(Message::ToggleContextPage(ContextPage::Translations(
+                key.to_string(),
+            ))),
+            widget::text(fl!("action-translations")),
+            widget::tooltip::Position::Bottom,
+        )
+        .into()
+    }
+
+    pub fn context_translations<'a>(&'a self, key: &'a str) -> Element<'a, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+        let doc = self.doc();
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+
+        let untranslated = doc
+            .current_entry
+            .as_ref()
+            .and_then(|e| e.desktop_entry(key))
+            .map_or_else(|| fl!("value-unset"), ToString::to_string);
+
+        let mut list = list::ListColumn::new().add(column!(
+            widget::text::heading(fl!("translations-default")),
+            widget::text::body(untranslated),
+        ));
+        for (locale, value) in doc.translations(key) {
+            let (set_key, set_locale) = (key.to_string(), locale.clone());
+            list = list.add(
                 row!(
-                    widget::text(fl!("field-implements"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::Implements,
-                        fl!("hint-implements"),
-                        appdata
-                            .implements()
-                            .map(|v| v.join(";"))
-                            .unwrap_or_default(),
-                        self.am_editing.implements,
-                        self
-                    )
-                    .width(Length::Fill)
+                    widget::text::body(locale.clone()).width(80),
+                    widget::text_input("", value)
+                        .on_input(move |v| {
+                            Message::SetTranslation(set_key.clone(), set_locale.clone(), v)
+                        })
+                        .width(Length::Fill),
+                    widget::button::icon(delete.clone())
+                        .on_press(Message::RemoveTranslation(key.to_string(), locale)),
                 )
                 .align_y(Center)
-                .spacing(5),
+                .spacing(space_xxs),
+            );
+        }
+
+        let add_key = key.to_string();
+        column!(
+            list,
+            row!(
+                widget::text::body(fl!("translations-add")).width(Length::Fill),
+                widget::dropdown(TRANSLATION_LOCALES, None, move |i| {
+                    Message::AddTranslation(add_key.clone(), TRANSLATION_LOCALES[i].to_string())
+                }),
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-startupwmclass"))
-                        .align_x(Left)
-                        .width(label_w),
-                    desktop_edit_field!(
-                        DesktopKey::StartupWMClass,
-                        "",
-                        appdata.startup_wm_class().unwrap_or_default(),
-                        self.am_editing.startupwmclass,
-                        self
-                    )
-                    .width(Length::Fill)
-                )
-                .align_y(Center)
-                .spacing(5),
+            .align_y(Center)
+            .spacing(space_xxs),
+        )
+        .spacing(space_s)
+        .into()
+    }
+
+    fn dbus_warnings<'a>(doc: &Document, entry: &DesktopEntry) -> Element<'a, Message> {
+        let warnings = if entry.dbus_activatable() {
+            dbus_activation_warnings(
+                doc.current_entry_path.as_deref(),
+                doc.dbus_service.as_deref(),
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-startupnotify"))
-                        .align_x(Left)
-                        .width(label_w),
-                    horizontal_space(),
-                    widget::toggler(appdata.startup_notify())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::StartupNotify, b)),
-                )
-                .align_y(Center)
-                .spacing(5),
+        } else {
+            Vec::new()
+        };
+        widget::column()
+            .extend(warnings.into_iter().map(Self::warning))
+            .into()
+    }
+
+    // Programs in $PATH the first Exec token could be completed to, while editing it.
+    fn exec_completions<'a>(&self, doc: &Document, entry: &DesktopEntry) -> Element<'a, Message> {
+        const MAX_COMPLETIONS: usize = 6;
+
+        if !doc.am_editing.exec {
+            return widget::row().into();
+        }
+        let current = doc
+            .staged_text(&DesktopKey::Exec)
+            .or(entry.exec())
+            .unwrap_or_default();
+        let buttons = exec::complete_program(&self.path_programs, current, MAX_COMPLETIONS)
+            .into_iter()
+            .map(|completed| {
+                let program = completed
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                widget::button::text(program)
+                    .on_press(Message::SetTextEntry(DesktopKey::Exec, completed))
+                    .into()
+            });
+        widget::row().extend(buttons).spacing(5).into()
+    }
+
+    // Problems with the Exec line, shown under the field as it's edited.
+    fn exec_warnings<'a>(doc: &Document) -> Element<'a, Message> {
+        widget::column()
+            .extend(
+                doc.exec_problems
+                    .iter()
+                    .map(|p| Self::warning(p.to_string())),
             )
-            .add(
-                row!(
-                    widget::text(fl!("field-hidden"))
-                        .align_x(Left)
-                        .width(label_w),
-                    horizontal_space(),
-                    widget::toggler(appdata.hidden())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::Hidden, b)),
-                )
+            .into()
+    }
+
+    // Labels sit above their inputs on condensed widths, where a fixed label column
+    // plus input plus buttons doesn't fit.
+    fn stacked_fields(&self) -> bool {
+        self.core.is_condensed()
+    }
+
+    // A field's label next to its input, or above it when stacked.
+    fn field_row<'a>(
+        &self,
+        label: impl Into<Element<'a, Message>>,
+        input: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        if self.stacked_fields() {
+            column!(label.into(), input.into()).spacing(2).into()
+        } else {
+            row!(label.into(), input.into())
                 .align_y(Center)
-                .spacing(5),
-            )
-            .add(
-                row!(
-                    widget::text(fl!("field-singlemainwindow"))
-                        .align_x(Left)
-                        .width(label_w),
+                .spacing(5)
+                .into()
+        }
+    }
+
+    // A field's label with a button explaining its key.
+    fn field_label<'a>(
+        &'a self,
+        label: String,
+        key: DesktopKey,
+        width: u16,
+    ) -> Element<'a, Message> {
+        let width = if self.stacked_fields() {
+            Length::Shrink
+        } else {
+            Length::Fixed(width.into())
+        };
+        let Some(explanation) = help::explain(&key) else {
+            return widget::text(label).align_x(Left).width(width).into();
+        };
+        let open = self.help_key.as_deref() == Some(key.key_str().as_ref());
+
+        let button = named(
+            widget::button::icon(widget::icon::from_name("help-info-symbolic")).extra_small(),
+            fl!("a11y-help", field = key.to_string()),
+        )
+        .on_press(Message::ToggleHelp(key.clone()));
+        let mut popover = widget::popover(button).on_close(Message::ToggleHelp(key.clone()));
+        if open {
+            let cosmic_theme::Spacing {
+                space_xxs, space_s, ..
+            } = theme::active().cosmic().spacing;
+            popover = popover.popup(
+                column!(
+                    widget::text::heading(key.to_string()),
+                    widget::text::body(explanation),
+                )
+                .spacing(space_xxs)
+                .padding(space_s)
+                .width(320)
+                .apply(widget::container)
+                .class(cosmic::style::Container::Dropdown),
+            );
+        }
+
+        row!(widget::text(label).align_x(Left), popover)
+            .align_y(Center)
+            .spacing(2)
+            .width(width)
+            .into()
+    }
+
+    fn exec_help_button(&'_ self) -> Element<'_, Message> {
+        let button = widget::button::icon(widget::icon::from_name("help-about-symbolic"))
+            .on_press(Message::ToggleExecHelp);
+        let mut popover = widget::popover(button).on_close(Message::ToggleExecHelp);
+
+        if self.exec_help {
+            let cosmic_theme::Spacing {
+                space_xxs, space_s, ..
+            } = theme::active().cosmic().spacing;
+            let mut codes = widget::column().spacing(space_xxs);
+            for code in exec::FIELD_CODES {
+                codes = codes.push(
+                    row!(
+                        widget::text::monotext(*code).width(32),
+                        widget::text::body(field_code_help(code)),
+                    )
+                    .spacing(space_s),
+                );
+            }
+            popover = popover.popup(
+                column!(widget::text::heading(fl!("fieldcodes-title")), codes)
+                    .spacing(space_s)
+                    .padding(space_s)
+                    .width(360)
+                    .apply(widget::container)
+                    .class(cosmic::style::Container::Dropdown),
+            );
+        }
+        popover.into()
+    }
+
+    pub fn context_systemd(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let doc = self.doc();
+        let (Some(entry), Some(path)) = (&doc.current_entry, &doc.current_entry_path) else {
+            return widget::text::body(fl!("context-systemd-unsaved")).into();
+        };
+        let unit_path = systemd::unit_path(path).unwrap_or_default();
+
+        let page = column!(
+            widget::text::body(fl!("context-systemd-expl")),
+            widget::text::caption(unit_path.display().to_string()),
+        )
+        .spacing(space_xxs);
+
+        if let Some(unit) = &self.systemd_unit {
+            let state = if unit.enabled {
+                fl!("systemd-enabled")
+            } else {
+                fl!("systemd-disabled")
+            };
+            return page
+                .push(widget::text::heading(fl!("systemd-existing")))
+                .push(widget::text::monotext(unit.exec()))
+                .push(widget::text::caption(state))
+                .push(row!(
+                    horizontal_space(),
+                    widget::button::suggested(fl!("action-fromunit"))
+                        .on_press(Message::ConvertFromUnit)
+                ))
+                .into();
+        }
+
+        let state = if systemd::autostart_enabled(entry) {
+            fl!("systemd-willenable")
+        } else {
+            fl!("systemd-willdisable")
+        };
+        let page = page.push(widget::text::heading(fl!("systemd-preview")));
+        match systemd::unit_text(entry, path, &self.locales) {
+            Ok(text) => page
+                .push(widget::text::monotext(text))
+                .push(widget::text::caption(state))
+                .push(row!(
                     horizontal_space(),
-                    widget::toggler(appdata.single_main_window())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::SingleMainWindow, b)),
-                )
-                .align_y(Center)
-                .spacing(5),
+                    widget::button::suggested(fl!("action-tounit"))
+                        .on_press(Message::ConvertToUnit)
+                ))
+                .into(),
+            Err(e) => page.push(Self::warning(e.to_string())).into(),
+        }
+    }
+
+    pub fn context_open_test(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let pick = widget::button::standard(fl!("action-pickfile"))
+            .on_press(Message::OpenPath(PickKind::SampleFile));
+        let page = column!(widget::text::body(fl!("context-opentest-expl"))).spacing(space_xxs);
+        let Some(test) = &self.open_test else {
+            return page.push(row!(horizontal_space(), pick)).into();
+        };
+
+        let description = self.mime_descriptions.lookup(&test.mime);
+        let mut page = page
+            .push(widget::text::caption(test.file.display().to_string()))
+            .push(widget::text::heading(match description {
+                Some(d) => format!("{} ({d})", test.mime),
+                None => test.mime.clone(),
+            }))
+            .push(widget::text::heading(match test.winner() {
+                Some(id) => fl!("opentest-winner", id = id),
+                None => fl!("opentest-nowinner"),
+            }))
+            .push(widget::text::body(fl!("opentest-order")));
+
+        for candidate in &test.candidates {
+            let source = match &candidate.source {
+                Source::Default(path) => {
+                    fl!("opentest-default", path = path.display().to_string())
+                }
+                Source::Added(path) => fl!("opentest-added", path = path.display().to_string()),
+                Source::Declared => fl!("opentest-declared"),
+            };
+            let verdict = match &candidate.verdict {
+                Verdict::Opens => fl!("opentest-opens"),
+                Verdict::Fallback => fl!("opentest-fallback"),
+                Verdict::NotInstalled => fl!("opentest-notinstalled"),
+                Verdict::Removed(path) => {
+                    fl!("opentest-removed", path = path.display().to_string())
+                }
+            };
+            let id = widget::text::monotext(candidate.id.clone());
+            let id = if candidate.verdict == Verdict::Opens {
+                id.class(theme::Text::Accent)
+            } else {
+                id
+            };
+            page = page.push(
+                column!(id, widget::text::caption(format!("{source} · {verdict}")))
+                    .padding(cosmic::iced::Padding::ZERO.left(space_xxs)),
+            );
+        }
+        if test.candidates.is_empty() {
+            page = page.push(widget::text::caption(fl!("opentest-none")));
+        }
+
+        page.push(
+            row!(
+                widget::button::standard(fl!("menu-mimeapps"))
+                    .on_press(Message::OpenMimeAppsEditor),
+                horizontal_space(),
+                widget::button::standard(fl!("action-runagain")).on_press(Message::RerunOpenTest),
+                pick,
             )
-            .add(
+            .spacing(space_xxs),
+        )
+        .into()
+    }
+
+    pub fn context_running_apps(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let probe: Element<'_, Message> = match &self.wm_probe {
+            Some(None) => widget::text::body(fl!("runningapps-clickwindow")).into(),
+            probe => {
+                let button = widget::button::standard(fl!("action-probewmclass"))
+                    .on_press(Message::ProbeWMClass);
+                match probe {
+                    Some(Some(e)) => column!(button, Self::warning(e.clone()))
+                        .spacing(space_xxs)
+                        .into(),
+                    _ => button.into(),
+                }
+            }
+        };
+
+        let apps = match &self.running_apps {
+            None => return column!(widget::text::body(fl!("runningapps-listing")), probe).into(),
+            Some(Err(e)) => return column!(Self::warning(e.clone()), probe).into(),
+            Some(Ok(apps)) if apps.is_empty() => {
+                return column!(widget::text::body(fl!("runningapps-none")), probe).into();
+            }
+            Some(Ok(apps)) => apps,
+        };
+
+        let mut list = list::ListColumn::new();
+        for app in apps {
+            list = list.add(
                 row!(
-                    widget::text(fl!("field-dbusactivation"))
-                        .align_x(Left)
-                        .width(label_w),
-                    horizontal_space(),
-                    widget::toggler(appdata.dbus_activatable())
-                        .on_toggle(|b| Message::SetBoolEntry(DesktopKey::DBusActivatable, b)),
+                    column!(
+                        widget::text::heading(app.app_id.clone()),
+                        widget::text::caption(app.title.clone()),
+                    )
+                    .spacing(space_xxs)
+                    .width(Length::Fill),
+                    widget::button::standard(fl!("action-use"))
+                        .on_press(Message::PickRunningApp(app.app_id.clone())),
                 )
                 .align_y(Center)
-                .spacing(5),
+                .spacing(space_xxs),
             );
-
-        let ctrl = widget::scrollable::vertical(list);
-        ctrl.into()
+        }
+        column!(widget::text::body(fl!("runningapps-expl")), list, probe)
+            .spacing(space_xxs)
+            .into()
     }
 
-    fn changed(&mut self) {
-        self.current_entry_changed = true;
-    }
+    pub fn context_test_launch(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let (command, result) = match &self.test_launch {
+            TestLaunch::Idle => return widget::column().into(),
+            TestLaunch::Running(command) => {
+                return column!(
+                    widget::text::monotext(command.clone()),
+                    widget::text::body(fl!("testlaunch-running")),
+                )
+                .spacing(space_s)
+                .into();
+            }
+            TestLaunch::Finished(command, result) => (command, result),
+        };
 
-    pub fn set_text(&mut self, key: DesktopKey, text: impl Into<String>) {
-        if let Some(entry) = &mut self.current_entry {
-            entry.add_desktop_entry(key.to_string(), text.into());
-            self.changed();
+        let mut page = widget::column().spacing(space_s);
+        if !command.is_empty() {
+            page = page.push(widget::text::monotext(command.clone()));
+        }
+        match result {
+            Err(e) => page = page.push(Self::warning(e.clone())),
+            Ok(output) => {
+                let status = match output.status {
+                    Some(0) => widget::text::heading(fl!("testlaunch-success")).into(),
+                    Some(code) => Self::warning(fl!("testlaunch-exitcode", code = code)),
+                    None => Self::warning(fl!("testlaunch-signal")),
+                };
+                page = page.push(status);
+                for (title, text) in [
+                    (fl!("testlaunch-stdout"), &output.stdout),
+                    (fl!("testlaunch-stderr"), &output.stderr),
+                ] {
+                    if !text.is_empty() {
+                        page = page.push(
+                            column!(
+                                widget::text::heading(title),
+                                widget::text::monotext(text.clone())
+                            )
+                            .spacing(space_xxs),
+                        );
+                    }
+                }
+            }
         }
-    }
 
-    pub fn set_bool(&mut self, key: DesktopKey, value: bool) {
-        self.set_text(key, if value { "true" } else { "false" });
-        self.changed();
+        page.push(row!(
+            horizontal_space(),
+            widget::button::standard(fl!("action-runagain")).on_press(Message::TestLaunch)
+        ))
+        .into()
     }
 
-    pub fn set_list<S: AsRef<str>>(&mut self, key: DesktopKey, items: &[S]) {
-        let s = items
-            .iter()
-            .map(std::convert::AsRef::as_ref)
-            .collect::<Vec<_>>()
-            .join(";");
-        // Many tools tolerate missing trailing ';', add if you prefer:
-        // let s = format!("{s};");
-        self.set_text(key, s);
-        self.changed();
-    }
+    // Command line and working directory for running the current entry without files.
+    fn prepare_test_launch(&self) -> Result<(Vec<String>, Option<PathBuf>), String> {
+        let doc = self.doc();
+        let Some(entry) = &doc.current_entry else {
+            return Err(fl!("testlaunch-noexec"));
+        };
+        let Some(exec) = entry.exec() else {
+            return Err(fl!("testlaunch-noexec"));
+        };
+
+        let ctx = LaunchContext {
+            icon: entry.icon().map(ToString::to_string),
+            name: entry.name(&self.locales).map(Cow::into_owned),
+            desktop_file: doc
+                .current_entry_path
+                .as_ref()
+                .map(|p| p.display().to_string()),
+        };
+        let mut args = exec::expand_field_codes(exec, &ctx).map_err(|e| e.to_string())?;
+
+        if entry.terminal() {
+            let mut terminal = exec::terminal_command(&self.config.terminal)
+                .ok_or_else(|| fl!("testlaunch-noterminal"))?;
+            terminal.append(&mut args);
+            args = terminal;
+        }
 
-    pub fn set_path(&mut self, path: &Path) {
-        let p = path.display().to_string();
-        let needs_quotes = p.contains(' ');
-        let val = if needs_quotes { format!("\"{p}\"") } else { p };
-        self.set_text(DesktopKey::Path, val);
-        self.changed();
+        Ok((args, doc.working_dir()))
     }
 
-    pub fn set_exec_with_args(&mut self, exe: &Path, kind: PickKind, args: Option<&str>) {
-        let exe_str = exe.display().to_string();
+    pub fn context_exec_builder(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+        let builder = &self.exec_builder;
+        let delete = widget::icon::from_name("edit-delete-symbolic").handle();
+
+        // One cell per argument, typed without quotes or escapes
+        let mut args = list::ListColumn::new();
+        for (i, arg) in builder.args.iter().enumerate() {
+            args = args.add(
+                row!(
+                    widget::text::caption(format!("{}", i + 1)).width(24),
+                    widget::text_input(fl!("hint-execarg"), arg)
+                        .on_input(move |a| Message::ExecBuilderArg(i, a))
+                        .width(Length::Fill),
+                    named(
+                        widget::button::icon(delete.clone()),
+                        fl!("a11y-remove", item = arg.clone()),
+                    )
+                    .on_press(Message::ExecBuilderRemoveArg(i)),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+        }
+        args = args.add(row!(
+            horizontal_space(),
+            widget::button::standard(fl!("action-addarg")).on_press(Message::ExecBuilderAddArg),
+        ));
+
+        let mut page = column!(
+            widget::text::heading(fl!("execbuilder-program")),
+            widget::text_input(fl!("hint-execprogram"), &builder.program)
+                .on_input(Message::ExecBuilderProgram),
+            widget::text::heading(fl!("execbuilder-args")),
+            args,
+            widget::text::heading(fl!("execbuilder-files")),
+            widget::dropdown(
+                &self.file_code_choices,
+                Some(builder.file_code.map_or(0, |i| i + 1)),
+                Message::ExecBuilderFileCode,
+            ),
+            widget::text::heading(fl!("execbuilder-result")),
+            widget::text::monotext(builder.to_exec()),
+        )
+        .spacing(space_xxs);
+        if let Some(e) = &builder.unparsed {
+            let error = e.to_string();
+            page = page.push(Self::warning(fl!("execbuilder-unparsed", error = error)));
+        }
+        page.push(row!(
+            horizontal_space(),
+            widget::button::suggested(fl!("action-apply")).on_press(Message::ApplyExecBuilder)
+        ))
+        .into()
+    }
 
-        // Quote the path if it contains spaces
-        let quoted = if exe_str.contains(' ') {
-            format!("\"{exe_str}\"")
-        } else {
-            exe_str
+    pub fn context_show_in(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+        let doc = self.doc();
+        let only = doc.list(&DesktopKey::OnlyShowIn);
+        let not = doc.list(&DesktopKey::NotShowIn);
+
+        // Setting one list locks the other, the spec allows only one of them
+        let boxes = |key: DesktopKey, current: &[String], locked: bool| {
+            let mut names: Vec<String> = categories::DESKTOPS
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            names.extend(current.iter().filter(|d| !names.contains(d)).cloned());
+
+            let checkboxes = names
+                .into_iter()
+                .map(|name| {
+                    let mut checkbox = widget::checkbox(name.clone(), current.contains(&name));
+                    if !locked {
+                        let key = key.clone();
+                        checkbox = checkbox
+                            .on_toggle(move |on| Message::SetShowIn(key.clone(), name.clone(), on));
+                    }
+                    checkbox.into()
+                })
+                .collect();
+            widget::flex_row(checkboxes)
+                .row_spacing(space_xxs)
+                .column_spacing(space_s)
         };
 
-        // Combine executable + args only if args are provided
-        let cmd = match args {
-            Some(arg) if !arg.is_empty() => format!("{quoted} {arg}"),
-            _ => quoted,
+        let mut page = widget::column().spacing(space_s);
+        if doc.show_in_conflict() {
+            page = page.push(Self::warning(fl!("showin-conflict")));
+        }
+        page.push(widget::text::heading(fl!("field-onlyshownin")))
+            .push(boxes(DesktopKey::OnlyShowIn, &only, !not.is_empty()))
+            .push(widget::text::heading(fl!("field-notshownin")))
+            .push(boxes(DesktopKey::NotShowIn, &not, !only.is_empty()))
+            .into()
+    }
+
+    pub fn context_categories(&'_ self) -> Element<'_, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+        let current: Vec<String> = self
+            .doc()
+            .current_entry
+            .as_ref()
+            .and_then(DesktopEntry::categories)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| !c.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        let boxes = |names: Vec<String>| {
+            let checkboxes = names
+                .into_iter()
+                .map(|name| {
+                    let checked = current.contains(&name);
+                    widget::checkbox(name.clone(), checked)
+                        .on_toggle(move |on| Message::SetCategory(name.clone(), on))
+                        .into()
+                })
+                .collect();
+            widget::flex_row(checkboxes)
+                .row_spacing(space_xxs)
+                .column_spacing(space_s)
         };
 
-        if kind == PickKind::TryExecutable {
-            self.set_text(DesktopKey::TryExec, cmd);
-        } else {
-            self.set_text(DesktopKey::Exec, cmd);
+        let mut page = widget::column().spacing(space_s);
+        if !current.iter().any(|c| categories::is_main(c)) {
+            page = page.push(Self::warning(fl!("categories-nomain")));
+        }
+        page = page
+            .push(widget::text::heading(fl!("categories-main")))
+            .push(boxes(
+                categories::MAIN.iter().map(ToString::to_string).collect(),
+            ))
+            .push(widget::text::heading(fl!("categories-additional")))
+            .push(boxes(
+                categories::ADDITIONAL
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            ));
+
+        // Vendor extensions and typos stay visible so they can be removed
+        let other: Vec<String> = current
+            .iter()
+            .filter(|c| !categories::is_main(c) && !categories::ADDITIONAL.contains(&c.as_str()))
+            .cloned()
+            .collect();
+        if !other.is_empty() {
+            page = page
+                .push(widget::text::heading(fl!("categories-other")))
+                .push(boxes(other));
         }
-        self.changed();
+
+        page.into()
     }
 
-    pub fn context_about(&'_ self) -> Element<'_, Message> {
+    pub fn context_overrides(&'_ self) -> Element<'_, Message> {
         let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let doc = self.doc();
+        let keys = doc.override_merge();
 
-        let icon = widget::svg(widget::svg::Handle::from_memory(APP_ICON));
+        if keys.is_empty() {
+            return widget::text::body(fl!("context-overrides-none")).into();
+        }
 
-        let title = widget::text::title3(fl!("app-title"));
+        let unset = || fl!("value-unset");
+        let mut list = list::ListColumn::new();
+        for key in &keys {
+            let name = if key.group == "Desktop Entry" {
+                key.key.clone()
+            } else {
+                format!("[{}] {}", key.group, key.key)
+            };
+            let state = match key.state {
+                MergeState::Upstream => Some(fl!("merge-upstream")),
+                MergeState::Conflict => Some(fl!("merge-conflict")),
+                MergeState::Mine => Some(fl!("merge-mine")),
+                MergeState::Differs => None,
+            };
 
-        let link = widget::button::link(REPOSITORY)
-            .on_press(Message::OpenRepositoryUrl)
-            .padding(0);
+            let mut values = column!(widget::text::heading(name))
+                .push_maybe(state.map(widget::text::caption))
+                .spacing(space_xxs)
+                .width(Length::Fill);
+            // The base only adds information when both sides moved away from it
+            if key.state == MergeState::Conflict {
+                let base = key.base.clone().unwrap_or_else(unset);
+                values = values.push(widget::text::caption(fl!("override-base", value = base)));
+            }
+            let system = key.system.clone().unwrap_or_else(unset);
+            let mine = key.mine.clone().unwrap_or_else(unset);
+            values = values
+                .push(widget::text::caption(fl!(
+                    "override-system",
+                    value = system
+                )))
+                .push(widget::text::caption(fl!("override-mine", value = mine)));
+
+            list = list.add(
+                row!(
+                    values,
+                    widget::button::standard(fl!("action-takesystem"))
+                        .on_press(Message::ResetToSystem(key.group.clone(), key.key.clone())),
+                )
+                .align_y(Center)
+                .spacing(space_xxs),
+            );
+        }
 
-        let version = format!("Version {}.", env!("CARGO_PKG_VERSION"));
+        let upstream = keys
+            .iter()
+            .filter(|k| k.state == MergeState::Upstream)
+            .count();
+        let mut take_all = widget::button::standard(fl!("action-takeupstream", count = upstream));
+        if upstream > 0 {
+            take_all = take_all.on_press(Message::TakeUpstream);
+        }
 
-        widget::column()
-            .push(icon)
-            .push(title)
-            .push(link)
-            .push(widget::text::body(version))
-            .align_x(Alignment::Center)
-            .spacing(space_xxs)
-            .into()
+        column!(
+            widget::text::body(if doc.override_base.is_some() {
+                fl!("context-overrides-expl")
+            } else {
+                fl!("context-overrides-nobase")
+            }),
+            list,
+            row!(
+                horizontal_space(),
+                take_all,
+                widget::button::standard(fl!("action-markmerged")).on_press(Message::MarkMerged),
+            )
+            .spacing(space_xxs),
+        )
+        .spacing(space_xxs)
+        .into()
     }
 
     pub fn context_ioerror(&'_ self, error: &str) -> Element<'_, Message> {
@@ -1839,7 +6531,7 @@ impl AppModel {
         }
     }
 
-    fn create_nav_bar(&mut self) {
+    fn create_nav_bar(doc: &mut Document) {
         let mut nav = nav_bar::Model::default();
 
         nav.insert()
@@ -1848,7 +6540,13 @@ impl AppModel {
             .icon(icon::from_svg_bytes(GENERAL_ICON).symbolic(true).icon())
             .activate();
 
-        if let Some(t) = self.entry_type()
+        // Thumbnailers only have the program and the types it handles
+        if doc.thumbnailer {
+            nav.insert()
+                .text(fl!("nav-mimetypes"))
+                .data::<NavPage>(NavPage::Mimetypes)
+                .icon(icon::from_svg_bytes(MIMETYPES_ICON).symbolic(true).icon());
+        } else if let Some(t) = doc.entry_type()
             && t == DesktopEntryType::Application
         {
             nav.insert()
@@ -1874,161 +6572,382 @@ impl AppModel {
 
         nav.activate_position(0);
 
-        self.nav = nav;
+        doc.nav = nav;
     }
 
-    fn create_mimetype(&mut self, mimetype: &str) {
-        if let Some(entry) = &mut self.current_entry {
-            // Make new list, including new one
-            let mut mimes = vec![mimetype.to_string()];
-            if let Some(existing) = entry.mime_type() {
-                mimes.extend(
-                    existing
-                        .iter()
-                        .filter(|s| !s.is_empty())
-                        .map(ToString::to_string),
-                );
-            }
-            // Update desktop entry
-            self.set_list(DesktopKey::MimeType, &mimes);
-
-            // Update table
-            let description = self
-                .mime_descriptions
-                .lookup(mimetype)
-                .cloned()
-                .unwrap_or_default();
-            let _ = self.mime_table.insert(MimeItem {
-                name: mimetype.to_owned(),
-                description,
-            });
+    // Show `page` if the entry has that tab.
+    fn activate_nav_page(doc: &mut Document, page: NavPage) {
+        let found = doc
+            .nav
+            .iter()
+            .find(|id| doc.nav.data::<NavPage>(*id) == Some(&page));
+        if let Some(id) = found {
+            doc.nav.activate(id);
         }
     }
 
-    fn create_xkey(&mut self, xkey_item: &XKeyItem) {
-        self.set_text(
-            DesktopKey::Unknown(xkey_item.name.clone()),
-            xkey_item.value.clone(),
-        );
-        let _ = self.xkey_table.insert(xkey_item.clone());
+    fn remember_recent(&mut self, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        let recent = self.config.recent_files_with(&path);
+        self.set_recent_files(recent);
     }
 
-    fn clear_all(&mut self) {
-        self.current_entry = None;
-        self.current_entry_path = None;
-        self.current_entry_error = None;
-        self.mime_table.clear();
-        self.xkey_table.clear();
-        self.dialog_data = None;
+    fn set_recent_files(&mut self, recent: Vec<PathBuf>) {
+        set_config!(self, set_recent_files, recent_files, recent);
     }
 
-    fn entry_type(&self) -> Option<DesktopEntryType> {
-        self.current_entry
-            .as_ref()
-            .and_then(|e| e.type_())
-            .and_then(|s| s.parse::<DesktopEntryType>().ok())
+    // The current entry as written to disk, normalized when set up in Preferences
+    // or asked for with `normalize`. Deterministic output always sorts the keys.
+    fn save_contents(&mut self, normalize: bool) -> String {
+        let doc = self.doc_mut();
+        doc.finish_edits();
+        doc.save_warnings = doc
+            .current_entry
+            .as_mut()
+            .map(Self::tidy_lists)
+            .unwrap_or_default();
+        let Some((entry, contents)) = self.text_to_write(self.doc(), normalize) else {
+            return String::new();
+        };
+        // The entry becomes what was written, so it matches the file again
+        self.doc_mut().current_entry = Some(entry);
+        contents
+    }
+
+    // The document's entry as Save normalizes it, and the text written for it.
+    // The document is left alone, the review drawer shows the same text.
+    fn text_to_write(&self, doc: &Document, normalize: bool) -> Option<(DesktopEntry, String)> {
+        let mut entry = doc.current_entry.clone()?;
+        let mut steps = if normalize || self.config.normalize_on_save {
+            self.config.normalize_steps
+        } else {
+            0
+        };
+        let deterministic = self.config.deterministic_output;
+        if deterministic {
+            steps |= normalize::SORT_KEYS;
+        }
+        normalize::tidy_lists(&mut entry);
+        let mut contents = normalize::apply(&mut entry, steps);
+        if doc.thumbnailer {
+            contents = thumbnailer::file_text(&contents);
+        }
+        if deterministic {
+            contents = normalize::stable(&contents);
+        }
+        Some((entry, contents))
+    }
+
+    // Tidy the entry's lists for saving, and what to tell about it.
+    fn tidy_lists(entry: &mut DesktopEntry) -> Vec<String> {
+        let mut warnings: Vec<String> = normalize::tidy_lists(entry)
+            .into_iter()
+            .map(|(key, items)| fl!("save-duplicates", key = key, items = items.join(", ")))
+            .collect();
+        let unknown: Vec<String> = entry
+            .categories()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|c| !c.is_empty() && categories::is_unknown(c))
+            .map(ToString::to_string)
+            .collect();
+        if !unknown.is_empty() {
+            warnings.push(fl!("categories-unknown", names = unknown.join(", ")));
+        }
+        warnings
     }
 
-    fn save_desktop_entry(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    fn save_desktop_entry(
+        path: &std::path::Path,
+        contents: &str,
+        chmod: Option<u32>,
+    ) -> std::io::Result<()> {
         std::fs::write(path, contents)?; // write file contents
 
+        // Only launchers are ever executed, .directory files keep their mode
+        let Some(bits) = chmod else {
+            return Ok(());
+        };
+        if path.extension().is_none_or(|e| e != "desktop") {
+            return Ok(());
+        }
+
         // Get existing permissions
         let mut perms = std::fs::metadata(path)?.permissions();
 
-        // OR existing mode with 0o755 (rwxr-xr-x)
-        let mode = perms.mode() | 0o755;
+        // OR existing mode with the configured bits
+        let mode = perms.mode() | bits;
         perms.set_mode(mode);
         std::fs::set_permissions(path, perms)?;
 
         Ok(())
     }
-    fn load_entry_from_path(&mut self, path: &Path) {
-        self.clear_all();
+    // The document receiving edits: the focused additional window, or the main window.
+    fn doc(&self) -> &Document {
+        self.active_window
+            .and_then(|id| self.windows.get(&id))
+            .unwrap_or(&self.main_doc)
+    }
 
-        if !path.exists() {
-            self.current_entry_error = Some(AppError::FileNotFound(path.display().to_string()));
-            return;
+    // The window showing the active document, where its async results go back to.
+    fn document_window(&self) -> Option<WindowId> {
+        self.active_window.or_else(|| self.core.main_window_id())
+    }
+
+    // Make the document in window `id` the active one, false if the window is gone.
+    fn activate_window(&mut self, id: WindowId) -> bool {
+        if self.windows.contains_key(&id) {
+            self.active_window = Some(id);
+        } else if Some(id) == self.core.main_window_id() {
+            self.active_window = None;
+        } else {
+            return false;
         }
+        true
+    }
 
-        match DesktopEntry::from_path::<&str>(path, None) {
-            Ok(entry) => {
-                if let Some(mimetypes) = entry.mime_type() {
-                    for item in mimetypes {
-                        if !item.is_empty() {
-                            let description = self
-                                .mime_descriptions
-                                .lookup(item)
-                                .cloned()
-                                .unwrap_or_default();
-                            let _ = self.mime_table.insert(MimeItem {
-                                name: item.to_owned(),
-                                description,
-                            });
-                        }
-                    }
-                }
-                let xkeys = crate::xkeys::read_custom_x_keys_localized(
-                    &self.locales,
-                    "Desktop Entry",
-                    &entry,
-                );
-                for xkey_entry in xkeys {
-                    let _ = self.xkey_table.insert(xkey_entry);
-                }
+    // Apply `f` to the document in window `id`, keeping the focused one active after.
+    fn in_window(
+        &mut self,
+        id: WindowId,
+        f: impl FnOnce(&mut Self) -> Task<cosmic::Action<Message>>,
+    ) -> Task<cosmic::Action<Message>> {
+        let focused = self.active_window;
+        if !self.activate_window(id) {
+            return Task::none();
+        }
+        let task = f(self);
+        self.active_window = focused;
+        task
+    }
+
+    fn documents(&self) -> impl Iterator<Item = &Document> {
+        std::iter::once(&self.main_doc).chain(self.windows.values())
+    }
+
+    fn documents_mut(&mut self) -> impl Iterator<Item = &mut Document> {
+        std::iter::once(&mut self.main_doc).chain(self.windows.values_mut())
+    }
+
+    fn doc_mut(&mut self) -> &mut Document {
+        self.doc_with_caches().0
+    }
+
+    // The active document along with the caches needed to fill its tables.
+    fn doc_with_caches(&mut self) -> (&mut Document, &[String], &MimeCache) {
+        let doc = match self.active_window {
+            Some(id) if self.windows.contains_key(&id) => self
+                .windows
+                .get_mut(&id)
+                .expect("window document checked above"),
+            _ => &mut self.main_doc,
+        };
+        (doc, &self.locales, &self.mime_descriptions)
+    }
+
+    // Write the focused document to `res`, the file Save or Save As picked.
+    fn save_finished(&mut self, res: Option<PathBuf>) -> Task<cosmic::Action<Message>> {
+        info!("Saving to {res:?}");
+        let normalize = std::mem::take(&mut self.normalize_next_save);
+        if let Some(path) = res
+            && self.doc().current_entry.is_some()
+        {
+            if let Err(e) = backup::rotate(&path, self.config.backup_count) {
+                info!("Error backing up {e}");
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                    e.to_string(),
+                )));
+            }
+            let chmod = (self.config.chmod_on_save && !self.config.deterministic_output)
+                .then_some(self.config.chmod_bits);
+            let contents = self.save_contents(normalize);
+            if let Err(e) = Self::save_desktop_entry(&path, &contents, chmod) {
+                info!("Error saving {e}");
+                return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                    e.to_string(),
+                )));
+            }
+
+            if let Err(e) = history::record(&path, &contents, self.config.history_count) {
+                log::warn!("Could not record a version of {}: {e}", path.display());
+            }
 
-                self.current_entry = Some(entry);
-                self.current_entry_path = Some(path.to_owned());
-                self.create_nav_bar();
+            self.remember_recent(&path);
+            let doc = self.doc_mut();
+            let renamed = doc.current_entry_path.as_ref() != Some(&path);
+            if let Some(entry) = &mut doc.current_entry {
+                entry.path.clone_from(&path);
+            }
+            doc.current_entry_changed = false;
+            doc.current_entry_error = None;
+            doc.read_only = false;
+            doc.current_entry_path = Some(path.clone());
+            doc.refresh_file_state();
+            // A new name is checked like a rename
+            if renamed {
+                let dbus = doc
+                    .current_entry
+                    .as_ref()
+                    .is_some_and(DesktopEntry::dbus_activatable);
+                doc.rename_warnings =
+                    xdghelp::file_name_warnings(&path, dbus, doc.dbus_service.as_deref());
             }
-            Err(err) => {
-                self.current_entry_error = Some(AppError::Decode(err));
+            // An open review drawer compares against the saved file now
+            if self.context_page == ContextPage::ReviewChanges && self.core.window.show_context {
+                return self.update(Message::ReviewChanges);
             }
         }
+        Task::none()
     }
 
-    fn load_entry_from_args(&mut self) {
-        self.current_entry = None;
-        self.current_entry_error = None;
+    // Write the open system launcher's edits to the user override at `target`.
+    fn save_override(&mut self, target: PathBuf) -> Task<cosmic::Action<Message>> {
+        info!("Saving override to {}", target.display());
+        // The system file as it is now is the base later merges compare against
+        if let Some(source) = &self.doc().current_entry_path
+            && let Ok(text) = std::fs::read_to_string(source)
+            && let Err(e) = merge::record_base(&target, &text)
+        {
+            log::warn!("Could not record the base of {}: {e}", target.display());
+        }
+        if let Some(parent) = target.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            return self.update(Message::ToggleContextPage(ContextPage::IOError(
+                e.to_string(),
+            )));
+        }
+        self.save_finished(Some(target))
+    }
 
-        let args: Vec<String> = std::env::args().collect();
+    fn load_entry_from_path(&mut self, path: &Path) {
+        self.dialog_data = None;
 
-        if args.len() != 2 {
-            self.current_entry_error = Some(AppError::MissingArgument);
-            return;
+        let (doc, locales, mimes) = self.doc_with_caches();
+        if doc.load_from_path(path, locales, mimes) {
+            Self::create_nav_bar(doc);
+            self.remember_recent(path);
+        }
+    }
+
+    // Start a new entry and/or open files as asked on the command line.
+    fn open_from_cli(&mut self, cli: Cli) -> Task<cosmic::Action<Message>> {
+        let mut tasks = Vec::new();
+        if let Some(kind) = cli.new {
+            // An empty window takes the new entry, otherwise it gets its own
+            if self.doc().current_entry.is_some() {
+                tasks.push(self.update(Message::NewWindow));
+            }
+            tasks.push(self.update(Message::CreateEntry(kind)));
+
+            let doc = self.doc_mut();
+            let given = [
+                (DesktopKey::Name, cli.name),
+                (DesktopKey::Exec, cli.exec),
+                (DesktopKey::Url, cli.url),
+            ];
+            for (key, value) in given {
+                if let Some(value) = value {
+                    doc.set_text(key, value);
+                }
+            }
+        }
+        for arg in &cli.paths {
+            let path = xdghelp::path_from_arg(arg);
+            tasks.push(self.update(Message::OpenInWindow(path)));
         }
+        Task::batch(tasks)
+    }
+
+    fn load_entry_from_args(&mut self, arg: Option<&String>) {
+        self.main_doc.current_entry = None;
+        self.main_doc.current_entry_error = None;
 
-        let path = std::path::Path::new(&args[1]);
+        // %f/%F give paths, %u/%U file:// URIs
+        let Some(arg) = arg else {
+            self.main_doc.current_entry_error = Some(AppError::MissingArgument);
+            return;
+        };
+
+        let path = xdghelp::path_from_arg(&arg);
         if !path.exists() {
             let path_str = format!("{path:?}");
-            self.current_entry_error = Some(AppError::FileNotFound(path_str));
+            self.main_doc.current_entry_error = Some(AppError::FileNotFound(path_str));
             return;
         }
 
-        self.load_entry_from_path(path);
+        self.load_entry_from_path(&path);
+    }
+
+    // Whether mimeapps.list already opens .desktop files with this app.
+    fn is_desktop_handler() -> bool {
+        let id = format!("{}.desktop", Self::APP_ID);
+        MimeApps::load().default_for(DESKTOP_MIME) == Some(id.as_str())
+    }
+
+    fn get_icon_button(&self, doc: &Document) -> impl Into<Element<'static, Message>> {
+        widget::button::custom(widget::icon(self.entry_icon(doc)))
+            .width(90)
+            .height(90)
+            .on_press(Message::OpenPath(PickKind::IconFile))
+    }
+
+    // Whether TryExec resolves, a failing check hides the whole launcher.
+    fn try_exec_status(doc: &Document, entry: &DesktopEntry) -> Option<Element<'static, Message>> {
+        let try_exec = entry.try_exec().filter(|t| !t.trim().is_empty())?;
+        Some(match &doc.try_exec_found {
+            Some(path) => {
+                widget::text::caption(fl!("tryexec-found", path = path.display().to_string()))
+                    .into()
+            }
+            None => Self::warning(fl!("tryexec-missing", program = try_exec)),
+        })
+    }
+
+    // Warning for an Icon value that resolves to nothing, with the closest
+    // installed names as buttons that apply them.
+    fn missing_icon(&self, entry: &DesktopEntry) -> Option<Element<'static, Message>> {
+        let icon = entry.icon().filter(|i| !i.is_empty())?;
+        if !self.icons_scanned || self.icon_cache.resolves(icon) {
+            return None;
+        }
+
+        let mut col = column!(Self::warning(fl!("icon-missing", name = icon))).spacing(5);
+        // A path has nothing to match against the installed names
+        if !icon.contains('/') {
+            let suggestions = self.icon_cache.suggest(icon);
+            if !suggestions.is_empty() {
+                let buttons = suggestions.into_iter().map(|name| {
+                    widget::button::text(name.clone())
+                        .on_press(Message::SetTextEntry(DesktopKey::Icon, name))
+                        .into()
+                });
+                col = col.push(
+                    row!(widget::text::caption(fl!("icon-didyoumean")))
+                        .extend(buttons)
+                        .align_y(Center)
+                        .spacing(5),
+                );
+            }
+        }
+        Some(col.into())
     }
 
-    fn get_icon_button(&self) -> impl Into<Element<'static, Message>> {
+    // The entry's icon, or a warning sign when it doesn't resolve.
+    fn entry_icon(&self, doc: &Document) -> widget::icon::Handle {
         let no_icon: &str = "<svg width=\"800px\" height=\"800px\" viewBox=\"0 0 25 25\" fill=\"none\" xmlns=\"http://www.w3.org/2000/svg\">
 <path d=\"M12.5 16V14.5M12.5 9V13M20.5 12.5C20.5 16.9183 16.9183 20.5 12.5 20.5C8.08172 20.5 4.5 16.9183 4.5 12.5C4.5 8.08172 8.08172 4.5 12.5 4.5C16.9183 4.5 20.5 8.08172 20.5 12.5Z\" stroke=\"red\" stroke-width=\"1.2\"/>
 </svg>";
 
-        let handle = cosmic::widget::icon::from_svg_bytes(no_icon.as_bytes().to_owned());
-
-        let mut icon = widget::icon(handle); // default to placeholder
-
-        if let Some(entry) = &self.current_entry
+        if let Some(entry) = &doc.current_entry
             && let Some(icon_name) = entry.groups.desktop_entry().and_then(|g| g.entry("Icon"))
             && let Some(icon_path) = self.icon_cache.lookup(icon_name)
         {
-            println!("Resolved icon: {}", icon_path.display());
-            let handle = cosmic::widget::icon::from_path(icon_path.to_owned());
-            icon = widget::icon(handle);
+            return cosmic::widget::icon::from_path(icon_path.to_owned());
         }
 
-        widget::button::custom(icon)
-            .width(90)
-            .height(90)
-            .on_press(Message::OpenPath(PickKind::IconFile))
+        // Placeholder
+        cosmic::widget::icon::from_svg_bytes(no_icon.as_bytes().to_owned())
     }
 
     pub fn key_binds() -> HashMap<KeyBind, MenuAction> {
@@ -2045,7 +6964,9 @@ impl AppModel {
             );
         }};}
 
+        bind!([Ctrl], Key::Character("n".into()), NewWindow);
         bind!([Ctrl], Key::Character("o".into()), Open);
+        bind!([Ctrl], Key::Character("p".into()), QuickOpen);
         bind!([Ctrl], Key::Character("s".into()), Save);
         bind!([Ctrl, Shift], Key::Character("s".into()), SaveAs);
         bind!([Ctrl], Key::Character("q".into()), Quit);
@@ -2055,6 +6976,7 @@ impl AppModel {
 }
 
 /// The page to display in the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NavPage {
     General,
     Mimetypes,
@@ -2063,6 +6985,31 @@ pub enum NavPage {
     Advanced,
 }
 
+impl NavPage {
+    const ALL: [NavPage; 5] = [
+        NavPage::General,
+        NavPage::Mimetypes,
+        NavPage::Actions,
+        NavPage::Custom,
+        NavPage::Advanced,
+    ];
+
+    /// Untranslated name, as stored in the config.
+    pub fn id(self) -> &'static str {
+        match self {
+            NavPage::General => "general",
+            NavPage::Mimetypes => "mimetypes",
+            NavPage::Actions => "actions",
+            NavPage::Custom => "custom",
+            NavPage::Advanced => "advanced",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.id() == id)
+    }
+}
+
 impl fmt::Display for NavPage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let str = match self {
@@ -2076,12 +7023,35 @@ impl fmt::Display for NavPage {
     }
 }
 
+/// State of the test launch drawer.
+#[derive(Debug, Clone, Default)]
+enum TestLaunch {
+    #[default]
+    Idle,
+    // Running the command shown
+    Running(String),
+    Finished(String, Result<LaunchOutput, String>),
+}
+
 /// The context page to display in the context drawer.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub enum ContextPage {
     #[default]
     About,
     IOError(String),
+    Overrides,
+    ReviewChanges,
+    History,
+    Systemd,
+    OpenTest,
+    Preferences,
+    // Key name, e.g. "Comment"
+    Translations(String),
+    Categories,
+    ShowIn,
+    ExecBuilder,
+    TestLaunch,
+    RunningApps,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -2090,6 +7060,7 @@ pub enum MenuAction {
     Open,
     Save,
     SaveAs,
+    SaveNormalized,
     Quit,
     None,
     RemoveMimetype(usize),
@@ -2097,6 +7068,32 @@ pub enum MenuAction {
     NewApplication,
     NewLink,
     NewDirectory,
+    NewWebApp,
+    OpenRecent(usize),
+    ClearRecent,
+    NewWindow,
+    QuickOpen,
+    Compare,
+    ImportUrl,
+    ImportJson,
+    OpenContainingFolder,
+    ReviewChanges,
+    RestoreBackup,
+    History,
+    Revert,
+    SaveTemplate,
+    NewFromTemplate(usize),
+    ExportJson,
+    CopyEntry,
+    PasteEntry,
+    Rename,
+    AddToAutostart,
+    DeleteEntry,
+    Preferences,
+    TestLaunch,
+    MimeAppsEditor,
+    MenuEditor,
+    OpenTest,
 }
 
 impl menu::action::MenuAction for MenuAction {
@@ -2108,6 +7105,7 @@ impl menu::action::MenuAction for MenuAction {
             MenuAction::Open => Message::OpenPath(PickKind::DesktopFile),
             MenuAction::Save => Message::Save,
             MenuAction::SaveAs => Message::SaveAs,
+            MenuAction::SaveNormalized => Message::SaveNormalized,
             MenuAction::Quit => Message::Quit,
             MenuAction::None => Message::None,
             MenuAction::RemoveMimetype(pos) => Message::RemoveMimetype(*pos),
@@ -2115,11 +7113,37 @@ impl menu::action::MenuAction for MenuAction {
             MenuAction::NewApplication => Message::CreateEntry(DesktopEntryType::Application),
             MenuAction::NewLink => Message::CreateEntry(DesktopEntryType::Link),
             MenuAction::NewDirectory => Message::CreateEntry(DesktopEntryType::Directory),
+            MenuAction::NewWebApp => Message::NewWebApp,
+            MenuAction::OpenRecent(i) => Message::OpenRecent(*i),
+            MenuAction::ClearRecent => Message::ClearRecent,
+            MenuAction::NewWindow => Message::NewWindow,
+            MenuAction::QuickOpen => Message::QuickOpen,
+            MenuAction::Compare => Message::OpenPath(PickKind::CompareLeft),
+            MenuAction::ImportUrl => Message::OpenPath(PickKind::UrlShortcut),
+            MenuAction::ImportJson => Message::OpenPath(PickKind::JsonFile),
+            MenuAction::OpenContainingFolder => Message::OpenContainingFolder,
+            MenuAction::ReviewChanges => Message::ReviewChanges,
+            MenuAction::RestoreBackup => Message::RestoreBackup,
+            MenuAction::History => Message::ShowHistory,
+            MenuAction::Revert => Message::Revert,
+            MenuAction::SaveTemplate => Message::SaveTemplate,
+            MenuAction::NewFromTemplate(i) => Message::NewFromTemplate(*i),
+            MenuAction::ExportJson => Message::ExportJson,
+            MenuAction::CopyEntry => Message::CopyEntry,
+            MenuAction::PasteEntry => Message::PasteEntry,
+            MenuAction::Rename => Message::Rename,
+            MenuAction::AddToAutostart => Message::AddToAutostart,
+            MenuAction::DeleteEntry => Message::DeleteEntry,
+            MenuAction::TestLaunch => Message::TestLaunch,
+            MenuAction::MimeAppsEditor => Message::OpenMimeAppsEditor,
+            MenuAction::MenuEditor => Message::OpenMenuEditor,
+            MenuAction::OpenTest => Message::ShowOpenTest,
+            MenuAction::Preferences => Message::ToggleContextPage(ContextPage::Preferences),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DesktopKey {
     Type,
     Name,
@@ -2146,6 +7170,10 @@ pub enum DesktopKey {
     Url,
     Version,
     Path,
+    // Autostart
+    AutostartEnabled,
+    AutostartDelay,
+    AutostartCondition,
 
     // endor keys
     Unknown(String),
@@ -2179,6 +7207,9 @@ impl DesktopKey {
             DesktopKey::Url => "URL".into(), // spec-cased
             DesktopKey::Version => "Version".into(),
             DesktopKey::Path => "Path".into(),
+            DesktopKey::AutostartEnabled => "X-GNOME-Autostart-enabled".into(),
+            DesktopKey::AutostartDelay => "X-GNOME-Autostart-Delay".into(),
+            DesktopKey::AutostartCondition => "AutostartCondition".into(),
             DesktopKey::Unknown(k) => k.as_str().into(),
         }
     }
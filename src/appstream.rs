@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! AppStream metainfo for the launcher, to keep the two consistent.
+
+use crate::fl;
+use crate::xdghelp::system_data_dirs;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const XML_LANG: (&str, &str) = ("http://www.w3.org/XML/1998/namespace", "lang");
+
+/// The parts of a metainfo component a launcher duplicates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Component {
+    pub path: PathBuf,
+    pub id: String,
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    // Stock icon name
+    pub icon: Option<String>,
+    // Desktop file id the component launches
+    pub launchable: Option<String>,
+}
+
+impl Component {
+    /// Where the launcher `desktop_id` disagrees with this component.
+    pub fn mismatches(&self, entry: &DesktopEntry, desktop_id: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Some(name) = &self.name
+            && entry.desktop_entry("Name") != Some(name.as_str())
+        {
+            problems.push(fl!("appstream-name", name = name.as_str()));
+        }
+        if let Some(icon) = &self.icon
+            && entry.icon() != Some(icon.as_str())
+        {
+            problems.push(fl!("appstream-icon", icon = icon.as_str()));
+        }
+        let launches = self.launchable.as_deref().unwrap_or(self.id.as_str());
+        if launches != desktop_id && launches != desktop_id.trim_end_matches(".desktop") {
+            problems.push(fl!("appstream-id", id = launches));
+        }
+        problems
+    }
+}
+
+// metainfo is the current location, appdata the legacy one.
+fn metainfo_dirs() -> Vec<PathBuf> {
+    dirs::data_dir()
+        .into_iter()
+        .chain(system_data_dirs())
+        .flat_map(|d| [d.join("metainfo"), d.join("appdata")])
+        .collect()
+}
+
+/// The component for the launcher `desktop_id`, named after it or declaring it
+/// launchable. Blocking I/O.
+pub fn find(desktop_id: &str) -> Option<Component> {
+    let stem = desktop_id.trim_end_matches(".desktop");
+    let names = [
+        format!("{stem}.metainfo.xml"),
+        format!("{stem}.appdata.xml"),
+        format!("{desktop_id}.metainfo.xml"),
+        format!("{desktop_id}.appdata.xml"),
+    ];
+    let dirs = metainfo_dirs();
+
+    let by_name = dirs
+        .iter()
+        .flat_map(|d| names.iter().map(move |n| d.join(n)))
+        .find_map(|p| parse(&p));
+    if by_name.is_some() {
+        return by_name;
+    }
+
+    dirs.iter()
+        .filter_map(|d| fs::read_dir(d).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|e| parse(&e.path()))
+        .find(|c| c.launchable.as_deref() == Some(desktop_id))
+}
+
+fn parse(path: &Path) -> Option<Component> {
+    let xml = fs::read_to_string(path).ok()?;
+    let doc = roxmltree::Document::parse(&xml).ok()?;
+    let root = doc.root_element();
+    if !root.has_tag_name("component") {
+        return None;
+    }
+
+    // Translations carry xml:lang, the untranslated text doesn't
+    let text = |tag: &str| {
+        root.children()
+            .filter(|n| n.has_tag_name(tag) && n.attribute(XML_LANG).is_none())
+            .find_map(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    let with_type = |tag: &str, kind: &str| {
+        root.children()
+            .filter(|n| n.has_tag_name(tag) && n.attribute("type") == Some(kind))
+            .find_map(|n| n.text())
+            .map(|t| t.trim().to_string())
+    };
+
+    Some(Component {
+        path: path.to_owned(),
+        id: text("id")?,
+        name: text("name"),
+        summary: text("summary"),
+        icon: with_type("icon", "stock"),
+        launchable: with_type("launchable", "desktop-id"),
+    })
+}
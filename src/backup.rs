@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Rotating `<file>.bak` copies taken before a file is overwritten.
+
+use log::info;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `foo.desktop.bak` for index 0, `foo.desktop.bak.N` for older copies.
+pub fn backup_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".bak");
+    if index > 0 {
+        name.push(format!(".{index}"));
+    }
+    PathBuf::from(name)
+}
+
+/// The newest backup of `path`, if any.
+pub fn latest_backup(path: &Path) -> Option<PathBuf> {
+    Some(backup_path(path, 0)).filter(|p| p.is_file())
+}
+
+/// Copy `path` to its newest backup slot, shifting older copies down and
+/// dropping the oldest so at most `count` backups remain.
+pub fn rotate(path: &Path, count: u32) -> std::io::Result<()> {
+    if count == 0 || !path.is_file() {
+        return Ok(());
+    }
+
+    for index in (1..count).rev() {
+        let older = backup_path(path, index - 1);
+        if older.is_file() {
+            fs::rename(&older, backup_path(path, index))?;
+        }
+    }
+
+    let newest = backup_path(path, 0);
+    fs::copy(path, &newest)?;
+    info!("Backed up {} to {}", path.display(), newest.display());
+    Ok(())
+}
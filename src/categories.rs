@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Registered categories from the freedesktop Desktop Menu Specification.
+
+/// Main categories, every launcher shown in a menu should have at least one.
+pub const MAIN: &[&str] = &[
+    "AudioVideo",
+    "Audio",
+    "Video",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+];
+
+/// Additional categories, refining a main category.
+pub const ADDITIONAL: &[&str] = &[
+    "Building",
+    "Debugger",
+    "IDE",
+    "GUIDesigner",
+    "Profiling",
+    "RevisionControl",
+    "Translation",
+    "Calendar",
+    "ContactManagement",
+    "Database",
+    "Dictionary",
+    "Chart",
+    "Email",
+    "Finance",
+    "FlowChart",
+    "PDA",
+    "ProjectManagement",
+    "Presentation",
+    "Spreadsheet",
+    "WordProcessor",
+    "2DGraphics",
+    "VectorGraphics",
+    "RasterGraphics",
+    "3DGraphics",
+    "Scanning",
+    "OCR",
+    "Photography",
+    "Publishing",
+    "Viewer",
+    "TextTools",
+    "DesktopSettings",
+    "HardwareSettings",
+    "Printing",
+    "PackageManager",
+    "Dialup",
+    "InstantMessaging",
+    "Chat",
+    "IRCClient",
+    "Feed",
+    "FileTransfer",
+    "HamRadio",
+    "News",
+    "P2P",
+    "RemoteAccess",
+    "Telephony",
+    "TelephonyTools",
+    "VideoConference",
+    "WebBrowser",
+    "WebDevelopment",
+    "Midi",
+    "Mixer",
+    "Sequencer",
+    "Tuner",
+    "TV",
+    "AudioVideoEditing",
+    "Player",
+    "Recorder",
+    "DiscBurning",
+    "ActionGame",
+    "AdventureGame",
+    "ArcadeGame",
+    "BoardGame",
+    "BlocksGame",
+    "CardGame",
+    "KidsGame",
+    "LogicGame",
+    "RolePlaying",
+    "Shooter",
+    "Simulation",
+    "SportsGame",
+    "StrategyGame",
+    "Art",
+    "Construction",
+    "Music",
+    "Languages",
+    "ArtificialIntelligence",
+    "Astronomy",
+    "Biology",
+    "Chemistry",
+    "ComputerScience",
+    "DataVisualization",
+    "Economy",
+    "Electricity",
+    "Geography",
+    "Geology",
+    "Geoscience",
+    "History",
+    "Humanities",
+    "ImageProcessing",
+    "Literature",
+    "Maps",
+    "Math",
+    "NumericalAnalysis",
+    "MedicalSoftware",
+    "Physics",
+    "Robotics",
+    "Spirituality",
+    "Sports",
+    "ParallelComputing",
+    "Amusement",
+    "Archiving",
+    "Compression",
+    "Electronics",
+    "Emulator",
+    "Engineering",
+    "FileTools",
+    "FileManager",
+    "TerminalEmulator",
+    "Filesystem",
+    "Monitor",
+    "Security",
+    "Accessibility",
+    "Calculator",
+    "Clock",
+    "TextEditor",
+    "Documentation",
+    "Adult",
+    "Core",
+    "KDE",
+    "GNOME",
+    "XFCE",
+    "DDE",
+    "GTK",
+    "Qt",
+    "Motif",
+    "Java",
+    "ConsoleOnly",
+];
+
+/// Registered desktop environment names for OnlyShowIn and NotShowIn.
+pub const DESKTOPS: &[&str] = &[
+    "COSMIC",
+    "GNOME",
+    "GNOME-Classic",
+    "GNOME-Flashback",
+    "KDE",
+    "XFCE",
+    "LXDE",
+    "LXQt",
+    "MATE",
+    "Cinnamon",
+    "Budgie",
+    "Pantheon",
+    "Deepin",
+    "Enlightenment",
+    "Unity",
+    "EDE",
+    "ROX",
+    "TDE",
+    "Endless",
+    "Old",
+];
+
+pub fn is_main(category: &str) -> bool {
+    MAIN.contains(&category)
+}
+
+/// Neither main, additional nor a vendor `X-` extension: likely a typo.
+pub fn is_unknown(category: &str) -> bool {
+    !is_main(category) && !ADDITIONAL.contains(&category) && !category.starts_with("X-")
+}
+
+/// A `;` list (categories, desktops) with `category` added at the end or removed.
+pub fn toggled(categories: &[&str], category: &str, on: bool) -> Vec<String> {
+    let mut list: Vec<String> = categories
+        .iter()
+        .filter(|c| !c.is_empty() && **c != category)
+        .map(ToString::to_string)
+        .collect();
+    if on {
+        list.push(category.to_string());
+    }
+    list
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Command line parsing. Anything not starting with `-` is a file to open.
+
+use crate::app::DesktopEntryType;
+use crate::exec::FIELD_CODES;
+use crate::xdghelp;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CliError {
+    #[error("Unknown option {0}")]
+    UnknownOption(String),
+    #[error("{0} needs a value")]
+    MissingValue(String),
+    #[error("{0} only applies to new entries")]
+    NotNew(String),
+    #[error("Only one of --new-application, --new-link and --new-directory can be given")]
+    ConflictingNew,
+    #[error("{0} needs at least one file")]
+    NoFiles(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cli {
+    pub help: bool,
+    // Check the files and print problems instead of starting the editor
+    pub validate: bool,
+    // Print the files as JSON instead of starting the editor
+    pub dump_json: bool,
+    // Start with a new entry of this type instead of opening a file
+    pub new: Option<DesktopEntryType>,
+    pub name: Option<String>,
+    pub exec: Option<String>,
+    pub url: Option<String>,
+    pub paths: Vec<String>,
+}
+
+impl Cli {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                for arg in args.by_ref() {
+                    cli.push_path(arg);
+                }
+                break;
+            }
+            if !arg.starts_with('-') || arg == "-" {
+                cli.push_path(arg);
+                continue;
+            }
+
+            // Both `--name Foo` and `--name=Foo`
+            let (option, inline) = match arg.split_once('=') {
+                Some((o, v)) => (o.to_string(), Some(v.to_string())),
+                None => (arg, None),
+            };
+            let mut value = |option: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| CliError::MissingValue(option.to_string()))
+            };
+
+            match option.as_str() {
+                "-h" | "--help" => cli.help = true,
+                "--validate" => cli.validate = true,
+                "--dump-json" => cli.dump_json = true,
+                "--new-application" => cli.set_new(DesktopEntryType::Application)?,
+                "--new-link" => cli.set_new(DesktopEntryType::Link)?,
+                "--new-directory" => cli.set_new(DesktopEntryType::Directory)?,
+                "--name" => cli.name = Some(value(&option)?),
+                "--exec" => cli.exec = Some(value(&option)?),
+                "--url" => cli.url = Some(value(&option)?),
+                _ => return Err(CliError::UnknownOption(option.clone())),
+            }
+        }
+
+        if cli.paths.is_empty() {
+            if cli.validate {
+                return Err(CliError::NoFiles("--validate".to_string()));
+            }
+            if cli.dump_json {
+                return Err(CliError::NoFiles("--dump-json".to_string()));
+            }
+        }
+        if cli.new.is_none() {
+            let given = [
+                ("--name", &cli.name),
+                ("--exec", &cli.exec),
+                ("--url", &cli.url),
+            ];
+            if let Some((option, _)) = given.iter().find(|(_, v)| v.is_some()) {
+                return Err(CliError::NotNew((*option).to_string()));
+            }
+        }
+        Ok(cli)
+    }
+
+    /// Resolve the files against the current directory, before they're handed
+    /// to an instance running in another one. `file://` URIs become paths.
+    pub fn make_paths_absolute(&mut self) {
+        for arg in &mut self.paths {
+            if let Ok(path) = std::path::absolute(xdghelp::path_from_arg(arg)) {
+                *arg = path.to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    /// Arguments `parse` turns back into the entry to create and files to open.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(kind) = self.new {
+            args.push(match kind {
+                DesktopEntryType::Application => "--new-application".to_string(),
+                DesktopEntryType::Link => "--new-link".to_string(),
+                DesktopEntryType::Directory => "--new-directory".to_string(),
+            });
+        }
+        let given = [
+            ("--name", &self.name),
+            ("--exec", &self.exec),
+            ("--url", &self.url),
+        ];
+        for (option, value) in given {
+            if let Some(value) = value {
+                args.push(format!("{option}={value}"));
+            }
+        }
+        // Files named like options stay files
+        if !self.paths.is_empty() {
+            args.push("--".to_string());
+            args.extend(self.paths.iter().cloned());
+        }
+        args
+    }
+
+    // Launchers given no files may pass empty arguments or leave field codes
+    // unexpanded, neither names a file. The same file twice opens once.
+    fn push_path(&mut self, arg: String) {
+        if arg.trim().is_empty() || FIELD_CODES.contains(&arg.as_str()) {
+            return;
+        }
+        if !self.paths.contains(&arg) {
+            self.paths.push(arg);
+        }
+    }
+
+    fn set_new(&mut self, kind: DesktopEntryType) -> Result<(), CliError> {
+        if self.new.is_some_and(|k| k != kind) {
+            return Err(CliError::ConflictingNew);
+        }
+        self.new = Some(kind);
+        Ok(())
+    }
+}
@@ -1,9 +1,86 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::normalize;
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+/// How many entries the File → Recent menu remembers.
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// Upper limit for the backup rotation count in Preferences.
+pub const MAX_BACKUPS: u32 = 20;
+
+/// Upper limit for the saved versions kept per file in Preferences.
+pub const MAX_HISTORY: u32 = 100;
+
+/// Permission bits OR-ed into a saved launcher's mode by default (rwxr-xr-x).
+pub const DEFAULT_CHMOD_BITS: u32 = 0o755;
+
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
 pub struct Config {
-    demo: String,
+    /// Most recently opened files, newest first.
+    pub recent_files: Vec<PathBuf>,
+    /// Number of `.bak` copies kept when overwriting a file, 0 disables backups.
+    pub backup_count: u32,
+    /// Saved versions kept per file in the history, 0 disables it.
+    pub history_count: u32,
+    /// Folder Save As starts in, `~/` allowed, empty for ~/.local/share/applications.
+    pub save_folder: PathBuf,
+    /// Whether saving a `.desktop` file adds `chmod_bits` to its mode.
+    pub chmod_on_save: bool,
+    /// Permission bits OR-ed into the mode when `chmod_on_save` is set.
+    pub chmod_bits: u32,
+    /// Whether every save applies the normalizations in `normalize_steps`.
+    pub normalize_on_save: bool,
+    /// `normalize` step flags applied on save, or by Save normalized.
+    pub normalize_steps: u32,
+    /// Byte-stable output for files kept in version control: sorted keys, LF
+    /// endings and the file mode left alone.
+    pub deterministic_output: bool,
+    /// Terminal emulator for test launching Terminal=true entries, empty picks one.
+    pub terminal: String,
+    /// Size of the main window when it was last closed, 0 for the default.
+    pub window_width: u32,
+    pub window_height: u32,
+    /// The tab the main window showed last, as `NavPage::id`.
+    pub nav_page: String,
+    /// Whether the user was already asked to make this the .desktop file handler.
+    pub handler_prompted: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            recent_files: Vec::new(),
+            backup_count: 0,
+            history_count: 10,
+            save_folder: PathBuf::new(),
+            chmod_on_save: true,
+            chmod_bits: DEFAULT_CHMOD_BITS,
+            normalize_on_save: false,
+            normalize_steps: normalize::ALL,
+            deterministic_output: false,
+            terminal: String::new(),
+            window_width: 0,
+            window_height: 0,
+            nav_page: String::new(),
+            handler_prompted: false,
+        }
+    }
+}
+
+impl Config {
+    /// The recent files list with `path` moved to the front.
+    pub fn recent_files_with(&self, path: &Path) -> Vec<PathBuf> {
+        let mut recent = vec![path.to_owned()];
+        recent.extend(
+            self.recent_files
+                .iter()
+                .filter(|p| p.as_path() != path)
+                .cloned(),
+        );
+        recent.truncate(MAX_RECENT_FILES);
+        recent
+    }
 }
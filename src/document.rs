@@ -0,0 +1,1260 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::app::{AppError, DesktopEntryType, DesktopKey, NavPage};
+use crate::appstream::{self, Component};
+use crate::backup;
+use crate::entrydiff::{KeyDiff, diff_entries, flatten, set_value, split_locale};
+use crate::exec;
+use crate::lint;
+use crate::merge::{self, MergeKey, MergeState};
+use crate::mimelist::{MimeCache, MimeCategory, MimeItem, is_valid_mimetype, split_mime_list};
+use crate::packaging::{self, Packaged};
+use crate::thumbnailer;
+use crate::vendor;
+use crate::xdghelp::{
+    PickKind, find_autostart_copy, find_dbus_service, is_autostart_file, shadowed_system_path,
+};
+use crate::xkeys::{XKeyCategory, XKeyItem};
+
+use cosmic::widget::{nav_bar, table};
+use freedesktop_desktop_entry::DesktopEntry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Editing {
+    pub name: bool,
+    pub generic_name: bool,
+    pub comment: bool,
+    pub path: bool,
+    pub exec: bool,
+    pub icon: bool,
+    pub try_exec: bool,
+    pub implements: bool,
+    pub startupwmclass: bool,
+    pub url: bool,
+    pub autostart_condition: bool,
+}
+
+impl Editing {
+    pub fn toggle(&mut self, key: &DesktopKey) {
+        *self.flag(key) ^= true;
+    }
+
+    pub fn is_editing(&self, key: &DesktopKey) -> bool {
+        let mut flags = *self;
+        *flags.flag(key)
+    }
+
+    fn flag(&mut self, key: &DesktopKey) -> &mut bool {
+        match key {
+            DesktopKey::Name => &mut self.name,
+            DesktopKey::GenericName => &mut self.generic_name,
+            DesktopKey::Comment => &mut self.comment,
+            DesktopKey::Path => &mut self.path,
+            DesktopKey::Exec => &mut self.exec,
+            DesktopKey::Icon => &mut self.icon,
+            DesktopKey::TryExec => &mut self.try_exec,
+            DesktopKey::Implements => &mut self.implements,
+            DesktopKey::StartupWMClass => &mut self.startupwmclass,
+            DesktopKey::Url => &mut self.url,
+            DesktopKey::AutostartCondition => &mut self.autostart_condition,
+            _ => {
+                todo!();
+            }
+        }
+    }
+}
+
+// Main group keys the General and Advanced tabs show.
+const FORM_KEYS: &[&str] = &[
+    "Type",
+    "Name",
+    "GenericName",
+    "Comment",
+    "Icon",
+    "Exec",
+    "TryExec",
+    "Path",
+    "Terminal",
+    "Categories",
+    "Keywords",
+    "MimeType",
+    "Actions",
+    "OnlyShowIn",
+    "NotShowIn",
+    "StartupNotify",
+    "StartupWMClass",
+    "DBusActivatable",
+    "NoDisplay",
+    "Hidden",
+    "PrefersNonDefaultGPU",
+    "Implements",
+    "SingleMainWindow",
+    "URL",
+    "Version",
+    "AutostartCondition",
+];
+
+// Form keys whose translations are edited in the translations drawer.
+const FORM_TRANSLATED: &[&str] = &["Name", "GenericName", "Comment", "Keywords"];
+
+// What is typed into a field being edited, written to the entry once committed.
+#[derive(Debug)]
+struct Staged {
+    key: DesktopKey,
+    // The variant edited, e.g. `Name[da]` when the field shows that translation
+    field: String,
+    text: String,
+}
+
+/// Pasted mimetypes sorted into what would be added and what is skipped.
+#[derive(Debug, Default)]
+pub struct BulkMimetypes {
+    pub add: Vec<String>,
+    pub duplicates: usize,
+    pub invalid: Vec<String>,
+}
+
+/// One open desktop entry and the widget models mirroring it.
+/// Every application window edits its own document.
+pub struct Document {
+    pub nav: nav_bar::Model,
+    pub mime_table: table::SingleSelectModel<MimeItem, MimeCategory>,
+    pub xkey_table: table::SingleSelectModel<XKeyItem, XKeyCategory>,
+    pub current_entry: Option<DesktopEntry>,
+    pub current_entry_path: Option<PathBuf>,
+    pub current_entry_error: Option<AppError>,
+    pub current_entry_changed: bool,
+    pub am_editing: Editing,
+    // Text of the fields being edited, the one edited last at the end.
+    staged: Vec<Staged>,
+    // Modification time of the file when it was last loaded or saved.
+    pub disk_mtime: Option<SystemTime>,
+    // The file as it was on disk at that time, for reverting single keys.
+    pub disk_entry: Option<DesktopEntry>,
+    // Another program changed the file and the user hasn't decided what to do yet.
+    pub disk_changed: bool,
+    // The file can't be written by us, typically a system launcher.
+    pub read_only: bool,
+    // A .thumbnailer file, its group is edited as the main one.
+    pub thumbnailer: bool,
+    // The system entry this user-level entry overrides.
+    pub system_entry: Option<DesktopEntry>,
+    // The system entry as it was when the override was made, if we made it.
+    pub override_base: Option<DesktopEntry>,
+    // Text in the add field of each list editor, by key.
+    pub list_input: HashMap<String, String>,
+    // List key and position of the chip being dragged to a new position.
+    pub list_drag: Option<(String, usize)>,
+    // Only mimetypes whose name or description contain this are shown.
+    pub mime_filter: String,
+    // Column the mimetype table is sorted by and whether ascending, kept across rebuilds.
+    pub mime_sort: Option<(MimeCategory, bool)>,
+    // Show mimetypes grouped by media type instead of as a flat table.
+    pub mime_grouped: bool,
+    // Media types whose group is collapsed, e.g. "audio".
+    pub mime_collapsed: HashSet<String>,
+    // File just moved to the trash from here, offered for undo.
+    pub trashed: Option<PathBuf>,
+    // Problems with the file name after a rename, until dismissed.
+    pub rename_warnings: Vec<String>,
+    // What the last save tidied up or found wrong with lists, until dismissed.
+    pub save_warnings: Vec<String>,
+    // Problems with Exec, checked when it changes rather than on every redraw.
+    pub exec_problems: Vec<exec::ExecProblem>,
+    // The Exec value exec_problems were found for.
+    exec_checked: Option<String>,
+    // The program TryExec resolves to, None when launchers would hide the entry.
+    pub try_exec_found: Option<PathBuf>,
+    // The TryExec value try_exec_found was resolved for.
+    try_exec_checked: Option<String>,
+    // Whether the Path directory exists, for opening a terminal there.
+    pub working_dir_exists: bool,
+    // The Path working_dir_exists was checked for.
+    working_dir_checked: Option<PathBuf>,
+    // Whether a backup of the file exists to restore.
+    pub has_backup: bool,
+    // Autostart entry with the same file name, when this isn't one itself.
+    pub autostart_copy: Option<PathBuf>,
+    // Session bus service file named like this launcher, for DBusActivatable.
+    pub dbus_service: Option<PathBuf>,
+    // Locale shown in the launcher preview, None for the user's own.
+    pub preview_locale: Option<String>,
+    // Translated fields show and edit the untranslated value instead of the user's language.
+    pub edit_untranslated: bool,
+    // AppStream metadata describing the same application.
+    pub appstream: Option<Component>,
+    // Package format that generated this launcher and rewrites it on updates.
+    pub packaged: Option<Packaged>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            nav: nav_bar::Model::default(),
+            mime_table: table::Model::new(vec![
+                MimeCategory::Name,
+                MimeCategory::Description,
+                MimeCategory::Globs,
+                MimeCategory::Handler,
+            ]),
+            xkey_table: table::Model::new(vec![XKeyCategory::Name, XKeyCategory::Value]),
+            current_entry: None,
+            current_entry_path: None,
+            current_entry_error: None,
+            current_entry_changed: false,
+            am_editing: Editing::default(),
+            staged: Vec::new(),
+            disk_mtime: None,
+            disk_entry: None,
+            disk_changed: false,
+            read_only: false,
+            thumbnailer: false,
+            system_entry: None,
+            override_base: None,
+            list_input: HashMap::new(),
+            list_drag: None,
+            mime_sort: None,
+            mime_filter: String::new(),
+            mime_grouped: false,
+            mime_collapsed: HashSet::new(),
+            trashed: None,
+            rename_warnings: Vec::new(),
+            save_warnings: Vec::new(),
+            exec_problems: Vec::new(),
+            exec_checked: None,
+            try_exec_found: None,
+            try_exec_checked: None,
+            working_dir_exists: false,
+            working_dir_checked: None,
+            has_backup: false,
+            autostart_copy: None,
+            dbus_service: None,
+            preview_locale: None,
+            edit_untranslated: false,
+            appstream: None,
+            packaged: None,
+        }
+    }
+}
+
+impl Document {
+    pub fn changed(&mut self) {
+        self.current_entry_changed = true;
+        self.refresh_exec_problems();
+        self.refresh_try_exec();
+        self.refresh_working_dir();
+    }
+
+    /// Check Exec again if it changed since it was last checked.
+    pub fn refresh_exec_problems(&mut self) {
+        let current = self
+            .current_entry
+            .as_ref()
+            .and_then(|e| e.exec())
+            .map(str::to_owned);
+        if current == self.exec_checked {
+            return;
+        }
+        self.exec_problems = current.as_deref().map(exec::check).unwrap_or_default();
+        self.exec_checked = current;
+    }
+
+    /// Resolve TryExec again if it changed since it was last resolved.
+    pub fn refresh_try_exec(&mut self) {
+        let current = self
+            .current_entry
+            .as_ref()
+            .and_then(|e| e.try_exec())
+            .map(str::to_owned);
+        if current == self.try_exec_checked {
+            return;
+        }
+        self.try_exec_found = current.as_deref().and_then(exec::resolve_try_exec);
+        self.try_exec_checked = current;
+    }
+
+    /// The entry's Path, None when unset.
+    pub fn working_dir(&self) -> Option<PathBuf> {
+        self.current_entry
+            .as_ref()?
+            .desktop_entry("Path")
+            .map(|p| PathBuf::from(exec::unescape_value(p)))
+            .filter(|p| !p.as_os_str().is_empty())
+    }
+
+    /// Check again whether the Path directory exists if Path changed.
+    pub fn refresh_working_dir(&mut self) {
+        let current = self.working_dir();
+        if current == self.working_dir_checked {
+            return;
+        }
+        self.working_dir_exists = current.as_deref().is_some_and(Path::is_dir);
+        self.working_dir_checked = current;
+    }
+
+    /// Start editing a field with its value staged, or finish and commit the text.
+    pub fn toggle_edit(&mut self, key: &DesktopKey, locales: &[String]) {
+        self.am_editing.toggle(key);
+        if self.am_editing.is_editing(key) {
+            let field = self.field_key(key, locales);
+            self.staged.retain(|s| s.key != *key);
+            self.staged.push(Staged {
+                key: key.clone(),
+                text: self.value(&field).unwrap_or_default(),
+                field,
+            });
+        } else {
+            self.commit_staged(key);
+            self.staged.retain(|s| s.key != *key);
+        }
+    }
+
+    /// The text of a field being edited, not yet in the entry.
+    pub fn staged_text(&self, key: &DesktopKey) -> Option<&str> {
+        self.staged
+            .iter()
+            .find(|s| s.key == *key)
+            .map(|s| s.text.as_str())
+    }
+
+    /// Replace what's typed into a field being edited, leaving the entry alone.
+    pub fn stage(&mut self, key: &DesktopKey, text: String) {
+        if let Some(staged) = self.staged.iter_mut().find(|s| s.key == *key) {
+            staged.text = text;
+        }
+    }
+
+    /// Write a field's staged text to the entry, if it differs from what's there.
+    /// The field stays open for editing.
+    pub fn commit_staged(&mut self, key: &DesktopKey) {
+        let Some(staged) = self.staged.iter().find(|s| s.key == *key) else {
+            return;
+        };
+        if self.value(&staged.field).unwrap_or_default() == staged.text {
+            return;
+        }
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, "Desktop Entry", &staged.field, Some(&staged.text));
+            self.changed();
+        }
+    }
+
+    /// Commit and close every field being edited, before the entry is saved.
+    pub fn finish_edits(&mut self) {
+        while let Some(key) = self.staged.last().map(|s| s.key.clone()) {
+            self.commit_staged(&key);
+            self.am_editing.toggle(&key);
+            self.staged.pop();
+        }
+    }
+
+    // Start over from the entry's value in a field being edited, after the key
+    // was changed some other way.
+    fn restage(&mut self, key: &DesktopKey) {
+        let Some(field) = self
+            .staged
+            .iter()
+            .find(|s| s.key == *key)
+            .map(|s| s.field.clone())
+        else {
+            return;
+        };
+        let text = self.value(&field).unwrap_or_default();
+        self.stage(key, text);
+    }
+
+    /// The text fields of the shown tab in the order they appear, for Tab to walk through.
+    pub fn tab_order(&self) -> Vec<DesktopKey> {
+        let page = self.nav.active_data::<NavPage>().copied();
+        if self.thumbnailer {
+            return match page {
+                Some(NavPage::General) => vec![DesktopKey::TryExec, DesktopKey::Exec],
+                _ => Vec::new(),
+            };
+        }
+        match (self.entry_type(), page) {
+            (Some(DesktopEntryType::Link), Some(NavPage::General)) => vec![
+                DesktopKey::Name,
+                DesktopKey::GenericName,
+                DesktopKey::Icon,
+                DesktopKey::Comment,
+                DesktopKey::Url,
+            ],
+            (Some(DesktopEntryType::Directory), Some(NavPage::General)) => {
+                vec![DesktopKey::Name, DesktopKey::Icon, DesktopKey::Comment]
+            }
+            (Some(DesktopEntryType::Application), Some(NavPage::General)) => vec![
+                DesktopKey::Name,
+                DesktopKey::Icon,
+                DesktopKey::Comment,
+                DesktopKey::Exec,
+                DesktopKey::Path,
+            ],
+            (Some(DesktopEntryType::Application), Some(NavPage::Advanced)) => {
+                let mut order = vec![
+                    DesktopKey::GenericName,
+                    DesktopKey::TryExec,
+                    DesktopKey::StartupWMClass,
+                ];
+                if self
+                    .current_entry_path
+                    .as_deref()
+                    .is_some_and(is_autostart_file)
+                {
+                    order.push(DesktopKey::AutostartCondition);
+                }
+                order
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Finish editing the current field and return the next one in tab order, or
+    /// the previous one going `backwards`.
+    pub fn finish_for_next(&mut self, backwards: bool) -> Option<DesktopKey> {
+        let order = self.tab_order();
+        if order.is_empty() {
+            return None;
+        }
+        let current = self.staged.last().map(|s| s.key.clone());
+        if let Some(key) = &current {
+            self.commit_staged(key);
+            self.am_editing.toggle(key);
+            self.staged.pop();
+        }
+
+        let len = order.len();
+        let at = current.and_then(|k| order.iter().position(|o| *o == k));
+        let next = match (at, backwards) {
+            (None, false) => 0,
+            (None, true) => len - 1,
+            (Some(at), false) => (at + 1) % len,
+            (Some(at), true) => (at + len - 1) % len,
+        };
+        Some(order[next].clone())
+    }
+
+    /// Stop editing the field edited last, dropping what wasn't committed.
+    pub fn cancel_edit(&mut self) {
+        if let Some(staged) = self.staged.pop() {
+            self.am_editing.toggle(&staged.key);
+        }
+    }
+
+    pub fn set_text(&mut self, key: DesktopKey, text: impl Into<String>) {
+        if let Some(entry) = &mut self.current_entry {
+            entry.add_desktop_entry(key.to_string(), text.into());
+            self.changed();
+        }
+    }
+
+    pub fn set_bool(&mut self, key: DesktopKey, value: bool) {
+        self.set_text(key, if value { "true" } else { "false" });
+        self.changed();
+    }
+
+    pub fn set_list<S: AsRef<str>>(&mut self, key: DesktopKey, items: &[S]) {
+        let s = items
+            .iter()
+            .map(std::convert::AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join(";");
+        // Many tools tolerate missing trailing ';', add if you prefer:
+        // let s = format!("{s};");
+        self.set_text(key, s);
+        self.changed();
+    }
+
+    /// Set Path to `path` as it is, Path isn't split into arguments like Exec.
+    pub fn set_path(&mut self, path: &Path) {
+        let path = exec::escape_value(&path.display().to_string());
+        self.set_text(DesktopKey::Path, path);
+    }
+
+    /// Point Exec (or TryExec) at a new program, keeping the existing arguments and field codes.
+    pub fn set_program(&mut self, exe: &Path, kind: PickKind) {
+        let exe_str = exe.display().to_string();
+
+        // TryExec is a plain path, not a command line
+        if kind == PickKind::TryExecutable {
+            self.set_text(DesktopKey::TryExec, exec::escape_value(&exe_str));
+            return;
+        }
+
+        let existing = self
+            .current_entry
+            .as_ref()
+            .and_then(DesktopEntry::exec)
+            .unwrap_or_default()
+            .to_string();
+
+        let cmd = match exec::split(&existing) {
+            Ok(mut args) if !args.is_empty() => {
+                args[0] = exec::escape_percent(&exe_str);
+                exec::join(&args)
+            }
+            // Unparsable, swap the first word and leave the rest as it was
+            Err(_) => match existing.trim_start().split_once(char::is_whitespace) {
+                Some((_, rest)) => format!("{} {rest}", exec::quote_literal(&exe_str)),
+                None => exec::quote_literal(&exe_str),
+            },
+            Ok(_) => exec::quote_literal(&exe_str),
+        };
+        self.set_text(DesktopKey::Exec, cmd);
+    }
+
+    pub fn create_mimetype(&mut self, mimetype: &str, mimes: &MimeCache) {
+        if let Some(entry) = &mut self.current_entry {
+            // Make new list, including new one
+            let mut list = vec![mimetype.to_string()];
+            if let Some(existing) = entry.mime_type() {
+                if existing.contains(&mimetype) {
+                    return;
+                }
+                list.extend(
+                    existing
+                        .iter()
+                        .filter(|s| !s.is_empty())
+                        .map(ToString::to_string),
+                );
+            }
+            // Update desktop entry
+            self.set_list(DesktopKey::MimeType, &list);
+
+            // Update table
+            let item = mimes.item(mimetype, self.desktop_id().as_deref());
+            let _ = self.mime_table.insert(item);
+        }
+    }
+
+    pub fn create_xkey(&mut self, xkey_item: &XKeyItem) {
+        self.set_text(
+            DesktopKey::Unknown(xkey_item.name.clone()),
+            xkey_item.value.clone(),
+        );
+        let _ = self.xkey_table.insert(xkey_item.clone());
+    }
+
+    pub fn clear(&mut self) {
+        self.am_editing = Editing::default();
+        self.staged.clear();
+        self.current_entry = None;
+        self.current_entry_path = None;
+        self.current_entry_error = None;
+        self.current_entry_changed = false;
+        self.disk_mtime = None;
+        self.disk_entry = None;
+        self.disk_changed = false;
+        self.read_only = false;
+        self.thumbnailer = false;
+        self.system_entry = None;
+        self.override_base = None;
+        self.list_input.clear();
+        self.list_drag = None;
+        self.mime_filter.clear();
+        self.mime_collapsed.clear();
+        self.trashed = None;
+        self.rename_warnings.clear();
+        self.save_warnings.clear();
+        self.exec_problems.clear();
+        self.exec_checked = None;
+        self.try_exec_found = None;
+        self.try_exec_checked = None;
+        self.working_dir_exists = false;
+        self.working_dir_checked = None;
+        self.has_backup = false;
+        self.autostart_copy = None;
+        self.dbus_service = None;
+        self.appstream = None;
+        self.packaged = None;
+        self.mime_table.clear();
+        self.xkey_table.clear();
+    }
+
+    fn file_mtime(&self) -> Option<SystemTime> {
+        self.current_entry_path
+            .as_ref()
+            .and_then(|p| std::fs::metadata(p).ok())
+            .and_then(|m| m.modified().ok())
+    }
+
+    /// Remember the file's current modification time and contents as our own.
+    pub fn refresh_disk_mtime(&mut self) {
+        self.disk_mtime = self.file_mtime();
+        self.disk_entry = self
+            .current_entry_path
+            .as_ref()
+            .and_then(|p| DesktopEntry::from_path::<&str>(p, None).ok());
+        if self.thumbnailer
+            && let Some(entry) = &mut self.disk_entry
+        {
+            thumbnailer::into_editable(entry);
+        }
+        self.disk_changed = false;
+    }
+
+    /// Whether a [Desktop Entry] key differs from the file on disk.
+    pub fn key_modified(&self, key: &DesktopKey) -> bool {
+        let Some(disk) = &self.disk_entry else {
+            return false;
+        };
+        let key = key.to_string();
+        let current = self
+            .current_entry
+            .as_ref()
+            .and_then(|e| e.desktop_entry(&key));
+        current != disk.desktop_entry(&key)
+    }
+
+    /// Put back the on-disk value of one [Desktop Entry] key, leaving other edits alone.
+    pub fn revert_key(&mut self, key: &DesktopKey) {
+        let name = key.to_string();
+        let value = self
+            .disk_entry
+            .as_ref()
+            .and_then(|d| d.desktop_entry(&name))
+            .map(str::to_string);
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, "Desktop Entry", &name, value.as_deref());
+            self.changed();
+        }
+        self.restage(key);
+    }
+
+    /// Whether the main group has `key` at all, even with an empty value.
+    pub fn has_key(&self, key: &DesktopKey) -> bool {
+        self.current_entry
+            .as_ref()
+            .is_some_and(|e| e.desktop_entry(&key.to_string()).is_some())
+    }
+
+    /// A boolean key's value, None when it's missing or not a boolean.
+    pub fn bool_value(&self, key: &DesktopKey) -> Option<bool> {
+        let entry = self.current_entry.as_ref()?;
+        match entry.desktop_entry(&key.to_string())?.trim() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Delete `key` and its translations, rather than leave it empty.
+    pub fn remove_key(&mut self, key: &DesktopKey) {
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, "Desktop Entry", &key.to_string(), None);
+            self.changed();
+        }
+        self.restage(key);
+    }
+
+    /// Look for a backup of the file to restore.
+    pub fn refresh_backup(&mut self) {
+        self.has_backup = self
+            .current_entry_path
+            .as_deref()
+            .and_then(backup::latest_backup)
+            .is_some();
+    }
+
+    /// Look for an autostart copy of this launcher.
+    pub fn refresh_autostart(&mut self) {
+        self.autostart_copy = self
+            .current_entry_path
+            .as_deref()
+            .filter(|p| !is_autostart_file(p))
+            .and_then(find_autostart_copy);
+    }
+
+    /// Look for the D-Bus service that would activate this launcher.
+    pub fn refresh_dbus_service(&mut self) {
+        self.dbus_service = self
+            .current_entry_path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .and_then(|s| find_dbus_service(&s.to_string_lossy()));
+    }
+
+    /// Look for AppStream metadata for this launcher.
+    pub fn refresh_appstream(&mut self) {
+        self.appstream = self.desktop_id().and_then(|id| appstream::find(&id));
+    }
+
+    /// Find out whether a package format generated this launcher.
+    pub fn refresh_packaging(&mut self) {
+        self.packaged = match (&self.current_entry, &self.current_entry_path) {
+            (Some(entry), Some(path)) => packaging::detect(entry, path),
+            _ => None,
+        };
+    }
+
+    /// Look up everything known about the file at `current_entry_path` again,
+    /// after it was loaded, saved or renamed.
+    pub fn refresh_file_state(&mut self) {
+        self.refresh_disk_mtime();
+        self.refresh_backup();
+        self.refresh_autostart();
+        self.refresh_appstream();
+        self.refresh_dbus_service();
+        self.refresh_exec_problems();
+        self.refresh_try_exec();
+        self.refresh_working_dir();
+        self.refresh_packaging();
+    }
+
+    /// Check whether another program modified the file since we loaded or saved it.
+    pub fn check_disk(&mut self) {
+        if self.disk_changed || self.disk_mtime.is_none() {
+            return;
+        }
+        if self.file_mtime() != self.disk_mtime {
+            self.disk_changed = true;
+        }
+    }
+
+    /// Keys where this entry differs from the system entry it overrides.
+    pub fn override_diffs(&self) -> Vec<KeyDiff> {
+        match (&self.system_entry, &self.current_entry) {
+            (Some(system), Some(entry)) => diff_entries(system, entry),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Put back the system entry's value for one key, removing it if the system entry lacks it.
+    pub fn reset_to_system(&mut self, group: &str, key: &str) {
+        let value = self
+            .system_entry
+            .as_ref()
+            .and_then(|s| flatten(s).remove(&(group.to_string(), key.to_string())));
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, group, key, value.as_deref());
+            self.changed();
+        }
+    }
+
+    /// The override's keys merged three ways against the system entry, upstream
+    /// changes first.
+    pub fn override_merge(&self) -> Vec<MergeKey> {
+        match (&self.system_entry, &self.current_entry) {
+            (Some(system), Some(entry)) => {
+                merge::three_way(self.override_base.as_ref(), system, entry)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Pull every key only the system entry changed since the override was made.
+    pub fn take_upstream(&mut self) {
+        for key in self.override_merge() {
+            if key.state == MergeState::Upstream {
+                self.reset_to_system(&key.group, &key.key);
+            }
+        }
+    }
+
+    /// Keys no field shows, as (group, key, value): unknown and deprecated keys,
+    /// translations of untranslated fields and groups other than actions. X- keys
+    /// of the main group are left to the custom keys table. Like every key they
+    /// are written back exactly as read unless edited here.
+    pub fn other_keys(&self) -> Vec<(String, String, String)> {
+        let Some(entry) = &self.current_entry else {
+            return Vec::new();
+        };
+        flatten(entry)
+            .into_iter()
+            .filter(|((group, key), _)| {
+                if group.starts_with("Desktop Action ") {
+                    return false;
+                }
+                if group != "Desktop Entry" {
+                    return true;
+                }
+                let (base, locale) = split_locale(key);
+                let shown = match locale {
+                    None => FORM_KEYS.contains(&base),
+                    Some(_) => FORM_TRANSLATED.contains(&base),
+                };
+                !shown && !base.starts_with("X-")
+            })
+            .map(|((group, key), value)| (group, key, value))
+            .collect()
+    }
+
+    /// Set one of `other_keys`, or remove it with `None`.
+    pub fn set_other_key(&mut self, group: &str, key: &str, value: Option<&str>) {
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, group, key, value);
+            self.changed();
+        }
+    }
+
+    /// Drop the deprecated keys, moving their value to the replacing key unless
+    /// that one is already set.
+    pub fn migrate_deprecated(&mut self) {
+        let Some(entry) = &mut self.current_entry else {
+            return;
+        };
+        let deprecated = lint::deprecated_keys(entry);
+        if deprecated.is_empty() {
+            return;
+        }
+        for (key, replacement) in deprecated {
+            if let Some(replacement) = replacement
+                && entry.desktop_entry(replacement).is_none()
+                && let Some(value) = entry.desktop_entry(key).map(ToString::to_string)
+            {
+                set_value(entry, "Desktop Entry", replacement, Some(&value));
+            }
+            set_value(entry, "Desktop Entry", key, None);
+        }
+        self.changed();
+    }
+
+    /// Exec, TryExec and Path values in any group whose quoting doesn't follow
+    /// the spec, as (group, key, fixed value).
+    pub fn quoting_fixes(&self) -> Vec<(String, &'static str, String)> {
+        let Some(entry) = &self.current_entry else {
+            return Vec::new();
+        };
+        let mut fixes = Vec::new();
+        for (group_name, group) in &entry.groups.0 {
+            for key in exec::QUOTED_KEYS {
+                if let Some((value, _)) = group.0.get(key)
+                    && let Some(fixed) = exec::fix_key_quoting(key, value)
+                {
+                    fixes.push((group_name.to_string(), key, fixed));
+                }
+            }
+        }
+        fixes
+    }
+
+    pub fn fix_quoting(&mut self) {
+        let fixes = self.quoting_fixes();
+        if let Some(entry) = &mut self.current_entry
+            && !fixes.is_empty()
+        {
+            for (group, key, fixed) in fixes {
+                set_value(entry, &group, key, Some(&fixed));
+            }
+            self.changed();
+        }
+    }
+
+    /// The untranslated items of a semicolon separated list key, like Categories
+    /// or OnlyShowIn. Translations are edited separately.
+    pub fn list(&self, key: &DesktopKey) -> Vec<String> {
+        self.current_entry
+            .as_ref()
+            .and_then(|e| e.desktop_entry(&key.to_string()))
+            .map(|v| {
+                v.split(';')
+                    .filter(|d| !d.is_empty())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn set_list_items(&mut self, key: DesktopKey, items: &[String]) {
+        // Drop the key rather than write an empty value, unless translations hang off it
+        if items.is_empty()
+            && self.translations(&key.key_str()).is_empty()
+            && let Some(entry) = &mut self.current_entry
+        {
+            set_value(entry, "Desktop Entry", &key.to_string(), None);
+            self.changed();
+        } else {
+            self.set_list(key, items);
+        }
+    }
+
+    /// Both OnlyShowIn and NotShowIn are set, which the spec forbids.
+    pub fn show_in_conflict(&self) -> bool {
+        !self.list(&DesktopKey::OnlyShowIn).is_empty()
+            && !self.list(&DesktopKey::NotShowIn).is_empty()
+    }
+
+    /// Localized variants of a main group key as (locale, value), sorted by locale.
+    pub fn translations(&self, key: &str) -> Vec<(String, String)> {
+        self.current_entry
+            .as_ref()
+            .and_then(|e| e.groups.0.get("Desktop Entry"))
+            .and_then(|g| g.0.get(key))
+            .map(|(_, locales)| {
+                locales
+                    .iter()
+                    .map(|(l, v)| (l.to_string(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Locales any of `keys` is translated to, sorted.
+    pub fn translated_locales(&self, keys: &[&str]) -> Vec<String> {
+        let mut locales: Vec<String> = keys
+            .iter()
+            .flat_map(|k| self.translations(k))
+            .map(|(l, _)| l)
+            .collect();
+        locales.sort();
+        locales.dedup();
+        locales
+    }
+
+    /// `key` as a launcher shows it in `locale`: `de_DE` falls back to `de`, then untranslated.
+    pub fn localized(&self, key: &str, locale: &str) -> Option<String> {
+        let translations = self.translations(key);
+        locale_fallbacks(locale)
+            .iter()
+            .find_map(|name| translations.iter().find(|(l, _)| l == name))
+            .map(|(_, v)| v.clone())
+            .or_else(|| {
+                self.current_entry
+                    .as_ref()
+                    .and_then(|e| e.desktop_entry(key))
+                    .map(ToString::to_string)
+            })
+    }
+
+    /// The translation of a form key its field shows: the first of `locales` it's
+    /// translated to, `de_DE` falling back to `de`. None if it's untranslated.
+    pub fn shown_translation(&self, key: &DesktopKey, locales: &[String]) -> Option<String> {
+        let key = key.to_string();
+        if !FORM_TRANSLATED.contains(&key.as_str()) {
+            return None;
+        }
+        let translations = self.translations(&key);
+        locales.iter().find_map(|locale| {
+            locale_fallbacks(locale)
+                .into_iter()
+                .find(|l| translations.iter().any(|(t, _)| t == l))
+        })
+    }
+
+    /// The key a field edits, `Name[da]` while it shows the Danish translation.
+    pub fn field_key(&self, key: &DesktopKey, locales: &[String]) -> String {
+        match self.shown_translation(key, locales) {
+            Some(locale) if !self.edit_untranslated => format!("{key}[{locale}]"),
+            _ => key.to_string(),
+        }
+    }
+
+    /// The text of a field, from the variant of the key it edits.
+    pub fn field_text(&self, key: &DesktopKey, locales: &[String]) -> String {
+        self.value(&self.field_key(key, locales))
+            .unwrap_or_default()
+    }
+
+    /// Set what was typed into a field, in the variant of the key it shows.
+    pub fn set_field_text(&mut self, key: DesktopKey, text: String, locales: &[String]) {
+        let field = self.field_key(&key, locales);
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, "Desktop Entry", &field, Some(&text));
+            self.changed();
+        }
+    }
+
+    // A possibly localized main group key, like `Name[da]`.
+    fn value(&self, field: &str) -> Option<String> {
+        let (key, locale) = split_locale(field);
+        match locale {
+            Some(locale) => self
+                .translations(key)
+                .into_iter()
+                .find_map(|(l, v)| (l == locale).then_some(v)),
+            None => self
+                .current_entry
+                .as_ref()
+                .and_then(|e| e.desktop_entry(key))
+                .map(str::to_string),
+        }
+    }
+
+    /// Set `Key[locale]` in the main group, or remove it with `None`.
+    pub fn set_translation(&mut self, key: &str, locale: &str, value: Option<&str>) {
+        if let Some(entry) = &mut self.current_entry {
+            set_value(entry, "Desktop Entry", &format!("{key}[{locale}]"), value);
+            self.changed();
+        }
+    }
+
+    /// The desktop file id, e.g. `org.example.App.desktop`, as mimeapps.list refers to it.
+    pub fn desktop_id(&self) -> Option<String> {
+        self.current_entry_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|n| n.to_string_lossy().into_owned())
+    }
+
+    pub fn entry_type(&self) -> Option<DesktopEntryType> {
+        self.current_entry
+            .as_ref()
+            .and_then(|e| e.type_())
+            .and_then(|s| s.parse::<DesktopEntryType>().ok())
+    }
+
+    /// Mimetypes listed in the entry.
+    pub fn mimetypes(&self) -> Vec<String> {
+        self.current_entry
+            .as_ref()
+            .and_then(DesktopEntry::mime_type)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| !m.is_empty())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Repeated and empty items in MimeType, as (duplicates, empty). The empty
+    /// item after a final `;` is how the spec ends lists and isn't counted.
+    pub fn mimetype_problems(&self) -> (usize, usize) {
+        let Some(raw) = self
+            .current_entry
+            .as_ref()
+            .and_then(|e| e.desktop_entry("MimeType"))
+        else {
+            return (0, 0);
+        };
+        let raw = raw.strip_suffix(';').unwrap_or(raw);
+        let mut seen = HashSet::new();
+        let (mut duplicates, mut empty) = (0, 0);
+        for item in raw.split(';').map(str::trim) {
+            if item.is_empty() {
+                empty += 1;
+            } else if !seen.insert(item.to_ascii_lowercase()) {
+                duplicates += 1;
+            }
+        }
+        (duplicates, empty)
+    }
+
+    /// Rewrite MimeType without repeated or empty items, keeping the first occurrence.
+    pub fn clean_mimetypes(&mut self, mimes: &MimeCache) {
+        let mut seen = HashSet::new();
+        let cleaned: Vec<String> = self
+            .mimetypes()
+            .into_iter()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty() && seen.insert(m.to_ascii_lowercase()))
+            .collect();
+        self.set_list_items(DesktopKey::MimeType, &cleaned);
+        self.rebuild_mime_table(mimes);
+    }
+
+    /// Sort a pasted list of mimetypes against the ones already present.
+    pub fn bulk_mimetypes(&self, text: &str) -> BulkMimetypes {
+        let mut existing = self.mimetypes();
+        let mut bulk = BulkMimetypes::default();
+        for name in split_mime_list(text) {
+            if !is_valid_mimetype(&name) {
+                bulk.invalid.push(name);
+            } else if existing.contains(&name) {
+                bulk.duplicates += 1;
+            } else {
+                existing.push(name.clone());
+                bulk.add.push(name);
+            }
+        }
+        bulk
+    }
+
+    /// Append mimetypes to the entry and the table.
+    pub fn add_mimetypes(&mut self, names: &[String], mimes: &MimeCache) {
+        if names.is_empty() {
+            return;
+        }
+        let mut all = self.mimetypes();
+        all.extend(names.iter().cloned());
+        self.set_list(DesktopKey::MimeType, &all);
+        self.rebuild_mime_table(mimes);
+    }
+
+    /// Shown mimetypes by media type, e.g. "audio" → [audio/flac, audio/ogg].
+    pub fn mime_groups(&self) -> BTreeMap<String, Vec<&MimeItem>> {
+        let mut groups: BTreeMap<String, Vec<&MimeItem>> = BTreeMap::new();
+        for item in self
+            .mime_table
+            .iter()
+            .filter_map(|e| self.mime_table.item(e))
+        {
+            let group = item
+                .name
+                .split_once('/')
+                .map_or(item.name.as_str(), |(g, _)| g);
+            groups.entry(group.to_string()).or_default().push(item);
+        }
+        groups
+    }
+
+    /// Remove every shown mimetype of one media type.
+    pub fn remove_mime_group(&mut self, group: &str, mimes: &MimeCache) {
+        let names: Vec<String> = self
+            .mime_groups()
+            .remove(group)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| item.name.clone())
+            .collect();
+        let remaining: Vec<String> = self
+            .mimetypes()
+            .into_iter()
+            .filter(|m| !names.contains(m))
+            .collect();
+        self.set_list(DesktopKey::MimeType, &remaining);
+        self.rebuild_mime_table(mimes);
+    }
+
+    pub fn remove_mimetype(&mut self, mimetype: &str) {
+        let mimes: Vec<String> = self
+            .mimetypes()
+            .into_iter()
+            .filter(|m| m != mimetype)
+            .collect();
+        self.set_list(DesktopKey::MimeType, &mimes);
+
+        let entity = self
+            .mime_table
+            .iter()
+            .find(|e| self.mime_table.item(*e).is_some_and(|i| i.name == mimetype));
+        if let Some(entity) = entity {
+            self.mime_table.remove(entity);
+        }
+    }
+
+    /// Refill the mimetype table from the entry, applying the filter.
+    pub fn rebuild_mime_table(&mut self, mimes: &MimeCache) {
+        self.mime_table.clear();
+        let own_id = self.desktop_id();
+        let filter = self.mime_filter.to_lowercase();
+
+        for name in self.mimetypes() {
+            let item = mimes.item(&name, own_id.as_deref());
+            if filter.is_empty()
+                || item.name.to_lowercase().contains(&filter)
+                || item.description.to_lowercase().contains(&filter)
+            {
+                let _ = self.mime_table.insert(item);
+            }
+        }
+        if let Some((category, ascending)) = self.mime_sort {
+            self.mime_table.sort(category, ascending);
+        }
+    }
+
+    /// Sort the mimetype table by `category`, flipping the order when it already is.
+    pub fn sort_mimetypes(&mut self, category: MimeCategory) {
+        let ascending = match self.mime_sort {
+            Some((sorted, ascending)) if sorted == category => !ascending,
+            _ => true,
+        };
+        self.mime_sort = Some((category, ascending));
+        self.mime_table.sort(category, ascending);
+    }
+
+    /// Refill the mimetype and custom key tables from the entry.
+    pub fn rebuild_tables(&mut self, locales: &[String], mimes: &MimeCache) {
+        self.rebuild_mime_table(mimes);
+        self.xkey_table.clear();
+        let Some(entry) = &self.current_entry else {
+            return;
+        };
+
+        let xkeys = crate::xkeys::read_custom_x_keys_localized(locales, "Desktop Entry", entry);
+        // Vendor keys with their own widgets aren't repeated in the table
+        for xkey_entry in xkeys
+            .into_iter()
+            .filter(|x| !vendor::is_vendor_key(&x.name))
+        {
+            let _ = self.xkey_table.insert(xkey_entry);
+        }
+    }
+
+    /// Replace the document with the entry at `path`. Returns true on success.
+    pub fn load_from_path(&mut self, path: &Path, locales: &[String], mimes: &MimeCache) -> bool {
+        self.clear();
+
+        if !path.exists() {
+            self.current_entry_error = Some(AppError::FileNotFound(path.display().to_string()));
+            return false;
+        }
+
+        match DesktopEntry::from_path::<&str>(path, None) {
+            Ok(mut entry) => {
+                self.thumbnailer = thumbnailer::is_thumbnailer(path);
+                if self.thumbnailer {
+                    thumbnailer::into_editable(&mut entry);
+                }
+                let unquoted = unquote_path(&mut entry);
+                self.current_entry = Some(entry);
+                self.rebuild_tables(locales, mimes);
+                self.current_entry_path = Some(path.to_owned());
+                self.read_only = !is_writable(path);
+                self.system_entry = shadowed_system_path(path)
+                    .and_then(|p| DesktopEntry::from_path::<&str>(p, None).ok());
+                self.override_base = self
+                    .system_entry
+                    .as_ref()
+                    .and_then(|_| merge::load_base(path));
+                self.refresh_file_state();
+                // Saving writes the unquoted Path back
+                if unquoted {
+                    self.changed();
+                }
+                true
+            }
+            Err(err) => {
+                self.current_entry_error = Some(AppError::Decode(err));
+                false
+            }
+        }
+    }
+}
+
+// Older versions quoted a Path containing spaces, but launchers take the value
+// as it is and the quotes end up in the directory name. Returns whether it did.
+fn unquote_path(entry: &mut DesktopEntry) -> bool {
+    let Some(path) = entry
+        .desktop_entry("Path")
+        .and_then(exec::fix_plain_quoting)
+    else {
+        return false;
+    };
+    set_value(entry, "Desktop Entry", "Path", Some(&path));
+    true
+}
+
+// Opening for writing without truncating tells us whether a save would succeed,
+// without touching the file.
+fn is_writable(path: &Path) -> bool {
+    std::fs::OpenOptions::new().write(true).open(path).is_ok()
+}
+
+// The locale names to look a translation up under, most specific first, as the
+// Desktop Entry spec orders them: `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`,
+// `lang@MODIFIER`, `lang`. The `.ENCODING` part is never matched.
+fn locale_fallbacks(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or(rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+    let mut names = vec![];
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        names.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        names.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        names.push(format!("{lang}@{modifier}"));
+    }
+    names.push(lang.to_string());
+    names
+}
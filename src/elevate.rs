@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Writing files we don't own, through pkexec and whatever polkit asks for.
+
+use crate::exec::find_in_path;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+// Where tee is installed. It runs as root, so it isn't looked up in $PATH.
+const TEE: [&str; 2] = ["/usr/bin/tee", "/bin/tee"];
+
+/// Whether pkexec is available to write as administrator.
+pub fn available() -> bool {
+    find_in_path("pkexec").is_some()
+}
+
+/// Replace `path` with `contents` as root. polkit shows its own authentication
+/// dialog, declining it is reported as an error like any other failure.
+pub async fn write_file(path: PathBuf, contents: String) -> Result<(), String> {
+    let tee = TEE
+        .iter()
+        .map(Path::new)
+        .find(|p| p.is_file())
+        .ok_or_else(|| "tee was not found".to_string())?;
+    let not_authorized = || format!("Not authorized to write {}", path.display());
+    let mut child = tokio::process::Command::new("pkexec")
+        .arg(tee)
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(contents.as_bytes()).await {
+            Ok(()) => {}
+            // pkexec exits without reading when authentication is dismissed
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                _ = child.wait().await;
+                return Err(not_authorized());
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+        // Closing stdin ends tee's input
+    }
+
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        // pkexec's codes for a dismissed or refused authentication
+        Some(126 | 127) => Err(not_authorized()),
+        _ => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Key-by-key comparison of desktop entries.
+
+use freedesktop_desktop_entry::{DecodeError, DesktopEntry};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// A key whose value differs between two entries. Localized variants are
+/// separate keys, written the way they appear in the file, e.g. `Name[de]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyDiff {
+    pub group: String,
+    pub key: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl KeyDiff {
+    pub fn is_added(&self) -> bool {
+        self.left.is_none()
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.right.is_none()
+    }
+}
+
+/// Two files loaded for side-by-side comparison.
+pub struct Comparison {
+    pub left: PathBuf,
+    pub right: PathBuf,
+    pub diffs: Vec<KeyDiff>,
+    // Keys present with the same value in both
+    pub identical: usize,
+}
+
+impl Comparison {
+    pub fn load(left: PathBuf, right: PathBuf) -> Result<Self, DecodeError> {
+        let l = DesktopEntry::from_path::<&str>(&left, None)?;
+        let r = DesktopEntry::from_path::<&str>(&right, None)?;
+        let diffs = diff_entries(&l, &r);
+        let total = flatten(&l)
+            .into_keys()
+            .chain(flatten(&r).into_keys())
+            .collect::<BTreeSet<_>>()
+            .len();
+
+        Ok(Self {
+            left,
+            right,
+            identical: total - diffs.len(),
+            diffs,
+        })
+    }
+}
+
+/// Every value of every group, keyed by (group, key[locale]).
+pub fn flatten(entry: &DesktopEntry) -> BTreeMap<(String, String), String> {
+    let mut flat = BTreeMap::new();
+    for (group_name, group) in &entry.groups.0 {
+        for (key, (value, locales)) in &group.0 {
+            flat.insert((group_name.to_string(), key.to_string()), value.clone());
+            for (locale, value) in locales {
+                flat.insert(
+                    (group_name.to_string(), format!("{key}[{locale}]")),
+                    value.clone(),
+                );
+            }
+        }
+    }
+    flat
+}
+
+/// Keys added, removed or changed going from `left` to `right`.
+pub fn diff_entries(left: &DesktopEntry, right: &DesktopEntry) -> Vec<KeyDiff> {
+    let left = flatten(left);
+    let right = flatten(right);
+
+    let keys: BTreeSet<_> = left.keys().chain(right.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|k| {
+            let (l, r) = (left.get(k), right.get(k));
+            (l != r).then(|| KeyDiff {
+                group: k.0.clone(),
+                key: k.1.clone(),
+                left: l.cloned(),
+                right: r.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Split `Name[de]` into ("Name", Some("de")).
+pub fn split_locale(key: &str) -> (&str, Option<&str>) {
+    if let Some(base) = key.strip_suffix(']')
+        && let Some((base, locale)) = base.split_once('[')
+    {
+        (base, Some(locale))
+    } else {
+        (key, None)
+    }
+}
+
+/// Set or, with `None`, remove a possibly localized key in any group.
+pub fn set_value(entry: &mut DesktopEntry, group: &str, key: &str, value: Option<&str>) {
+    let (base, locale) = split_locale(key);
+
+    match value {
+        Some(value) => {
+            let group = entry.groups.0.entry(group.to_string()).or_default();
+            let slot = group.0.entry(base.to_string()).or_default();
+            match locale {
+                Some(locale) => {
+                    slot.1.insert(locale.to_string(), value.to_string());
+                }
+                None => slot.0 = value.to_string(),
+            }
+        }
+        None => {
+            let Some(group) = entry.groups.0.get_mut(group) else {
+                return;
+            };
+            match locale {
+                Some(locale) => {
+                    if let Some(slot) = group.0.get_mut(base) {
+                        slot.1.remove(locale);
+                    }
+                }
+                None => {
+                    group.0.remove(base);
+                }
+            }
+        }
+    }
+}
+
+/// One line of a line-by-line diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Line diff of two texts, longest common subsequence based.
+/// Desktop files are short, so the quadratic table is fine.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed((*l).to_string())));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added((*l).to_string())));
+    out
+}
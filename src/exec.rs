@@ -0,0 +1,804 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Exec key parsing and quoting, following the Desktop Entry Specification.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Field codes a launcher replaces with the files or URLs it was given.
+pub const FILE_CODES: &[&str] = &["%f", "%F", "%u", "%U"];
+
+/// Every field code still in use, in the order the spec lists them.
+pub const FIELD_CODES: &[&str] = &["%f", "%F", "%u", "%U", "%i", "%c", "%k"];
+
+// Characters that force an argument into double quotes.
+const RESERVED: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')',
+    '`',
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExecError {
+    #[error("Unbalanced quote")]
+    UnbalancedQuote,
+    #[error("Trailing backslash")]
+    TrailingBackslash,
+}
+
+/// Something wrong with an Exec line that would make the launcher fail.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExecProblem {
+    #[error("{0}")]
+    Syntax(#[from] ExecError),
+    #[error("No program given")]
+    Empty,
+    #[error("{0} was not found in $PATH")]
+    NotInPath(String),
+    #[error("{0} does not exist")]
+    Missing(String),
+    #[error("{0} is not executable")]
+    NotExecutable(String),
+}
+
+/// Check that an Exec line parses and its program can be found.
+pub fn check(exec: &str) -> Vec<ExecProblem> {
+    let args = match split(exec) {
+        Ok(args) => args,
+        Err(e) => return vec![e.into()],
+    };
+    let Some(program) = args.first() else {
+        return vec![ExecProblem::Empty];
+    };
+
+    let mut problems = Vec::new();
+    if program.contains('/') {
+        let path = Path::new(program);
+        if !path.exists() {
+            problems.push(ExecProblem::Missing(program.clone()));
+        } else if !is_executable(path) {
+            problems.push(ExecProblem::NotExecutable(program.clone()));
+        }
+    } else if find_in_path(program).is_none() {
+        problems.push(ExecProblem::NotInPath(program.clone()));
+    }
+    problems
+}
+
+/// The first executable named `program` in $PATH.
+pub fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|p| is_executable(p))
+}
+
+/// The program a TryExec value names, looked up in $PATH unless absolute. None
+/// means launchers hide the entry.
+pub fn resolve_try_exec(try_exec: &str) -> Option<PathBuf> {
+    let try_exec = unescape_value(try_exec.trim());
+    let path = Path::new(&try_exec);
+    if path.is_absolute() {
+        is_executable(path).then(|| path.to_owned())
+    } else {
+        find_in_path(&try_exec)
+    }
+}
+
+/// Names of every executable in $PATH, sorted. Blocking I/O.
+pub fn path_programs() -> Vec<String> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let mut programs: Vec<String> = std::env::split_paths(&path)
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    programs.sort();
+    programs.dedup();
+    programs
+}
+
+/// Completions of the program, the first token of `exec`, from `programs`.
+/// Each is the whole Exec line with the program completed.
+pub fn complete_program(programs: &[String], exec: &str, max: usize) -> Vec<String> {
+    let exec = exec.trim_start();
+    let (program, rest) = match exec.find(char::is_whitespace) {
+        Some(i) => exec.split_at(i),
+        None => (exec, ""),
+    };
+    // Paths and quoted programs aren't looked up in $PATH
+    if program.is_empty() || program.contains(['/', '"']) {
+        return Vec::new();
+    }
+
+    programs
+        .iter()
+        .filter(|p| p.starts_with(program) && p.as_str() != program)
+        .take(max)
+        .map(|p| format!("{p}{rest}"))
+        .collect()
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Whether `path` is a shell script, by extension or `#!` line.
+pub fn is_shell_script(path: &Path) -> bool {
+    if path.extension().is_some_and(|e| e == "sh" || e == "bash") {
+        return true;
+    }
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut first = String::new();
+    let _ = std::io::BufRead::read_line(&mut std::io::BufReader::new(file), &mut first);
+    // #!/bin/sh, #!/usr/bin/env bash, #!/bin/zsh...
+    first.strip_prefix("#!").is_some_and(|interpreter| {
+        interpreter
+            .split_whitespace()
+            .filter_map(|word| word.rsplit('/').next())
+            .any(|name| matches!(name, "sh" | "bash" | "dash" | "zsh" | "ksh" | "fish"))
+    })
+}
+
+/// A display name for a program file, `my_tool-2.sh` gives "My Tool 2".
+pub fn name_from_program(path: &Path) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    stem.split(['-', '_', '.', ' '])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Undo the escapes of a string value, `\s`, `\n`, `\t`, `\r` and `\\`. Other
+/// backslashes are kept, in Exec they belong to the quoting rules applied after.
+pub fn unescape_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let unescaped = match (c, chars.peek()) {
+            ('\\', Some('s')) => ' ',
+            ('\\', Some('n')) => '\n',
+            ('\\', Some('t')) => '\t',
+            ('\\', Some('r')) => '\r',
+            ('\\', Some('\\')) => '\\',
+            _ => {
+                out.push(c);
+                continue;
+            }
+        };
+        chars.next();
+        out.push(unescaped);
+    }
+    out
+}
+
+/// Escape text for a string value, the reverse of `unescape_value`.
+pub fn escape_value(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split an Exec value into arguments, removing quotes and escapes. Field codes
+/// and `%%` are left as they are.
+pub fn split(exec: &str) -> Result<Vec<String>, ExecError> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    // The string escapes are undone before the quoting rules apply
+    let exec = unescape_value(exec);
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                // "" is an empty argument
+                current.get_or_insert_with(String::new);
+            }
+            '\\' => {
+                let escaped = chars.next().ok_or(ExecError::TrailingBackslash)?;
+                current.get_or_insert_with(String::new).push(escaped);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quoted {
+        return Err(ExecError::UnbalancedQuote);
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+/// Quote one argument if it contains reserved characters, then escape it for
+/// the string value, so a literal backslash is written as four. Field codes and
+/// `%%` pass through, use `quote_literal` for text that may contain `%`.
+pub fn quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(RESERVED) {
+        return arg.to_string();
+    }
+
+    let mut out = String::with_capacity(arg.len() + 2);
+    out.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    escape_value(&out)
+}
+
+/// `%` doubled so a path or URL isn't read as containing field codes.
+pub fn escape_percent(arg: &str) -> String {
+    arg.replace('%', "%%")
+}
+
+/// Quote a literal argument such as a picked path or a URL.
+pub fn quote_literal(arg: &str) -> String {
+    quote(&escape_percent(arg))
+}
+
+/// Compose an Exec value from arguments, quoting as needed.
+pub fn join<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(|a| quote(a.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Letters that may follow `%`: the field codes, deprecated ones and `%%`
+const CODE_LETTERS: &str = "fFuUickdDnNvm%";
+
+// Exec arguments read the way a shell would, and whether that took more than
+// the spec allows: single quotes, backslashes outside double quotes, escapes
+// the spec doesn't have, unquoted reserved characters, quoted field codes or a
+// missing closing quote.
+fn split_lenient(exec: &str) -> (Vec<String>, bool) {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut was_quoted = false;
+    let mut broken = false;
+    let mut chars = exec.chars();
+
+    let mut finish = |current: &mut Option<String>, was_quoted: &mut bool, broken: &mut bool| {
+        if let Some(arg) = current.take() {
+            *broken |= *was_quoted && FIELD_CODES.contains(&arg.as_str());
+            args.push(arg);
+        }
+        *was_quoted = false;
+    };
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('"' | '\'', None) => {
+                broken |= c == '\'';
+                quote = Some(c);
+                was_quoted = true;
+                current.get_or_insert_with(String::new);
+            }
+            (c, Some(q)) if c == q => quote = None,
+            ('\\', Some('"')) => {
+                let arg = current.get_or_insert_with(String::new);
+                match chars.next() {
+                    Some(e @ ('"' | '`' | '$' | '\\')) => arg.push(e),
+                    // Not an escape in Exec, the backslash is meant literally
+                    Some(e) => {
+                        broken = true;
+                        arg.push('\\');
+                        arg.push(e);
+                    }
+                    None => broken = true,
+                }
+            }
+            ('\\', None) => {
+                broken = true;
+                if let Some(e) = chars.next() {
+                    current.get_or_insert_with(String::new).push(e);
+                }
+            }
+            (c, None) if c.is_whitespace() => finish(&mut current, &mut was_quoted, &mut broken),
+            (c, q) => {
+                broken |= q.is_none() && RESERVED.contains(&c);
+                current.get_or_insert_with(String::new).push(c);
+            }
+        }
+    }
+    finish(&mut current, &mut was_quoted, &mut broken);
+    (args, broken || quote.is_some())
+}
+
+// `%` not starting a field code doubled, and whether there was any.
+fn escape_stray_percent(arg: &str) -> (String, bool) {
+    let mut out = String::with_capacity(arg.len());
+    let mut stray = false;
+    let mut chars = arg.chars().peekable();
+    while let Some(c) = chars.next() {
+        out.push(c);
+        if c != '%' {
+            continue;
+        }
+        match chars.peek() {
+            Some(&next) if CODE_LETTERS.contains(next) => {
+                out.push(next);
+                chars.next();
+            }
+            _ => {
+                stray = true;
+                out.push('%');
+            }
+        }
+    }
+    (out, stray)
+}
+
+/// Exec with its quoting redone by the spec, reading what's wrong the way a
+/// shell would. None when the quoting is already valid.
+pub fn fix_quoting(exec: &str) -> Option<String> {
+    let (args, mut broken) = split_lenient(&unescape_value(exec));
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| {
+            let (arg, stray) = escape_stray_percent(arg);
+            broken |= stray;
+            arg
+        })
+        .collect();
+    broken.then(|| join(&args))
+}
+
+/// A TryExec or Path value without the quotes or backslash escapes a shell
+/// would need, neither key is parsed like Exec. None when it's fine.
+pub fn fix_plain_quoting(value: &str) -> Option<String> {
+    let unescaped = unescape_value(value);
+    let trimmed = unescaped.trim();
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|q| trimmed.strip_prefix(*q)?.strip_suffix(*q))
+        .unwrap_or(trimmed)
+        .replace("\\ ", " ");
+    let fixed = escape_value(&unquoted);
+    (fixed != value).then_some(fixed)
+}
+
+/// Keys `fix_key_quoting` checks.
+pub const QUOTED_KEYS: [&str; 3] = ["Exec", "TryExec", "Path"];
+
+/// The fixed value of one of `QUOTED_KEYS`, None when it's fine.
+pub fn fix_key_quoting(key: &str, value: &str) -> Option<String> {
+    if key == "Exec" {
+        fix_quoting(value)
+    } else {
+        fix_plain_quoting(value)
+    }
+}
+
+/// Exec split into the parts edited by the command builder.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecBuilder {
+    pub program: String,
+    // One per cell, unquoted and unescaped
+    pub args: Vec<String>,
+    // Index into FILE_CODES
+    pub file_code: Option<usize>,
+    // Why the line didn't split by the spec's rules, if it didn't
+    pub unparsed: Option<ExecError>,
+}
+
+impl ExecBuilder {
+    pub fn from_exec(exec: &str) -> Self {
+        let (mut args, unparsed) = match split(exec) {
+            Ok(args) => (args, None),
+            // Split a broken line on spaces for the user to fix cell by cell
+            Err(e) => (
+                unescape_value(exec)
+                    .split_whitespace()
+                    .map(ToString::to_string)
+                    .collect(),
+                Some(e),
+            ),
+        };
+
+        let file_code = args
+            .last()
+            .and_then(|last| FILE_CODES.iter().position(|c| c == last));
+        if file_code.is_some() {
+            args.pop();
+        }
+
+        let program = if args.is_empty() {
+            String::new()
+        } else {
+            args.remove(0)
+        };
+
+        Self {
+            program,
+            args,
+            file_code,
+            unparsed,
+        }
+    }
+
+    /// The cells quoted back into an Exec value.
+    pub fn to_exec(&self) -> String {
+        let args: Vec<&String> = std::iter::once(&self.program).chain(&self.args).collect();
+        let mut exec = join(&args);
+        if let Some(code) = self.file_code.and_then(|i| FILE_CODES.get(i)) {
+            exec.push(' ');
+            exec.push_str(code);
+        }
+        exec
+    }
+
+    pub fn set_arg(&mut self, index: usize, arg: String) {
+        if let Some(a) = self.args.get_mut(index) {
+            *a = arg;
+        }
+    }
+
+    pub fn remove_arg(&mut self, index: usize) {
+        if index < self.args.len() {
+            self.args.remove(index);
+        }
+    }
+}
+
+/// What a test launch printed and how it ended.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOutput {
+    // None when killed by a signal
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Values substituted for %i, %c and %k when launching.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchContext {
+    pub icon: Option<String>,
+    pub name: Option<String>,
+    pub desktop_file: Option<String>,
+}
+
+/// Arguments of `exec` with field codes expanded as if launched without files.
+pub fn expand_field_codes(exec: &str, ctx: &LaunchContext) -> Result<Vec<String>, ExecError> {
+    let mut out = Vec::new();
+    for arg in split(exec)? {
+        match arg.as_str() {
+            "%i" => {
+                if let Some(icon) = &ctx.icon {
+                    out.push("--icon".to_string());
+                    out.push(icon.clone());
+                }
+            }
+            _ => {
+                let expanded = expand_arg(&arg, ctx);
+                // A lone file code without files leaves no argument behind
+                if !(expanded.is_empty() && FILE_CODES.contains(&arg.as_str())) {
+                    out.push(expanded);
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn expand_arg(arg: &str, ctx: &LaunchContext) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => out.push('%'),
+            Some('c') => out.push_str(ctx.name.as_deref().unwrap_or_default()),
+            Some('k') => out.push_str(ctx.desktop_file.as_deref().unwrap_or_default()),
+            // File codes and deprecated ones expand to nothing
+            _ => {}
+        }
+    }
+    out
+}
+
+// Known terminal emulators and the arguments placing a command after them.
+const TERMINALS: &[(&str, &[&str])] = &[
+    ("cosmic-term", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("ptyxis", &["--"]),
+    ("kgx", &["-e"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("mate-terminal", &["-x"]),
+    ("lxterminal", &["-e"]),
+    ("qterminal", &["-e"]),
+    ("tilix", &["-e"]),
+    ("terminator", &["-x"]),
+    ("alacritty", &["-e"]),
+    ("kitty", &["--"]),
+    ("foot", &[]),
+    ("wezterm", &["start", "--"]),
+    ("x-terminal-emulator", &["-e"]),
+    ("xterm", &["-e"]),
+];
+
+/// Names of the known terminal emulators found in $PATH.
+pub fn installed_terminals() -> Vec<String> {
+    TERMINALS
+        .iter()
+        .filter(|(name, _)| find_in_path(name).is_some())
+        .map(|(name, _)| (*name).to_string())
+        .collect()
+}
+
+/// The command prefix running a program in a terminal, e.g. `["xterm", "-e"]`.
+/// An empty `preferred` picks $TERMINAL or the first installed one.
+pub fn terminal_command(preferred: &str) -> Option<Vec<String>> {
+    let prefix = |name: &str, args: &[&str]| {
+        std::iter::once(name)
+            .chain(args.iter().copied())
+            .map(ToString::to_string)
+            .collect()
+    };
+
+    if !preferred.is_empty() {
+        let args = TERMINALS
+            .iter()
+            .find(|(name, _)| *name == preferred)
+            .map_or(&["-e"][..], |(_, args)| args);
+        return Some(prefix(preferred, args));
+    }
+
+    if let Ok(terminal) = std::env::var("TERMINAL")
+        && let Ok(mut args) = split(&terminal)
+        && !args.is_empty()
+    {
+        args.push("-e".to_string());
+        return Some(args);
+    }
+
+    TERMINALS
+        .iter()
+        .find(|(name, _)| find_in_path(name).is_some())
+        .map(|(name, args)| prefix(name, args))
+}
+
+/// The command opening an interactive shell in a terminal, `terminal_command`
+/// without the arguments that would run a program instead.
+pub fn terminal_shell(preferred: &str) -> Option<Vec<String>> {
+    let mut args = terminal_command(preferred)?;
+    while args.len() > 1 && matches!(args.last().map(String::as_str), Some("-e" | "-x" | "--")) {
+        args.pop();
+    }
+    Some(args)
+}
+
+/// Run a command to completion, capturing its output.
+pub async fn run(args: Vec<String>, dir: Option<PathBuf>) -> Result<LaunchOutput, String> {
+    let Some((program, rest)) = args.split_first() else {
+        return Err(ExecProblem::Empty.to_string());
+    };
+
+    let mut command = tokio::process::Command::new(program);
+    command.args(rest).stdin(std::process::Stdio::null());
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+
+    let output = command.output().await.map_err(|e| e.to_string())?;
+    Ok(LaunchOutput {
+        status: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+/// Start a command in the background without waiting for it.
+pub fn spawn(args: &[String], dir: Option<PathBuf>) -> Result<(), String> {
+    let Some((program, rest)) = args.split_first() else {
+        return Err(ExecProblem::Empty.to_string());
+    };
+
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(rest)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    // Reap it whenever it exits
+    tokio::spawn(async move { child.wait().await });
+    Ok(())
+}
+
+/// Let the user click an X11 window and return its WM_CLASS class.
+pub async fn probe_wm_class() -> Result<String, String> {
+    if std::env::var_os("DISPLAY").is_none() {
+        return Err("No X11 display, XWayland isn't running".to_string());
+    }
+    let output = tokio::process::Command::new("xprop")
+        .arg("WM_CLASS")
+        .output()
+        .await
+        .map_err(|e| format!("xprop: {e}"))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_wm_class(&stdout).ok_or_else(|| "The window has no WM_CLASS".to_string())
+}
+
+// `WM_CLASS(STRING) = "instance", "Class"`, the class is what StartupWMClass matches.
+fn parse_wm_class(xprop: &str) -> Option<String> {
+    let (_, values) = xprop.split_once('=')?;
+    let mut quoted = values.split('"').skip(1).step_by(2);
+    let instance = quoted.next()?;
+    Some(quoted.next().unwrap_or(instance).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> LaunchContext {
+        LaunchContext {
+            icon: Some("app-icon".to_string()),
+            name: Some("My App".to_string()),
+            desktop_file: Some("/usr/share/applications/app.desktop".to_string()),
+        }
+    }
+
+    #[test]
+    fn split_quotes_and_escapes() {
+        assert_eq!(split("foo  bar").unwrap(), ["foo", "bar"]);
+        assert_eq!(split(r#"foo "a b" c"#).unwrap(), ["foo", "a b", "c"]);
+        assert_eq!(split(r#"foo """#).unwrap(), ["foo", ""]);
+        // Inside quotes `\"`, `\$` and `\\\\` (a backslash escaped twice) are literal
+        assert_eq!(
+            split(r#"echo "say \"hi\"" "\$HOME" "a\\\\b""#).unwrap(),
+            ["echo", r#"say "hi""#, "$HOME", r"a\b"]
+        );
+        // String escapes are undone first, `\s` is a space splitting arguments
+        assert_eq!(split(r"foo\sbar").unwrap(), ["foo", "bar"]);
+        // Field codes and %% are left for the launcher
+        assert_eq!(split("printf 100%% %f").unwrap(), ["printf", "100%%", "%f"]);
+    }
+
+    #[test]
+    fn split_errors() {
+        assert_eq!(split(r#"foo "bar"#), Err(ExecError::UnbalancedQuote));
+        assert_eq!(split(r"foo \"), Err(ExecError::TrailingBackslash));
+    }
+
+    #[test]
+    fn quote_only_when_needed() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote("%f"), "%f");
+        assert_eq!(quote(""), r#""""#);
+        assert_eq!(quote("a b"), r#""a b""#);
+        assert_eq!(quote("$HOME"), r#""\\$HOME""#);
+        // A literal backslash is written as four
+        assert_eq!(quote(r"a\b"), r#""a\\\\b""#);
+        assert_eq!(quote("tab\there"), r#""tab\there""#);
+    }
+
+    #[test]
+    fn quote_literal_doubles_percent() {
+        assert_eq!(quote_literal("50%"), "50%%");
+        assert_eq!(quote_literal("/my dir/%f"), r#""/my dir/%%f""#);
+    }
+
+    #[test]
+    fn join_round_trips() {
+        let args = [
+            "prog",
+            "a b",
+            "",
+            r"back\slash",
+            r#"say "hi""#,
+            "$HOME",
+            "`cmd`",
+            "tab\there",
+            "line\nbreak",
+            "semi;colon",
+            "%U",
+        ];
+        assert_eq!(split(&join(&args)).unwrap(), args);
+    }
+
+    #[test]
+    fn fix_quoting_leaves_valid_lines() {
+        assert_eq!(fix_quoting(r#"foo "a b" %f"#), None);
+        assert_eq!(fix_quoting("printf 100%%"), None);
+    }
+
+    #[test]
+    fn fix_quoting_reads_like_a_shell() {
+        assert_eq!(
+            fix_quoting("sh -c 'echo hi'").as_deref(),
+            Some(r#"sh -c "echo hi""#)
+        );
+        assert_eq!(
+            fix_quoting(r"foo my\ file").as_deref(),
+            Some(r#"foo "my file""#)
+        );
+        assert_eq!(fix_quoting("foo a|b").as_deref(), Some(r#"foo "a|b""#));
+        assert_eq!(fix_quoting(r#"foo "%f""#).as_deref(), Some("foo %f"));
+        assert_eq!(fix_quoting("printf 50%").as_deref(), Some("printf 50%%"));
+        assert_eq!(fix_quoting(r#"foo "bar"#).as_deref(), Some("foo bar"));
+    }
+
+    #[test]
+    fn expand_without_files() {
+        // A lone file code expands to nothing, one inside an argument leaves the rest
+        assert_eq!(expand_field_codes("prog %f", &ctx()).unwrap(), ["prog"]);
+        assert_eq!(
+            expand_field_codes("prog --file=%f %U -x", &ctx()).unwrap(),
+            ["prog", "--file=", "-x"]
+        );
+        assert_eq!(
+            expand_field_codes("prog %c %k", &ctx()).unwrap(),
+            ["prog", "My App", "/usr/share/applications/app.desktop"]
+        );
+        // %% is a literal %, even before a field code letter
+        assert_eq!(
+            expand_field_codes("printf 100%% %%f", &ctx()).unwrap(),
+            ["printf", "100%", "%f"]
+        );
+        assert_eq!(
+            expand_field_codes(&quote_literal("50%"), &ctx()).unwrap(),
+            ["50%"]
+        );
+    }
+
+    #[test]
+    fn expand_icon() {
+        assert_eq!(
+            expand_field_codes("prog %i", &ctx()).unwrap(),
+            ["prog", "--icon", "app-icon"]
+        );
+        assert_eq!(
+            expand_field_codes("prog %i", &LaunchContext::default()).unwrap(),
+            ["prog"]
+        );
+    }
+
+    #[test]
+    fn expand_bad_quoting() {
+        assert_eq!(
+            expand_field_codes(r#"prog "x"#, &ctx()),
+            Err(ExecError::UnbalancedQuote)
+        );
+    }
+}
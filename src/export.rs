@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Entries as JSON, for tooling and auditing.
+
+use freedesktop_desktop_entry::DesktopEntry;
+use serde_json::{Map, Value, json};
+use std::path::Path;
+
+/// Every group and key, each key as `{"value": …, "localized": {locale: …}}`.
+pub fn to_json(entry: &DesktopEntry, path: Option<&Path>) -> Value {
+    let mut groups = Map::new();
+    for (group_name, group) in &entry.groups.0 {
+        let mut keys = Map::new();
+        for (key, (value, locales)) in &group.0 {
+            let localized: Map<String, Value> = locales
+                .iter()
+                .map(|(locale, value)| (locale.to_string(), Value::from(value.as_str())))
+                .collect();
+            keys.insert(
+                key.to_string(),
+                json!({ "value": value, "localized": localized }),
+            );
+        }
+        groups.insert(group_name.to_string(), Value::Object(keys));
+    }
+
+    json!({
+        "path": path.map(|p| p.display().to_string()),
+        "groups": groups,
+    })
+}
+
+pub fn to_json_string(entry: &DesktopEntry, path: Option<&Path>) -> String {
+    // Serializing a Value can't fail
+    serde_json::to_string_pretty(&to_json(entry, path)).unwrap_or_default()
+}
+
+/// Print the files as JSON, an array when given several.
+/// Returns the process exit code: 0 if all were read, 2 otherwise.
+pub fn dump_files(paths: &[String]) -> i32 {
+    let mut code = 0;
+    let mut entries = Vec::new();
+    for arg in paths {
+        let path = crate::xdghelp::path_from_arg(arg);
+        match DesktopEntry::from_path::<&str>(&path, None) {
+            Ok(entry) => entries.push(to_json(&entry, Some(&path))),
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                code = 2;
+            }
+        }
+    }
+
+    if entries.is_empty() {
+        return code;
+    }
+    let out = if paths.len() == 1 {
+        entries.pop().unwrap_or(Value::Null)
+    } else {
+        Value::Array(entries)
+    };
+    println!("{}", serde_json::to_string_pretty(&out).unwrap_or_default());
+    code
+}
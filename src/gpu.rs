@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Graphics cards as switcheroo-control reports them, the service desktops ask
+//! which card PrefersNonDefaultGPU starts a program on.
+
+use std::collections::HashMap;
+use zbus::zvariant::OwnedValue;
+
+const SERVICE: &str = "net.hadess.SwitcherooControl";
+const OBJECT: &str = "/net/hadess/SwitcherooControl";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpu {
+    pub name: String,
+    // The card programs run on unless they prefer another
+    pub default: bool,
+}
+
+/// Every graphics card in the machine. Fails when switcheroo-control isn't running.
+pub async fn detect() -> Result<Vec<Gpu>, String> {
+    let connection = zbus::Connection::system()
+        .await
+        .map_err(|e| e.to_string())?;
+    let proxy = zbus::Proxy::new(&connection, SERVICE, OBJECT, SERVICE)
+        .await
+        .map_err(|e| e.to_string())?;
+    let gpus: Vec<HashMap<String, OwnedValue>> = proxy
+        .get_property("GPUs")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(gpus
+        .iter()
+        .map(|gpu| Gpu {
+            name: gpu
+                .get("Name")
+                .and_then(|v| v.downcast_ref::<&str>().ok())
+                .unwrap_or_default()
+                .to_string(),
+            default: gpu
+                .get("Default")
+                .and_then(|v| v.downcast_ref::<bool>().ok())
+                .unwrap_or(false),
+        })
+        .collect())
+}
+
+/// The card a program preferring the non-default GPU starts on.
+pub fn non_default(gpus: &[Gpu]) -> Option<&Gpu> {
+    gpus.iter().find(|g| !g.default)
+}
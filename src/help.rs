@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Short explanations of each key, following the Desktop Entry Specification,
+//! and the names the form gives keys, which screen readers announce.
+
+use crate::app::DesktopKey;
+use crate::fl;
+use crate::vendor;
+
+/// The label of `key`'s field, the key itself for keys without one.
+pub fn label(key: &DesktopKey) -> String {
+    match key {
+        DesktopKey::Name => fl!("field-name"),
+        DesktopKey::GenericName => fl!("field-genericname"),
+        DesktopKey::Comment => fl!("field-comment"),
+        DesktopKey::Icon => fl!("field-icon"),
+        DesktopKey::Exec => fl!("field-command"),
+        DesktopKey::TryExec => fl!("field-tryexec"),
+        DesktopKey::Terminal => fl!("field-runinterm"),
+        DesktopKey::Categories => fl!("field-categories"),
+        DesktopKey::Keywords => fl!("field-keywords"),
+        DesktopKey::OnlyShowIn => fl!("field-onlyshownin"),
+        DesktopKey::NotShowIn => fl!("field-notshownin"),
+        DesktopKey::StartupNotify => fl!("field-startupnotify"),
+        DesktopKey::StartupWMClass => fl!("field-startupwmclass"),
+        DesktopKey::DBusActivatable => fl!("field-dbusactivation"),
+        DesktopKey::NoDisplay => fl!("field-hide"),
+        DesktopKey::Hidden => fl!("field-hidden"),
+        DesktopKey::PrefersNonDefaultGPU => fl!("field-nondefaultgpu"),
+        DesktopKey::Implements => fl!("field-implements"),
+        DesktopKey::SingleMainWindow => fl!("field-singlemainwindow"),
+        DesktopKey::Url => fl!("field-url"),
+        DesktopKey::Version => fl!("field-version"),
+        DesktopKey::Path => fl!("field-workpath"),
+        DesktopKey::AutostartEnabled => fl!("field-autostartenabled"),
+        DesktopKey::AutostartDelay => fl!("field-autostartdelay"),
+        DesktopKey::AutostartCondition => fl!("field-autostartcondition"),
+        key => key.to_string(),
+    }
+}
+
+/// What `key` means to launchers and menus, None for keys the spec doesn't define.
+pub fn explain(key: &DesktopKey) -> Option<String> {
+    let text = match key {
+        DesktopKey::Type => fl!("help-type"),
+        DesktopKey::Name => fl!("help-name"),
+        DesktopKey::GenericName => fl!("help-genericname"),
+        DesktopKey::Comment => fl!("help-comment"),
+        DesktopKey::Icon => fl!("help-icon"),
+        DesktopKey::Exec => fl!("help-exec"),
+        DesktopKey::TryExec => fl!("help-tryexec"),
+        DesktopKey::Terminal => fl!("help-terminal"),
+        DesktopKey::Categories => fl!("help-categories"),
+        DesktopKey::Keywords => fl!("help-keywords"),
+        DesktopKey::MimeType => fl!("help-mimetype"),
+        DesktopKey::Actions => fl!("help-actions"),
+        DesktopKey::OnlyShowIn => fl!("help-onlyshowin"),
+        DesktopKey::NotShowIn => fl!("help-notshowin"),
+        DesktopKey::StartupNotify => fl!("help-startupnotify"),
+        DesktopKey::StartupWMClass => fl!("help-startupwmclass"),
+        DesktopKey::DBusActivatable => fl!("help-dbusactivatable"),
+        DesktopKey::NoDisplay => fl!("help-nodisplay"),
+        DesktopKey::Hidden => fl!("help-hidden"),
+        DesktopKey::PrefersNonDefaultGPU => fl!("help-prefersnondefaultgpu"),
+        DesktopKey::Implements => fl!("help-implements"),
+        DesktopKey::SingleMainWindow => fl!("help-singlemainwindow"),
+        DesktopKey::Url => fl!("help-url"),
+        DesktopKey::Version => fl!("help-version"),
+        DesktopKey::Path => fl!("help-path"),
+        DesktopKey::AutostartEnabled => fl!("help-autostartenabled"),
+        DesktopKey::AutostartDelay => fl!("help-autostartdelay"),
+        DesktopKey::AutostartCondition => fl!("help-autostartcondition"),
+        DesktopKey::Unknown(key) => return vendor::explain(key),
+    };
+    Some(text)
+}
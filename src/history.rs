@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Saved versions of each file, kept under `$XDG_STATE_HOME/launchedit/history`.
+
+use crate::xdghelp::state_path;
+use chrono::{DateTime, Local};
+use log::info;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub file: PathBuf,
+    pub saved: SystemTime,
+}
+
+impl Version {
+    /// When the version was saved, in local time.
+    pub fn label(&self) -> String {
+        DateTime::<Local>::from(self.saved)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+}
+
+// One directory per edited file.
+fn history_dir(path: &Path) -> Option<PathBuf> {
+    state_path("history", path)
+}
+
+/// Saved versions of `path`, newest first.
+pub fn versions(path: &Path) -> Vec<Version> {
+    let Some(dir) = history_dir(path) else {
+        return Vec::new();
+    };
+    let Ok(files) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    // Files are named by the milliseconds since the epoch they were saved at
+    let mut versions: Vec<Version> = files
+        .flatten()
+        .filter_map(|f| {
+            let file = f.path();
+            let millis: u64 = file.file_stem()?.to_str()?.parse().ok()?;
+            let saved = UNIX_EPOCH + std::time::Duration::from_millis(millis);
+            Some(Version { file, saved })
+        })
+        .collect();
+    versions.sort_by(|a, b| b.saved.cmp(&a.saved));
+    versions
+}
+
+/// Remember `contents` as the newest saved version of `path`, keeping at most
+/// `keep` versions. Saving unchanged contents adds no version.
+pub fn record(path: &Path, contents: &str, keep: u32) -> std::io::Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    let Some(dir) = history_dir(path) else {
+        return Ok(());
+    };
+
+    let existing = versions(path);
+    let unchanged = existing
+        .first()
+        .and_then(|v| fs::read_to_string(&v.file).ok())
+        .is_some_and(|newest| newest == contents);
+    if !unchanged {
+        fs::create_dir_all(&dir)?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        fs::write(dir.join(format!("{millis}.desktop")), contents)?;
+        info!(
+            "Recorded a version of {} in {}",
+            path.display(),
+            dir.display()
+        );
+    }
+
+    for old in versions(path).iter().skip(keep as usize) {
+        fs::remove_file(&old.file)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! On-disk cache of icon scan results, so later launches don't have to walk
+//! the icon directories again unless they changed.
+
+use crate::xdghelp::IconCache;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const INDEX_HEADER: &str = "# launchedit icon index v1";
+const BASE_PREFIX: &str = "@ ";
+
+#[derive(Debug, Default)]
+pub struct IconIndex {
+    // Base directory -> (fingerprint, icon paths in priority order)
+    bases: HashMap<PathBuf, (u64, Vec<PathBuf>)>,
+    dirty: bool,
+}
+
+impl IconIndex {
+    fn index_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|c| c.join("launchedit").join("icon-index"))
+    }
+
+    /// Read the index from `$XDG_CACHE_HOME/launchedit/icon-index`.
+    /// A missing or unreadable index just means everything gets rescanned.
+    fn load() -> Self {
+        let mut index = Self::default();
+        let Some(path) = Self::index_path() else {
+            return index;
+        };
+        let Ok(text) = fs::read_to_string(&path) else {
+            return index;
+        };
+
+        let mut lines = text.lines();
+        if lines.next() != Some(INDEX_HEADER) {
+            warn!("Ignoring icon index with unknown format {}", path.display());
+            return index;
+        }
+
+        let mut current: Option<PathBuf> = None;
+        for line in lines {
+            if let Some(rest) = line.strip_prefix(BASE_PREFIX) {
+                // "@ <fingerprint> <base dir>"
+                current = rest.split_once(' ').and_then(|(fp, base)| {
+                    let fp = u64::from_str_radix(fp, 16).ok()?;
+                    let base = PathBuf::from(base);
+                    index.bases.insert(base.clone(), (fp, Vec::new()));
+                    Some(base)
+                });
+            } else if let Some(base) = &current
+                && !line.is_empty()
+                && let Some((_, paths)) = index.bases.get_mut(base)
+            {
+                paths.push(PathBuf::from(line));
+            }
+        }
+
+        info!("Icon index: Loaded {} base directories", index.bases.len());
+        index
+    }
+
+    /// Write the index back if anything was rescanned.
+    fn save(&self) {
+        if !self.dirty {
+            return;
+        }
+        let Some(path) = Self::index_path() else {
+            return;
+        };
+
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut out = std::io::BufWriter::new(fs::File::create(&path)?);
+            writeln!(out, "{INDEX_HEADER}")?;
+            for (base, (fp, paths)) in &self.bases {
+                writeln!(out, "{BASE_PREFIX}{fp:016x} {}", base.display())?;
+                for p in paths {
+                    writeln!(out, "{}", p.display())?;
+                }
+            }
+            out.flush()
+        };
+
+        if let Err(e) = write() {
+            warn!("Failed to write icon index {}: {e}", path.display());
+        }
+    }
+
+    /// Resolve every icon search directory, through the index where possible,
+    /// handing each directory's paths to `found` in priority order.
+    /// This is blocking I/O, run it off the UI thread.
+    pub fn scan_all(mut found: impl FnMut(Vec<PathBuf>)) {
+        let bases = IconCache::icon_search_dirs();
+        let themes = IconCache::theme_chain(&bases);
+        let mut index = Self::load();
+
+        for base in &bases {
+            let paths = index.resolve(base, &themes);
+            if !paths.is_empty() {
+                found(paths);
+            }
+        }
+
+        index.retain_bases(&bases);
+        index.save();
+    }
+
+    /// Icon paths below `base`, from the index when the directory mtimes still
+    /// match, otherwise from a fresh scan which then replaces the indexed copy.
+    /// This is blocking I/O.
+    fn resolve(&mut self, base: &Path, themes: &[String]) -> Vec<PathBuf> {
+        let fp = Self::fingerprint(base, themes);
+
+        if let Some((cached_fp, paths)) = self.bases.get(base)
+            && *cached_fp == fp
+        {
+            return paths.clone();
+        }
+
+        info!("Icon index: Rescanning {}", base.display());
+        let paths = IconCache::scan_base(base, themes);
+        self.bases.insert(base.to_owned(), (fp, paths.clone()));
+        self.dirty = true;
+        paths
+    }
+
+    /// Forget base directories that are no longer searched.
+    fn retain_bases(&mut self, bases: &[PathBuf]) {
+        let before = self.bases.len();
+        self.bases.retain(|b, _| bases.contains(b));
+        self.dirty |= before != self.bases.len();
+    }
+
+    // Hash of the modification times of the base, every directory scan_base walks
+    // and each theme's icon-theme.cache. Adding or removing icons changes the mtime
+    // of the directory holding them, or for directories nested deeper at least the
+    // cache installers rebuild, and switching icon theme changes the directories walked.
+    fn fingerprint(base: &Path, themes: &[String]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let mut paths = vec![base.to_owned()];
+        paths.extend(themes.iter().map(|t| base.join(t).join("icon-theme.cache")));
+        paths.extend(IconCache::scanned_dirs(base, themes));
+
+        for path in paths {
+            let mtime = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos());
+            path.hash(&mut hasher);
+            mtime.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
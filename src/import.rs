@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Entries created from other launcher formats.
+
+use crate::app::DesktopEntryType;
+use crate::entrydiff::set_value;
+use freedesktop_desktop_entry::DesktopEntry;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A Link entry from a Windows Internet Shortcut (.url file), named after the file.
+pub fn url_shortcut(path: &Path) -> Result<DesktopEntry, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let text = String::from_utf8_lossy(&bytes);
+    let url = shortcut_url(&text)
+        .ok_or_else(|| format!("{} has no [InternetShortcut] URL", path.display()))?;
+
+    let name = path
+        .file_stem()
+        .map_or_else(|| url.clone(), |s| s.to_string_lossy().into_owned());
+    let mut entry = DesktopEntry::from_appid(name.clone());
+    entry.add_desktop_entry("Type".to_string(), DesktopEntryType::Link.to_string());
+    entry.add_desktop_entry("Name".to_string(), name);
+    entry.add_desktop_entry("URL".to_string(), url);
+    entry.add_desktop_entry("Icon".to_string(), "text-html".to_string());
+    Ok(entry)
+}
+
+// URL= from the [InternetShortcut] section. Windows writes these with a BOM and
+// CRLF line endings, and section and key names are case insensitive.
+fn shortcut_url(text: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in text.trim_start_matches('\u{feff}').lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+        } else if in_section
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim().eq_ignore_ascii_case("URL")
+            && !value.trim().is_empty()
+        {
+            return Some(value.trim().to_string());
+        }
+    }
+    None
+}
+
+/// An entry from desktop file text, as pasted from another machine or a chat.
+pub fn pasted_entry(text: &str) -> Result<DesktopEntry, String> {
+    let entry = DesktopEntry::from_str(
+        PathBuf::new(),
+        text.trim_start_matches('\u{feff}'),
+        None::<&[&str]>,
+    )
+    .map_err(|e| e.to_string())?;
+    if !entry.groups.0.contains_key("Desktop Entry") {
+        return Err("The clipboard holds no [Desktop Entry] group".to_string());
+    }
+    Ok(entry)
+}
+
+/// An entry from JSON shaped like File → Export writes it: `{"groups": {group:
+/// {key: {"value": …, "localized": {locale: …}}}}}`. A key may also be a plain
+/// string, or an object with only `localized`.
+pub fn json_entry(path: &Path) -> Result<DesktopEntry, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let json: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let groups = json
+        .get("groups")
+        .and_then(Value::as_object)
+        .ok_or_else(|| format!("{} has no \"groups\" object", path.display()))?;
+    if !groups.contains_key("Desktop Entry") {
+        return Err(format!("{} has no \"Desktop Entry\" group", path.display()));
+    }
+
+    let name = path
+        .file_stem()
+        .map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+    let mut entry = DesktopEntry::from_appid(name);
+    for (group, keys) in groups {
+        let keys = keys
+            .as_object()
+            .ok_or_else(|| format!("Group [{group}] is not an object"))?;
+        for (key, value) in keys {
+            match value {
+                Value::Object(fields) => {
+                    if let Some(value) = fields.get("value").and_then(scalar) {
+                        set_value(&mut entry, group, key, Some(&value));
+                    }
+                    let localized = fields.get("localized").and_then(Value::as_object);
+                    for (locale, value) in localized.into_iter().flatten() {
+                        if let Some(value) = scalar(value) {
+                            set_value(&mut entry, group, &format!("{key}[{locale}]"), Some(&value));
+                        }
+                    }
+                }
+                value => {
+                    let value = scalar(value)
+                        .ok_or_else(|| format!("[{group}] {key} is not a text value"))?;
+                    set_value(&mut entry, group, key, Some(&value));
+                }
+            }
+        }
+    }
+    Ok(entry)
+}
+
+// Numbers and booleans are accepted as their text, desktop files only store text.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
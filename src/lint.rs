@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Whole-entry validation, the checks the editor shows next to each field.
+
+use crate::app::DesktopEntryType;
+use crate::exec::{self, ExecProblem};
+use crate::mimelist::is_valid_mimetype;
+use crate::xdghelp::{dbus_activation_warnings, find_dbus_service};
+use crate::{categories, fl};
+use freedesktop_desktop_entry::DesktopEntry;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Spec versions an entry can declare in Version, oldest first.
+pub const SPEC_VERSIONS: &[&str] = &["1.0", "1.1", "1.5"];
+
+// Keys added after 1.0, with the spec version that introduced them.
+const KEY_VERSIONS: &[(&str, (u32, u32))] = &[
+    ("Actions", (1, 1)),
+    ("DBusActivatable", (1, 1)),
+    ("Implements", (1, 1)),
+    ("Keywords", (1, 1)),
+    ("PrefersNonDefaultGPU", (1, 4)),
+    ("SingleMainWindow", (1, 5)),
+];
+
+// Keys the spec deprecated or only old KDE releases read, with what replaced them.
+const DEPRECATED_KEYS: &[(&str, Option<&str>)] = &[
+    ("Encoding", None),
+    ("MiniIcon", None),
+    ("TerminalOptions", None),
+    ("DocPath", None),
+    ("SwallowTitle", None),
+    ("SwallowExec", None),
+    ("SortOrder", None),
+    ("FilePattern", None),
+    ("Protocols", None),
+    ("Extensions", None),
+    ("BinaryPattern", None),
+    ("MapNotify", Some("StartupNotify")),
+    ("X-KDE-StartupNotify", Some("StartupNotify")),
+    ("X-KDE-SubstituteUID", None),
+    ("X-KDE-Username", None),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub severity: Severity,
+    pub key: &'static str,
+    pub message: String,
+}
+
+impl Lint {
+    fn error(key: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            key,
+            message,
+        }
+    }
+
+    fn warning(key: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key,
+            message,
+        }
+    }
+}
+
+/// Check an entry loaded from `path`, errors first.
+pub fn lint(entry: &DesktopEntry, path: Option<&Path>) -> Vec<Lint> {
+    let mut lints = Vec::new();
+
+    let kind = match entry.desktop_entry("Type") {
+        None => {
+            lints.push(Lint::error("Type", fl!("lint-missing", key = "Type")));
+            None
+        }
+        Some(t) => match DesktopEntryType::from_str(t) {
+            Ok(kind) => Some(kind),
+            Err(()) => {
+                lints.push(Lint::error("Type", fl!("lint-badtype", value = t)));
+                None
+            }
+        },
+    };
+    if entry.desktop_entry("Name").is_none_or(str::is_empty) {
+        lints.push(Lint::error("Name", fl!("lint-missing", key = "Name")));
+    }
+
+    match kind {
+        Some(DesktopEntryType::Application) => lint_application(entry, path, &mut lints),
+        Some(DesktopEntryType::Link) => {
+            if entry.desktop_entry("URL").is_none_or(str::is_empty) {
+                lints.push(Lint::error("URL", fl!("lint-missing", key = "URL")));
+            }
+        }
+        Some(DesktopEntryType::Directory) | None => {}
+    }
+
+    if entry.desktop_entry("OnlyShowIn").is_some() && entry.desktop_entry("NotShowIn").is_some() {
+        lints.push(Lint::error("NotShowIn", fl!("showin-conflict")));
+    }
+    lints.extend(version_lints(entry));
+    for key in exec::QUOTED_KEYS {
+        if let Some(fixed) = entry
+            .desktop_entry(key)
+            .and_then(|v| exec::fix_key_quoting(key, v))
+        {
+            let message = fl!("lint-quoting", key = key, fixed = fixed);
+            lints.push(Lint::warning(key, message));
+        }
+    }
+    for (key, replacement) in deprecated_keys(entry) {
+        let message = match replacement {
+            Some(replacement) => fl!("lint-deprecatedby", key = key, replacement = replacement),
+            None => fl!("lint-deprecated", key = key),
+        };
+        lints.push(Lint::warning(key, message));
+    }
+
+    lints.sort_by(|a, b| b.severity.cmp(&a.severity));
+    lints
+}
+
+fn lint_application(entry: &DesktopEntry, path: Option<&Path>, lints: &mut Vec<Lint>) {
+    match entry.exec() {
+        Some(exec) => {
+            for problem in exec::check(exec) {
+                let lint = match problem {
+                    ExecProblem::Syntax(_) | ExecProblem::Empty => Lint::error,
+                    _ => Lint::warning,
+                };
+                lints.push(lint("Exec", problem.to_string()));
+            }
+        }
+        // A D-Bus activated application may be started without Exec
+        None if !entry.dbus_activatable() => {
+            lints.push(Lint::error("Exec", fl!("lint-missing", key = "Exec")));
+        }
+        None => {}
+    }
+
+    if entry.dbus_activatable() {
+        let service = path
+            .and_then(Path::file_stem)
+            .and_then(|s| find_dbus_service(&s.to_string_lossy()));
+        for warning in dbus_activation_warnings(path, service.as_deref()) {
+            lints.push(Lint::warning("DBusActivatable", warning));
+        }
+    }
+
+    let categories = entry.categories().unwrap_or_default();
+    let categories: Vec<&str> = categories.into_iter().filter(|c| !c.is_empty()).collect();
+    if !categories.iter().any(|c| categories::is_main(c)) {
+        lints.push(Lint::warning("Categories", fl!("categories-nomain")));
+    }
+    let unknown: Vec<&str> = categories
+        .into_iter()
+        .filter(|c| categories::is_unknown(c))
+        .collect();
+    if !unknown.is_empty() {
+        lints.push(Lint::warning(
+            "Categories",
+            fl!("categories-unknown", names = unknown.join(", ")),
+        ));
+    }
+
+    let invalid: Vec<&str> = entry
+        .mime_type()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| !m.is_empty() && !is_valid_mimetype(m))
+        .collect();
+    if !invalid.is_empty() {
+        lints.push(Lint::warning(
+            "MimeType",
+            fl!("lint-badmime", names = invalid.join(", ")),
+        ));
+    }
+}
+
+/// Deprecated keys in the main group, with the key replacing each if there is one.
+pub fn deprecated_keys(entry: &DesktopEntry) -> Vec<(&'static str, Option<&'static str>)> {
+    DEPRECATED_KEYS
+        .iter()
+        .filter(|(key, _)| entry.desktop_entry(key).is_some())
+        .copied()
+        .collect()
+}
+
+/// Keys the spec version declared in Version doesn't have yet. An entry without
+/// Version is taken to follow the current spec.
+pub fn version_lints(entry: &DesktopEntry) -> Vec<Lint> {
+    let Some(version) = entry.desktop_entry("Version") else {
+        return Vec::new();
+    };
+    let Some(declared) = parse_version(version) else {
+        return vec![Lint::warning(
+            "Version",
+            fl!("lint-badversion", value = version),
+        )];
+    };
+
+    KEY_VERSIONS
+        .iter()
+        .filter(|(key, since)| *since > declared && entry.desktop_entry(key).is_some())
+        .map(|(key, (major, minor))| {
+            let message = fl!(
+                "lint-newerkey",
+                key = *key,
+                since = format!("{major}.{minor}"),
+                version = version
+            );
+            Lint::warning("Version", message)
+        })
+        .collect()
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.trim().split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Print the problems of each file, as `file: severity: [Key] message`.
+/// Returns the process exit code: 0 if fine, 1 on errors, 2 if a file couldn't be read.
+pub fn validate_files(paths: &[String]) -> i32 {
+    let mut code = 0;
+    for arg in paths {
+        let path = crate::xdghelp::path_from_arg(arg);
+        let entry = match DesktopEntry::from_path::<&str>(&path, None) {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                code = 2;
+                continue;
+            }
+        };
+        for lint in lint(&entry, Some(&path)) {
+            println!(
+                "{}: {}: [{}] {}",
+                path.display(),
+                lint.severity,
+                lint.key,
+                lint.message
+            );
+            if lint.severity == Severity::Error && code == 0 {
+                code = 1;
+            }
+        }
+    }
+    code
+}
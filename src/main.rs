@@ -1,14 +1,45 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 mod app;
+mod appstream;
+mod backup;
+mod categories;
+mod cli;
 mod config;
+mod document;
+mod elevate;
+mod entrydiff;
+mod exec;
+mod export;
+mod gpu;
+mod help;
+mod history;
 mod i18n;
+mod iconindex;
+mod import;
+mod lint;
+mod merge;
+mod mimeapps;
 mod mimelist;
+mod normalize;
+mod packaging;
+mod quickopen;
+mod systemd;
+mod templates;
+mod thumbnailer;
+mod toplevels;
+mod trash;
+mod vendor;
+mod webapp;
+mod wrappers;
 mod xdghelp;
+mod xdgmenu;
 mod xkeys;
 
 use chrono::Local;
-use cosmic::iced::Limits;
+use cosmic::Application;
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::{Limits, Size};
 use log::info;
 use std::io;
 
@@ -32,19 +63,52 @@ fn setup_logger() -> Result<(), Box<dyn std::error::Error>> {
 fn main() -> cosmic::iced::Result {
     setup_logger().expect("Failed to initialize logger");
 
-    info!("Application started");
     // Get the system's preferred languages.
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
 
     // Enable localizations to be applied.
     i18n::init(&requested_languages);
 
-    let settings = cosmic::app::Settings::default().size_limits(
+    let mut settings = cosmic::app::Settings::default().size_limits(
         Limits::NONE
             .min_width(360.0)
             .min_height(300.0)
     );
 
-    // Starts the application's event loop with `()` as the application's flags.
-    cosmic::app::run::<app::AppModel>(settings, ())
+    // Open at the size the window had when it was last closed
+    let config = cosmic_config::Config::new(app::AppModel::APP_ID, config::Config::VERSION)
+        .ok()
+        .map(|c| config::Config::get_entry(&c).unwrap_or_else(|(_, config)| config))
+        .unwrap_or_default();
+    if config.window_width > 0 && config.window_height > 0 {
+        settings = settings.size(Size::new(
+            config.window_width as f32,
+            config.window_height as f32,
+        ));
+    }
+
+    let mut cli = match cli::Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("launchedit: {e}\n\n{}", fl!("cli-usage"));
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", fl!("cli-usage"));
+        return Ok(());
+    }
+    if cli.validate {
+        std::process::exit(lint::validate_files(&cli.paths));
+    }
+    if cli.dump_json {
+        std::process::exit(export::dump_files(&cli.paths));
+    }
+
+    info!("Application started");
+
+    // A running instance has its own working directory, relative paths mean ours
+    cli.make_paths_absolute();
+    // Hands the arguments to an already running instance if there is one.
+    cosmic::app::run_single_instance::<app::AppModel>(settings, app::Flags::new(cli))
 }
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Three-way comparison of an override with the system entry it shadows, using
+//! the system entry as it was when the override was made as the common base.
+
+use crate::entrydiff::flatten;
+use crate::xdghelp::state_path;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MergeState {
+    // Only the system entry changed since the override was made
+    Upstream,
+    // Both changed the key, differently
+    Conflict,
+    // Only the override changed it
+    Mine,
+    // No base is known, the two simply differ
+    Differs,
+}
+
+/// A key where the override and the system entry disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeKey {
+    pub group: String,
+    pub key: String,
+    pub base: Option<String>,
+    pub system: Option<String>,
+    pub mine: Option<String>,
+    pub state: MergeState,
+}
+
+/// Remember the system entry's text as the base of the override at `override_path`.
+pub fn record_base(override_path: &Path, system_text: &str) -> std::io::Result<()> {
+    let Some(base) = state_path("override-base", override_path) else {
+        return Ok(());
+    };
+    if let Some(parent) = base.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(base, system_text)
+}
+
+/// The system entry as it was when the override at `override_path` was made.
+pub fn load_base(override_path: &Path) -> Option<DesktopEntry> {
+    let base = state_path("override-base", override_path)?;
+    let text = fs::read_to_string(&base).ok()?;
+    DesktopEntry::from_str(&base, &text, None::<&[&str]>).ok()
+}
+
+/// Keys where `mine` differs from `system`, sorted with upstream changes first.
+pub fn three_way(
+    base: Option<&DesktopEntry>,
+    system: &DesktopEntry,
+    mine: &DesktopEntry,
+) -> Vec<MergeKey> {
+    let base = base.map(flatten);
+    let system = flatten(system);
+    let mine = flatten(mine);
+    let keys: BTreeSet<_> = system.keys().chain(mine.keys()).collect();
+
+    let mut merged: Vec<MergeKey> = keys
+        .into_iter()
+        .filter(|k| system.get(*k) != mine.get(*k))
+        .map(|k| {
+            let (s, m) = (system.get(k).cloned(), mine.get(k).cloned());
+            let b = base.as_ref().and_then(|b| b.get(k).cloned());
+            let state = match &base {
+                None => MergeState::Differs,
+                Some(_) if s == b => MergeState::Mine,
+                Some(_) if m == b => MergeState::Upstream,
+                Some(_) => MergeState::Conflict,
+            };
+            MergeKey {
+                group: k.0.clone(),
+                key: k.1.clone(),
+                base: b,
+                system: s,
+                mine: m,
+                state,
+            }
+        })
+        .collect();
+    merged.sort_by_key(|m| m.state);
+    merged
+}
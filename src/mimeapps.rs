@@ -0,0 +1,488 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Default applications from `mimeapps.list`, per the MIME Applications Associations spec.
+
+use crate::xdghelp::{application_dirs, system_config_dirs, system_data_dirs};
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// `mimeapps.list` files from highest to lowest precedence.
+pub fn search_paths() -> Vec<PathBuf> {
+    // Desktop specific files, e.g. cosmic-mimeapps.list, go before the generic one
+    let desktops: Vec<String> = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let names: Vec<String> = desktops
+        .iter()
+        .map(|d| format!("{d}-mimeapps.list"))
+        .chain(std::iter::once("mimeapps.list".to_string()))
+        .collect();
+
+    let config_dirs = dirs::config_dir().into_iter().chain(system_config_dirs());
+    let data_dirs = dirs::data_dir()
+        .into_iter()
+        .chain(system_data_dirs())
+        .map(|d| d.join("applications"));
+
+    config_dirs
+        .chain(data_dirs)
+        .flat_map(|dir| names.iter().map(move |n| dir.join(n)))
+        .collect()
+}
+
+/// The `[Default Applications]` of every mimeapps.list, merged by precedence.
+#[derive(Debug, Clone, Default)]
+pub struct MimeApps {
+    defaults: HashMap<String, Vec<String>>,
+}
+
+impl MimeApps {
+    pub fn load() -> Self {
+        let mut apps = Self::default();
+        for path in search_paths() {
+            if let Ok(text) = fs::read_to_string(&path) {
+                info!("Reading default applications from {}", path.display());
+                apps.merge(&text);
+            }
+        }
+        apps
+    }
+
+    // Files are read from highest precedence down, so the first value seen wins.
+    fn merge(&mut self, text: &str) {
+        for (mime, apps) in parse_section(text, "Default Applications") {
+            self.defaults.entry(mime).or_insert(apps);
+        }
+    }
+
+    /// Desktop file id of the preferred application for `mime`.
+    pub fn default_for(&self, mime: &str) -> Option<&str> {
+        self.defaults
+            .get(mime)
+            .and_then(|apps| apps.first())
+            .map(String::as_str)
+    }
+}
+
+/// Where an application considered for a mimetype was listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default(PathBuf),
+    Added(PathBuf),
+    // The MimeType key of the application's own desktop file
+    Declared,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    // The application the file opens with
+    Opens,
+    // Usable, but one listed before it wins
+    Fallback,
+    NotInstalled,
+    // Taken out by the [Removed Associations] of this mimeapps.list
+    Removed(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub id: String,
+    pub source: Source,
+    pub verdict: Verdict,
+}
+
+/// What opening a sample file resolves to, for the "which app opens this" tester.
+#[derive(Debug, Clone)]
+pub struct OpenTest {
+    pub file: PathBuf,
+    pub mime: String,
+    // Every application considered, in the order they are tried
+    pub candidates: Vec<Candidate>,
+}
+
+impl OpenTest {
+    pub fn new(file: PathBuf, mime: String) -> Self {
+        let candidates = resolve(&mime);
+        Self {
+            file,
+            mime,
+            candidates,
+        }
+    }
+
+    pub fn winner(&self) -> Option<&str> {
+        self.candidates
+            .iter()
+            .find(|c| c.verdict == Verdict::Opens)
+            .map(|c| c.id.as_str())
+    }
+}
+
+/// The applications tried for `mime` as the spec orders them: the defaults of
+/// each mimeapps.list by precedence, then their added associations, then the
+/// applications declaring the type. Removed associations hide an application
+/// from the associations of their own and lower precedence files only.
+pub fn resolve(mime: &str) -> Vec<Candidate> {
+    let installed: HashSet<String> = installed_desktop_files()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let lists: Vec<(PathBuf, String)> = search_paths()
+        .into_iter()
+        .filter_map(|path| fs::read_to_string(&path).ok().map(|text| (path, text)))
+        .collect();
+    let apps_for = |text: &str, section: &str| -> Vec<String> {
+        parse_section(text, section)
+            .into_iter()
+            .filter(|(m, _)| m == mime)
+            .flat_map(|(_, apps)| apps)
+            .collect()
+    };
+
+    let mut candidates = Vec::new();
+    for (path, text) in &lists {
+        for id in apps_for(text, "Default Applications") {
+            let verdict = if installed.contains(&id) {
+                Verdict::Fallback
+            } else {
+                Verdict::NotInstalled
+            };
+            candidates.push(Candidate {
+                id,
+                source: Source::Default(path.clone()),
+                verdict,
+            });
+        }
+    }
+
+    let mut removed: Vec<(String, PathBuf)> = Vec::new();
+    let verdict = |id: &String, removed: &[(String, PathBuf)]| {
+        if let Some((_, path)) = removed.iter().find(|(r, _)| r == id) {
+            Verdict::Removed(path.clone())
+        } else if installed.contains(id) {
+            Verdict::Fallback
+        } else {
+            Verdict::NotInstalled
+        }
+    };
+    for (path, text) in &lists {
+        removed.extend(
+            apps_for(text, "Removed Associations")
+                .into_iter()
+                .map(|id| (id, path.clone())),
+        );
+        for id in apps_for(text, "Added Associations") {
+            candidates.push(Candidate {
+                verdict: verdict(&id, &removed),
+                id,
+                source: Source::Added(path.clone()),
+            });
+        }
+    }
+    let declared = installed_handlers().remove(mime).unwrap_or_default();
+    for id in declared {
+        candidates.push(Candidate {
+            verdict: verdict(&id, &removed),
+            id,
+            source: Source::Declared,
+        });
+    }
+
+    if let Some(first) = candidates
+        .iter_mut()
+        .find(|c| c.verdict == Verdict::Fallback)
+    {
+        first.verdict = Verdict::Opens;
+    }
+    candidates
+}
+
+/// Make `id` the default application for `mime` in the user's mimeapps.list.
+pub fn set_user_default(mime: &str, id: &str) -> std::io::Result<()> {
+    let path = MimeAppsFile::user_path()
+        .ok_or_else(|| std::io::Error::other("No configuration directory"))?;
+    let mut file = MimeAppsFile::load(path)?;
+
+    let defaults = &mut file.sections[0];
+    let row = match defaults.iter().position(|(m, _)| m == mime) {
+        Some(row) => row,
+        None => {
+            defaults.push((mime.to_string(), String::new()));
+            defaults.len() - 1
+        }
+    };
+    let apps: Vec<String> = std::iter::once(id.to_string())
+        .chain(split_apps(&defaults[row].1).into_iter().filter(|a| a != id))
+        .collect();
+    defaults[row].1 = join_apps(&apps);
+    file.save()
+}
+
+/// `mimetype=app1.desktop;app2.desktop;` lines of one `[section]`.
+pub fn parse_section(text: &str, section: &str) -> Vec<(String, Vec<String>)> {
+    let mut in_section = false;
+    let mut out = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+            continue;
+        }
+        if in_section && let Some((mime, apps)) = line.split_once('=') {
+            let apps = apps
+                .split(';')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(ToString::to_string)
+                .collect();
+            out.push((mime.trim().to_string(), apps));
+        }
+    }
+    out
+}
+
+/// The sections of mimeapps.list edited by the mimeapps editor.
+pub const SECTIONS: [&str; 3] = [
+    "Default Applications",
+    "Added Associations",
+    "Removed Associations",
+];
+
+/// A user's mimeapps.list opened for editing. Sections other than
+/// `SECTIONS` are written back unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct MimeAppsFile {
+    pub path: PathBuf,
+    // Per section: mimetype and its `;` separated desktop file ids, as typed
+    pub sections: [Vec<(String, String)>; 3],
+    other: String,
+}
+
+impl MimeAppsFile {
+    /// `$XDG_CONFIG_HOME/mimeapps.list`, the file the spec says users write to.
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("mimeapps.list"))
+    }
+
+    /// Read `path`, a missing file gives an empty list.
+    pub fn load(path: PathBuf) -> std::io::Result<Self> {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let sections = SECTIONS.map(|section| {
+            parse_section(&text, section)
+                .into_iter()
+                .map(|(mime, apps)| (mime, join_apps(&apps)))
+                .collect()
+        });
+
+        // Keep everything outside our sections verbatim
+        let mut other = String::new();
+        let mut keep = true;
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                keep = !SECTIONS.contains(&name);
+            }
+            if keep {
+                other.push_str(line);
+                other.push('\n');
+            }
+        }
+
+        Ok(Self {
+            path,
+            sections,
+            other,
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = self.other.trim_end().to_string();
+        for (name, rows) in SECTIONS.iter().zip(&self.sections) {
+            let rows: Vec<_> = rows
+                .iter()
+                .map(|(mime, apps)| (mime.trim(), split_apps(apps)))
+                .filter(|(mime, apps)| !mime.is_empty() && !apps.is_empty())
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&format!("[{name}]"));
+            for (mime, apps) in rows {
+                text.push_str(&format!("\n{mime}={}", join_apps(&apps)));
+            }
+        }
+        text.push('\n');
+        text
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.to_text())
+    }
+}
+
+pub fn split_apps(apps: &str) -> Vec<String> {
+    apps.split(';')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+pub fn join_apps<S: AsRef<str>>(apps: &[S]) -> String {
+    apps.iter().map(|a| format!("{};", a.as_ref())).collect()
+}
+
+/// Installed launchers as (desktop file id, path), the first directory providing an id wins.
+pub fn installed_desktop_files() -> Vec<(String, PathBuf)> {
+    let mut seen = std::collections::HashSet::new();
+    let mut installed = Vec::new();
+
+    for dir in application_dirs() {
+        let mut files = Vec::new();
+        collect_desktop_files(&dir, &mut files);
+        for file in files {
+            // Subdirectories become part of the id: kde/foo.desktop is kde-foo.desktop
+            let Ok(relative) = file.strip_prefix(&dir) else {
+                continue;
+            };
+            let id = relative.to_string_lossy().replace('/', "-");
+            if seen.insert(id.clone()) {
+                installed.push((id, file));
+            }
+        }
+    }
+    installed
+}
+
+/// Installed applications by the mimetypes they declare, as desktop file ids.
+pub fn installed_handlers() -> HashMap<String, Vec<String>> {
+    let mut handlers: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (id, file) in installed_desktop_files() {
+        let Ok(text) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for (_, mimes) in parse_section(&text, "Desktop Entry")
+            .into_iter()
+            .filter(|(key, _)| key == "MimeType")
+        {
+            for mime in mimes {
+                handlers.entry(mime).or_default().push(id.clone());
+            }
+        }
+    }
+
+    for ids in handlers.values_mut() {
+        ids.sort();
+    }
+    handlers
+}
+
+fn collect_desktop_files(dir: &std::path::Path, out: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_desktop_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "desktop") {
+            out.push(path);
+        }
+    }
+}
+
+/// State of the mimeapps.list editor window.
+#[derive(Debug, Clone, Default)]
+pub struct MimeAppsEditor {
+    pub file: MimeAppsFile,
+    pub handlers: HashMap<String, Vec<String>>,
+    // Per section, the mimetype typed into its add field
+    pub new_mime: [String; 3],
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+impl MimeAppsEditor {
+    pub fn open() -> std::io::Result<Self> {
+        let path = MimeAppsFile::user_path()
+            .ok_or_else(|| std::io::Error::other("No configuration directory"))?;
+        Ok(Self {
+            file: MimeAppsFile::load(path)?,
+            handlers: installed_handlers(),
+            ..Self::default()
+        })
+    }
+
+    pub fn set_apps(&mut self, section: usize, row: usize, apps: String) {
+        if let Some(r) = self.file.sections[section].get_mut(row) {
+            r.1 = apps;
+            self.changed = true;
+        }
+    }
+
+    /// Add `app` to a row: first for defaults, appended for associations.
+    pub fn pick(&mut self, section: usize, row: usize, app: &str) {
+        let Some(r) = self.file.sections[section].get_mut(row) else {
+            return;
+        };
+        let mut apps: Vec<String> = split_apps(&r.1).into_iter().filter(|a| a != app).collect();
+        if section == 0 {
+            apps.insert(0, app.to_string());
+        } else {
+            apps.push(app.to_string());
+        }
+        r.1 = join_apps(&apps);
+        self.changed = true;
+    }
+
+    pub fn remove(&mut self, section: usize, row: usize) {
+        if row < self.file.sections[section].len() {
+            self.file.sections[section].remove(row);
+            self.changed = true;
+        }
+    }
+
+    pub fn add(&mut self, section: usize) {
+        let mime = std::mem::take(&mut self.new_mime[section])
+            .trim()
+            .to_string();
+        if mime.is_empty() {
+            return;
+        }
+        let rows = &mut self.file.sections[section];
+        if !rows.iter().any(|(m, _)| *m == mime) {
+            rows.push((mime, String::new()));
+            self.changed = true;
+        }
+    }
+
+    pub fn save(&mut self) {
+        match self.file.save() {
+            Ok(()) => {
+                self.changed = false;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
@@ -1,11 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use crate::mimeapps::MimeApps;
 use cosmic::iced;
 use cosmic::widget::table;
 use log::info;
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
@@ -13,6 +14,8 @@ pub enum MimeCategory {
     #[default]
     Name,
     Description,
+    Globs,
+    Handler,
 }
 
 impl std::fmt::Display for MimeCategory {
@@ -20,6 +23,8 @@ impl std::fmt::Display for MimeCategory {
         f.write_str(match self {
             Self::Name => "Name",
             Self::Description => "Description",
+            Self::Globs => "Patterns",
+            Self::Handler => "Default application",
         })
     }
 }
@@ -29,6 +34,8 @@ impl table::ItemCategory for MimeCategory {
         match self {
             Self::Name => iced::Length::Fixed(200.0),
             Self::Description => iced::Length::Fill,
+            Self::Globs => iced::Length::Fixed(140.0),
+            Self::Handler => iced::Length::Fixed(240.0),
         }
     }
 }
@@ -36,6 +43,10 @@ impl table::ItemCategory for MimeCategory {
 pub struct MimeItem {
     pub name: String,
     pub description: String,
+    // File name patterns from the mime database, e.g. "*.flac *.fla"
+    pub globs: String,
+    // Desktop file id currently opening this type, empty if none.
+    pub handler: String,
 }
 
 impl table::ItemInterface<MimeCategory> for MimeItem {
@@ -47,6 +58,8 @@ impl table::ItemInterface<MimeCategory> for MimeItem {
         match category {
             MimeCategory::Name => self.name.clone().into(),
             MimeCategory::Description => self.description.clone().into(),
+            MimeCategory::Globs => self.globs.clone().into(),
+            MimeCategory::Handler => self.handler.clone().into(),
         }
     }
 
@@ -57,18 +70,60 @@ impl table::ItemInterface<MimeCategory> for MimeItem {
                 .description
                 .to_lowercase()
                 .cmp(&other.description.to_lowercase()),
+            MimeCategory::Globs => self.globs.cmp(&other.globs),
+            MimeCategory::Handler => self.handler.cmp(&other.handler),
         }
     }
 }
 
+/// Whether `name` has the `type/subtype` form of a media type.
+pub fn is_valid_mimetype(name: &str) -> bool {
+    let valid_part = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+    };
+    name.split_once('/')
+        .is_some_and(|(kind, sub)| valid_part(kind) && valid_part(sub))
+}
+
+// Shell-style match of a mime glob like `*.tar.gz` or `[Mm]akefile` against a file name.
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| glob_matches(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && glob_matches(rest, &name[1..]),
+        Some(('[', rest)) if rest.contains(&']') => {
+            let end = rest.iter().position(|c| *c == ']').unwrap_or_default();
+            name.first().is_some_and(|c| {
+                rest[..end].contains(c) && glob_matches(&rest[end + 1..], &name[1..])
+            })
+        }
+        Some((c, rest)) => name.first() == Some(c) && glob_matches(rest, &name[1..]),
+    }
+}
+
+/// Split pasted text on `;`, `,` and whitespace, including newlines.
+pub fn split_mime_list(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ';' || c == ',' || c.is_whitespace())
+        .filter(|m| !m.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
 pub struct MimeCache {
     mime_descriptions: HashMap<String, String>,
+    mime_globs: HashMap<String, Vec<String>>,
+    mime_apps: MimeApps,
 }
 
 impl Default for MimeCache {
     fn default() -> Self {
         let mut cache = Self {
             mime_descriptions: HashMap::default(),
+            mime_globs: HashMap::default(),
+            mime_apps: MimeApps::load(),
         };
         cache.scan();
         cache
@@ -80,6 +135,87 @@ impl MimeCache {
         self.mime_descriptions.get(name)
     }
 
+    /// File name patterns like `*.flac` that map to `name`.
+    pub fn globs(&self, name: &str) -> &[String] {
+        self.mime_globs.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The mimetype of the file at `path` from its name, like the file manager
+    /// guesses it: the longest matching glob, case-sensitive ones first. Files
+    /// no glob matches are told apart by their content.
+    pub fn guess(&self, path: &Path) -> String {
+        if path.is_dir() {
+            return "inode/directory".to_string();
+        }
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let best = |fold: bool| {
+            let name: Vec<char> = if fold {
+                name.to_lowercase().chars().collect()
+            } else {
+                name.chars().collect()
+            };
+            self.mime_globs
+                .iter()
+                .flat_map(|(mime, globs)| globs.iter().map(move |g| (mime, g)))
+                .filter(|(_, glob)| {
+                    let glob: Vec<char> = if fold {
+                        glob.to_lowercase().chars().collect()
+                    } else {
+                        glob.chars().collect()
+                    };
+                    glob_matches(&glob, &name)
+                })
+                .max_by(|a, b| a.1.len().cmp(&b.1.len()).then_with(|| b.0.cmp(a.0)))
+                .map(|(mime, _)| mime.clone())
+        };
+        if let Some(mime) = best(false).or_else(|| best(true)) {
+            return mime;
+        }
+
+        // Like shared-mime-info, binary data is anything with a NUL byte early on
+        let mut head = Vec::new();
+        if let Ok(file) = fs::File::open(path) {
+            use std::io::Read;
+            let _ = file.take(512).read_to_end(&mut head);
+        }
+        if head.is_empty() {
+            "application/x-zerosize"
+        } else if head.contains(&0) {
+            "application/octet-stream"
+        } else {
+            "text/plain"
+        }
+        .to_string()
+    }
+
+    /// Desktop file id of the default application for `name`.
+    pub fn default_handler(&self, name: &str) -> Option<&str> {
+        self.mime_apps.default_for(name)
+    }
+
+    /// Re-read mimeapps.list after it changed.
+    pub fn reload_handlers(&mut self) {
+        self.mime_apps = MimeApps::load();
+    }
+
+    /// A table row for `name`, marking `own_id` when it's the default handler.
+    pub fn item(&self, name: &str, own_id: Option<&str>) -> MimeItem {
+        let handler = match self.default_handler(name) {
+            Some(id) if Some(id) == own_id => crate::fl!("mime-handler-this", id = id),
+            Some(id) => id.to_string(),
+            None => String::new(),
+        };
+        MimeItem {
+            name: name.to_owned(),
+            description: self.lookup(name).cloned().unwrap_or_default(),
+            globs: self.globs(name).join(" "),
+            handler,
+        }
+    }
+
     fn candidate_mime_dirs() -> Vec<PathBuf> {
         let in_flatpak = std::env::var_os("FLATPAK_ID").is_some();
 
@@ -133,6 +269,7 @@ impl MimeCache {
 
     pub fn scan(&mut self) {
         self.mime_descriptions.clear();
+        self.mime_globs.clear();
         let langs = freedesktop_desktop_entry::get_languages_from_env();
 
         let aliases = Self::get_mime_aliases();
@@ -193,6 +330,22 @@ impl MimeCache {
                                     }
                                 }
 
+                                let globs: Vec<String> = mime_node
+                                    .children()
+                                    .filter(|c| c.has_tag_name("glob"))
+                                    .filter_map(|c| c.attribute("pattern"))
+                                    .map(ToString::to_string)
+                                    .collect();
+                                if !globs.is_empty() {
+                                    let known =
+                                        self.mime_globs.entry(mime_type.clone()).or_default();
+                                    for glob in globs {
+                                        if !known.contains(&glob) {
+                                            known.push(glob);
+                                        }
+                                    }
+                                }
+
                                 let chosen = best_text.or(fallback_unlocalized);
 
                                 // So we insert the new mimetype/description but if there's an alias
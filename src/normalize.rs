@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Optional clean-ups applied when saving, to keep diffs of packaged launchers small.
+
+use crate::entrydiff::set_value;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Write the main group first and its keys in the order the spec lists them.
+pub const SORT_KEYS: u32 = 0b001;
+/// Remove keys whose value is empty.
+pub const DROP_EMPTY: u32 = 0b010;
+/// Write booleans as lowercase `true` and `false`.
+pub const BOOLEANS: u32 = 0b100;
+/// Every normalization, the default selection.
+pub const ALL: u32 = SORT_KEYS | DROP_EMPTY | BOOLEANS;
+
+const MAIN_GROUP: &str = "Desktop Entry";
+const ACTION_PREFIX: &str = "Desktop Action ";
+
+// Main group keys in the spec's order, the rest follow alphabetically.
+const KEY_ORDER: &[&str] = &[
+    "Type",
+    "Version",
+    "Name",
+    "GenericName",
+    "Comment",
+    "Icon",
+    "TryExec",
+    "Exec",
+    "Path",
+    "Terminal",
+    "Actions",
+    "MimeType",
+    "Categories",
+    "Implements",
+    "Keywords",
+    "OnlyShowIn",
+    "NotShowIn",
+    "StartupNotify",
+    "StartupWMClass",
+    "URL",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+    "DBusActivatable",
+    "NoDisplay",
+    "Hidden",
+];
+
+// Keys of type boolean, in any group.
+const BOOLEAN_KEYS: &[&str] = &[
+    "Terminal",
+    "StartupNotify",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+    "DBusActivatable",
+    "NoDisplay",
+    "Hidden",
+    "X-GNOME-Autostart-enabled",
+];
+
+// Keys holding ;-separated lists, in any group.
+const LIST_KEYS: &[&str] = &[
+    "Actions",
+    "Categories",
+    "Implements",
+    "Keywords",
+    "MimeType",
+    "NotShowIn",
+    "OnlyShowIn",
+];
+
+/// Trim the items of list keys, translations included, and drop empty items
+/// and duplicates compared case-insensitively. Done on every save, returns the
+/// duplicates dropped by key.
+pub fn tidy_lists(entry: &mut DesktopEntry) -> Vec<(String, Vec<String>)> {
+    let mut dropped = Vec::new();
+    for keys in entry.groups.0.values_mut() {
+        for (key, (value, locales)) in &mut keys.0 {
+            if !LIST_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            let values = std::iter::once((key.to_string(), value)).chain(
+                locales
+                    .iter_mut()
+                    .map(|(locale, value)| (format!("{key}[{locale}]"), value)),
+            );
+            for (name, value) in values {
+                let (tidy, duplicates) = tidy_list(value);
+                if let Some(tidy) = tidy {
+                    *value = tidy;
+                }
+                if !duplicates.is_empty() {
+                    dropped.push((name, duplicates));
+                }
+            }
+        }
+    }
+    dropped
+}
+
+// The list rewritten when trimming or dropping duplicates changes its items,
+// and the duplicates. Lists left alone keep their formatting.
+fn tidy_list(value: &str) -> (Option<String>, Vec<String>) {
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    let mut duplicates = Vec::new();
+    for item in value.split(';').map(str::trim).filter(|i| !i.is_empty()) {
+        if seen.insert(item.to_lowercase()) {
+            items.push(item);
+        } else {
+            duplicates.push(item.to_string());
+        }
+    }
+    let unchanged = value
+        .split(';')
+        .filter(|i| !i.is_empty())
+        .eq(items.iter().copied());
+    let tidy = (!unchanged).then(|| format!("{};", items.join(";")));
+    (tidy, duplicates)
+}
+
+/// Apply the normalizations in `steps` to `entry` and return the text to write.
+/// Without SORT_KEYS the text is the entry's usual serialization.
+pub fn apply(entry: &mut DesktopEntry, steps: u32) -> String {
+    if steps & DROP_EMPTY != 0 {
+        drop_empty(entry);
+    }
+    if steps & BOOLEANS != 0 {
+        fix_booleans(entry);
+    }
+    if steps & SORT_KEYS != 0 {
+        sorted_text(entry)
+    } else {
+        entry.to_string()
+    }
+}
+
+/// `text` with LF line endings and exactly one trailing newline, so the same
+/// entry always serializes to the same bytes.
+pub fn stable(text: &str) -> String {
+    let mut text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let end = text.trim_end_matches('\n').len();
+    text.truncate(end);
+    text.push('\n');
+    text
+}
+
+fn drop_empty(entry: &mut DesktopEntry) {
+    let mut empty = Vec::new();
+    for (group, keys) in &entry.groups.0 {
+        for (key, (value, locales)) in &keys.0 {
+            // A key holding translations stays, its translations need it
+            if value.trim().is_empty() && locales.is_empty() {
+                empty.push((group.to_string(), key.to_string()));
+            }
+            for (locale, value) in locales {
+                if value.trim().is_empty() {
+                    empty.push((group.to_string(), format!("{key}[{locale}]")));
+                }
+            }
+        }
+    }
+    for (group, key) in empty {
+        set_value(entry, &group, &key, None);
+    }
+}
+
+fn fix_booleans(entry: &mut DesktopEntry) {
+    for keys in entry.groups.0.values_mut() {
+        for (key, (value, _)) in &mut keys.0 {
+            if !BOOLEAN_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            // 0 and 1 are from before the spec settled on true and false
+            match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => *value = "true".to_string(),
+                "false" | "0" => *value = "false".to_string(),
+                _ => {}
+            }
+        }
+    }
+}
+
+// The main group first, then actions in the order Actions lists them, then the
+// remaining groups alphabetically.
+fn sorted_text(entry: &DesktopEntry) -> String {
+    let groups = &entry.groups.0;
+    let mut names: Vec<&str> = Vec::new();
+    if groups.contains_key(MAIN_GROUP) {
+        names.push(MAIN_GROUP);
+    }
+    let listed: Vec<String> = entry
+        .desktop_entry("Actions")
+        .unwrap_or_default()
+        .split(';')
+        .filter(|a| !a.is_empty())
+        .map(|a| format!("{ACTION_PREFIX}{a}"))
+        .collect();
+    for action in &listed {
+        if let Some((name, _)) = groups.get_key_value(action.as_str()) {
+            names.push(name.as_str());
+        }
+    }
+    let mut rest: Vec<&str> = groups
+        .keys()
+        .map(String::as_str)
+        .filter(|n| !names.contains(n))
+        .collect();
+    rest.sort_unstable();
+    names.extend(rest);
+
+    let mut text = String::new();
+    for (i, name) in names.into_iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let _ = writeln!(text, "[{name}]");
+
+        let keys = &groups[name].0;
+        let mut ordered: Vec<&str> = keys.keys().map(String::as_str).collect();
+        ordered.sort_by_key(|k| {
+            (
+                KEY_ORDER.iter().position(|o| o == k).unwrap_or(usize::MAX),
+                *k,
+            )
+        });
+        for key in ordered {
+            let (value, locales) = &keys[key];
+            let _ = writeln!(text, "{key}={value}");
+            for (locale, value) in locales {
+                let _ = writeln!(text, "{key}[{locale}]={value}");
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(text: &str) -> DesktopEntry {
+        DesktopEntry::from_str(PathBuf::new(), text, None::<&[&str]>).unwrap()
+    }
+
+    #[test]
+    fn tidy_list_keeps_tidy_lists() {
+        assert_eq!(tidy_list(""), (None, Vec::new()));
+        assert_eq!(tidy_list("Utility;Development;"), (None, Vec::new()));
+        // Neither a missing trailing ; nor an empty item changes the items
+        assert_eq!(tidy_list("Utility;Development"), (None, Vec::new()));
+        assert_eq!(tidy_list("Utility;;Development;"), (None, Vec::new()));
+        assert_eq!(tidy_list(";;"), (None, Vec::new()));
+    }
+
+    #[test]
+    fn tidy_list_trims_items() {
+        assert_eq!(
+            tidy_list(" Utility ; Development;"),
+            (Some("Utility;Development;".to_string()), Vec::new())
+        );
+        assert_eq!(
+            tidy_list("Utility; ;Development"),
+            (Some("Utility;Development;".to_string()), Vec::new())
+        );
+    }
+
+    #[test]
+    fn tidy_list_drops_duplicates_ignoring_case() {
+        // The first spelling stays
+        assert_eq!(
+            tidy_list("Utility;Development;utility;;UTILITY;"),
+            (
+                Some("Utility;Development;".to_string()),
+                vec!["utility".to_string(), "UTILITY".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn tidy_lists_covers_translations() {
+        let mut e = entry(
+            "[Desktop Entry]\nType=Application\nName=App;App;\nKeywords=a;A;\nKeywords[de]=b; b;\n",
+        );
+        let dropped = tidy_lists(&mut e);
+        assert_eq!(
+            dropped,
+            [
+                ("Keywords".to_string(), vec!["A".to_string()]),
+                ("Keywords[de]".to_string(), vec!["b".to_string()]),
+            ]
+        );
+        let keys = &e.groups.0[MAIN_GROUP].0;
+        assert_eq!(keys["Keywords"].0, "a;");
+        assert_eq!(keys["Keywords"].1["de"], "b;");
+        // Name isn't a list
+        assert_eq!(keys["Name"].0, "App;App;");
+    }
+
+    #[test]
+    fn stable_line_endings() {
+        assert_eq!(stable("a=1\r\nb=2\r\n"), "a=1\nb=2\n");
+        assert_eq!(stable("a=1\rb=2"), "a=1\nb=2\n");
+        assert_eq!(stable("a=1\n\n\n"), "a=1\n");
+        assert_eq!(stable("a=1\r\n\r\n"), "a=1\n");
+        assert_eq!(stable("a=1"), "a=1\n");
+    }
+
+    #[test]
+    fn fix_booleans_in_every_group() {
+        let mut e = entry(
+            "[Desktop Entry]\nType=Application\nName=App\nActions=new;\nTerminal=1\n\
+             NoDisplay=FALSE\nStartupNotify=yes\nX-Flag=0\n\n\
+             [Desktop Action new]\nName=New\nHidden=0\n",
+        );
+        fix_booleans(&mut e);
+        let main = &e.groups.0[MAIN_GROUP].0;
+        assert_eq!(main["Terminal"].0, "true");
+        assert_eq!(main["NoDisplay"].0, "false");
+        // Not a boolean, or not a key known to be one
+        assert_eq!(main["StartupNotify"].0, "yes");
+        assert_eq!(main["X-Flag"].0, "0");
+        assert_eq!(e.groups.0["Desktop Action new"].0["Hidden"].0, "false");
+    }
+
+    #[test]
+    fn sorted_text_orders_groups_and_keys() {
+        let e = entry(
+            "[X-Extra]\nFoo=1\n\n\
+             [Desktop Action b]\nName=B\n\n\
+             [Desktop Entry]\nX-Custom=1\nExec=app\nName=App\nActions=b;a;\nType=Application\n\n\
+             [Desktop Action a]\nName=A\n",
+        );
+        assert_eq!(
+            sorted_text(&e),
+            "[Desktop Entry]\nType=Application\nName=App\nExec=app\nActions=b;a;\nX-Custom=1\n\n\
+             [Desktop Action b]\nName=B\n\n\
+             [Desktop Action a]\nName=A\n\n\
+             [X-Extra]\nFoo=1\n"
+        );
+    }
+}
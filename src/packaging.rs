@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Launchers generated by a package format, which rewrites them on every update.
+
+use crate::xdghelp::user_override_path;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An application installed from Flatpak, as far as its exported launcher tells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatpakApp {
+    pub id: String,
+    pub arch: Option<String>,
+    pub branch: Option<String>,
+    // Remote the app was installed from, e.g. flathub
+    pub origin: Option<String>,
+}
+
+/// A snap, whose launchers snapd regenerates on every refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snap {
+    pub name: String,
+    pub revision: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packaged {
+    Flatpak(FlatpakApp),
+    Snap(Snap),
+}
+
+/// The package that generated the launcher at `path`, if any. Blocking I/O.
+pub fn detect(entry: &DesktopEntry, path: &Path) -> Option<Packaged> {
+    // An override is the user's own copy, even though it carries the package's keys
+    if user_override_path(path).as_deref() == Some(path) {
+        return None;
+    }
+    if let Some(name) = snap_name(entry, path) {
+        let revision = fs::read_link(Path::new("/snap").join(&name).join("current"))
+            .ok()
+            .map(|r| r.to_string_lossy().into_owned());
+        return Some(Packaged::Snap(Snap { name, revision }));
+    }
+    flatpak_id(entry, path).map(|id| Packaged::Flatpak(flatpak_app(id)))
+}
+
+// snapd writes launchers as /var/lib/snapd/desktop/applications/<snap>_<app>.desktop
+// and names the snap in X-SnapInstanceName.
+fn snap_name(entry: &DesktopEntry, path: &Path) -> Option<String> {
+    if !path.starts_with("/var/lib/snapd/desktop") {
+        return None;
+    }
+    if let Some(name) = entry.desktop_entry("X-SnapInstanceName") {
+        return Some(name.to_string());
+    }
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    let snap = stem.split_once('_').map_or(stem.as_str(), |(snap, _)| snap);
+    Some(snap.to_string())
+}
+
+// Flatpak adds X-Flatpak=<app id> to the launchers it exports.
+fn flatpak_id(entry: &DesktopEntry, path: &Path) -> Option<String> {
+    if let Some(id) = entry.desktop_entry("X-Flatpak") {
+        return Some(id.to_string());
+    }
+    let exported = path
+        .to_string_lossy()
+        .contains("/flatpak/exports/share/applications/");
+    exported
+        .then(|| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .flatten()
+}
+
+// The user installation first, it's the one used when the app is in both.
+fn installations() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs::data_dir()
+        .map(|d| d.join("flatpak"))
+        .into_iter()
+        .collect();
+    dirs.push(PathBuf::from("/var/lib/flatpak"));
+    dirs
+}
+
+// Arch and branch from the app's `current` link, "x86_64/stable", and the
+// origin from the remote ref that was deployed.
+fn flatpak_app(id: String) -> FlatpakApp {
+    let mut app = FlatpakApp {
+        id,
+        arch: None,
+        branch: None,
+        origin: None,
+    };
+
+    for installation in installations() {
+        let Ok(current) = fs::read_link(installation.join("app").join(&app.id).join("current"))
+        else {
+            continue;
+        };
+        let current = current.to_string_lossy().into_owned();
+        let Some((arch, branch)) = current.split_once('/') else {
+            continue;
+        };
+
+        let remotes = installation.join("repo").join("refs").join("remotes");
+        app.origin = fs::read_dir(&remotes).ok().and_then(|entries| {
+            entries.flatten().find_map(|remote| {
+                let deployed = remote
+                    .path()
+                    .join("app")
+                    .join(&app.id)
+                    .join(arch)
+                    .join(branch);
+                deployed
+                    .exists()
+                    .then(|| remote.file_name().to_string_lossy().into_owned())
+            })
+        });
+        app.arch = Some(arch.to_string());
+        app.branch = Some(branch.to_string());
+        break;
+    }
+    app
+}
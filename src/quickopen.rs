@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Fuzzy search over installed launchers for the quick-open dialog.
+
+use crate::mimeapps::installed_desktop_files;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::path::PathBuf;
+
+/// Results shown at once, the list is for picking, not browsing.
+pub const MAX_RESULTS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Launcher {
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub path: PathBuf,
+}
+
+/// Every installed launcher, named in the user's language. Blocking I/O.
+pub fn scan(locales: &[String]) -> Vec<Launcher> {
+    let mut launchers: Vec<Launcher> = installed_desktop_files()
+        .into_iter()
+        .filter_map(|(id, path)| {
+            let entry = DesktopEntry::from_path(&path, Some(locales)).ok()?;
+            Some(Launcher {
+                name: entry
+                    .name(locales)
+                    .map(|n| n.into_owned())
+                    .unwrap_or_default(),
+                exec: entry.exec().unwrap_or_default().to_string(),
+                id,
+                path,
+            })
+        })
+        .collect();
+    launchers.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    launchers
+}
+
+/// Launchers matching `query` by name, Exec or id, best first.
+pub fn search<'a>(launchers: &'a [Launcher], query: &str) -> Vec<&'a Launcher> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return launchers.iter().take(MAX_RESULTS).collect();
+    }
+
+    let mut scored: Vec<(i32, &Launcher)> = launchers
+        .iter()
+        .filter_map(|l| {
+            // A name match counts for more than one in the command or id
+            let score = [
+                fuzzy_score(&query, &l.name).map(|s| s + 20),
+                fuzzy_score(&query, &l.exec),
+                fuzzy_score(&query, &l.id),
+            ]
+            .into_iter()
+            .flatten()
+            .max()?;
+            Some((score, l))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, l)| l)
+        .collect()
+}
+
+// Score `text` if every character of the lowercase `query` appears in it in order.
+// Runs of consecutive characters and matches at word starts score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars() {
+        let found = pos + text[pos..].iter().position(|c| *c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    // Prefer shorter texts when the match is otherwise equal
+    Some(score * 4 - i32::try_from(text.len()).unwrap_or(i32::MAX) / 8)
+}
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Autostart entries as systemd user units and back. A unit started with the
+//! graphical session replaces the entry, which stays behind hidden.
+
+use crate::exec::{self, ExecError, LaunchContext};
+use freedesktop_desktop_entry::DesktopEntry;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The target units started with the desktop session are wanted by
+const TARGET: &str = "graphical-session.target";
+
+/// What an autostart entry keeps of a unit when converted back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Unit {
+    pub description: Option<String>,
+    pub args: Vec<String>,
+    pub working_directory: Option<String>,
+    // Seconds slept in ExecStartPre, the entry's X-GNOME-Autostart-Delay
+    pub delay: u32,
+    // Linked into the target's .wants directory
+    pub enabled: bool,
+}
+
+impl Unit {
+    /// The arguments as an Exec value, with `%` escaped for launchers.
+    pub fn exec(&self) -> String {
+        let args: Vec<String> = self.args.iter().map(|a| exec::quote_literal(a)).collect();
+        args.join(" ")
+    }
+}
+
+/// `~/.config/systemd/user/<name>.service` for the autostart file at `path`.
+pub fn unit_path(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    dirs::config_dir().map(|d| {
+        d.join("systemd")
+            .join("user")
+            .join(format!("{stem}.service"))
+    })
+}
+
+// The symlink enabling `unit`.
+fn wants_link(unit: &Path) -> Option<PathBuf> {
+    let name = unit.file_name()?;
+    Some(unit.parent()?.join(format!("{TARGET}.wants")).join(name))
+}
+
+/// Whether session managers start the entry: not Hidden and not turned off.
+pub fn autostart_enabled(entry: &DesktopEntry) -> bool {
+    !entry.hidden() && entry.desktop_entry("X-GNOME-Autostart-enabled") != Some("false")
+}
+
+// One ExecStart argument, quoted when needed, with systemd's specifiers and
+// variables escaped so it's taken literally.
+fn unit_arg(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if !escaped.is_empty() && !escaped.contains([' ', '\t', '"', '\'', '\\', ';']) {
+        return escaped;
+    }
+    let mut out = String::from("\"");
+    for c in escaped.chars() {
+        if matches!(c, '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// The unit file starting the autostart entry at `path` with the session.
+pub fn unit_text(
+    entry: &DesktopEntry,
+    path: &Path,
+    locales: &[String],
+) -> Result<String, ExecError> {
+    let ctx = LaunchContext {
+        icon: entry.icon().map(ToString::to_string),
+        name: entry.name(locales).map(|n| n.into_owned()),
+        desktop_file: Some(path.to_string_lossy().into_owned()),
+    };
+    let args = exec::expand_field_codes(entry.exec().unwrap_or_default(), &ctx)?;
+    let exec_start: Vec<String> = args.iter().map(|a| unit_arg(a)).collect();
+
+    let mut text = String::from("[Unit]\n");
+    if let Some(name) = &ctx.name {
+        text.push_str(&format!("Description={name}\n"));
+    }
+    text.push_str(&format!(
+        "PartOf={TARGET}\nAfter={TARGET}\n\n[Service]\nType=exec\n"
+    ));
+    let delay: u32 = entry
+        .desktop_entry("X-GNOME-Autostart-Delay")
+        .and_then(|d| d.trim().parse().ok())
+        .unwrap_or(0);
+    if delay > 0 {
+        text.push_str(&format!("ExecStartPre=sleep {delay}\n"));
+    }
+    text.push_str(&format!("ExecStart={}\n", exec_start.join(" ")));
+    if let Some(dir) = entry.desktop_entry("Path").filter(|d| !d.is_empty()) {
+        text.push_str(&format!("WorkingDirectory={}\n", exec::unescape_value(dir)));
+    }
+    text.push_str(&format!("Restart=no\n\n[Install]\nWantedBy={TARGET}\n"));
+    Ok(text)
+}
+
+/// Write the unit and enable it or not, like `systemctl --user enable` would.
+pub fn write_unit(unit: &Path, text: &str, enabled: bool) -> std::io::Result<()> {
+    if let Some(parent) = unit.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(unit, text)?;
+    set_enabled(unit, enabled)
+}
+
+fn set_enabled(unit: &Path, enabled: bool) -> std::io::Result<()> {
+    let Some(link) = wants_link(unit) else {
+        return Ok(());
+    };
+    let _ = fs::remove_file(&link);
+    if enabled {
+        if let Some(parent) = link.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::os::unix::fs::symlink(unit, &link)?;
+    }
+    Ok(())
+}
+
+/// Delete the unit and the link enabling it.
+pub fn remove_unit(unit: &Path) -> std::io::Result<()> {
+    set_enabled(unit, false)?;
+    fs::remove_file(unit)
+}
+
+// ExecStart's arguments: quotes and escapes removed, %% and $$ unescaped, and
+// the prefixes changing how systemd runs the command dropped.
+fn split_unit_command(line: &str) -> Vec<String> {
+    let line = line.trim_start_matches(['-', '@', '+', '!', ':']);
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' if quote.is_none() => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            c if Some(c) == quote => quote = None,
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            '%' | '$' if chars.peek() == Some(&c) => {
+                chars.next();
+                current.get_or_insert_with(String::new).push(c);
+            }
+            c if c.is_whitespace() && quote.is_none() => args.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+/// The parts of the unit at `unit` an autostart entry has keys for.
+pub fn read_unit(unit: &Path) -> Option<Unit> {
+    let text = fs::read_to_string(unit).ok()?;
+    let mut read = Unit {
+        enabled: wants_link(unit).is_some_and(|l| l.exists()),
+        ..Unit::default()
+    };
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Description" => read.description = Some(value.to_string()),
+            "ExecStart" => read.args = split_unit_command(value),
+            "WorkingDirectory" => read.working_directory = Some(value.to_string()),
+            "ExecStartPre" => {
+                if let ["sleep", seconds] = split_unit_command(value)
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .as_slice()
+                {
+                    read.delay = seconds.parse().unwrap_or(0);
+                }
+            }
+            _ => {}
+        }
+    }
+    (!read.args.is_empty()).then_some(read)
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! User templates, entries saved under a name to start new entries from.
+
+use freedesktop_desktop_entry::DesktopEntry;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A saved template, listed under its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// `$XDG_DATA_HOME/launchedit/templates`.
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("launchedit").join("templates"))
+}
+
+/// Every saved template, by name.
+pub fn list() -> Vec<Template> {
+    let Some(entries) = templates_dir().and_then(|d| fs::read_dir(d).ok()) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<Template> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "desktop"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some(Template { name, path })
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    templates
+}
+
+/// Save `entry` as the template `name`, replacing one of the same name.
+pub fn save(name: &str, entry: &DesktopEntry) -> io::Result<PathBuf> {
+    let dir = templates_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?;
+    fs::create_dir_all(&dir)?;
+    // The name is the file name, it can't name another directory
+    let file = format!("{}.desktop", name.trim().replace('/', "-"));
+    let path = dir.join(file);
+    fs::write(&path, entry.to_string())?;
+    Ok(path)
+}
+
+/// The template's entry, detached from the template file so it saves as a new one.
+pub fn instantiate(path: &Path) -> Result<DesktopEntry, String> {
+    let mut entry = DesktopEntry::from_path::<&str>(path, None).map_err(|e| e.to_string())?;
+    entry.path = PathBuf::new();
+    Ok(entry)
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! `.thumbnailer` files, which describe a program making thumbnails for the file
+//! manager. They are desktop entries with a `[Thumbnailer Entry]` group, edited
+//! as if it were the main group and written back under its own name.
+
+use crate::fl;
+use freedesktop_desktop_entry::DesktopEntry;
+use std::path::Path;
+
+const GROUP: &str = "Thumbnailer Entry";
+const MAIN_GROUP: &str = "Desktop Entry";
+
+// Field codes a thumbnailer's Exec can use
+const CODES: [char; 5] = ['s', 'u', 'i', 'o', '%'];
+
+pub fn is_thumbnailer(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "thumbnailer")
+}
+
+/// Move the thumbnailer group to where the form edits keys.
+pub fn into_editable(entry: &mut DesktopEntry) {
+    if let Some(group) = entry.groups.0.remove(GROUP) {
+        entry.groups.0.insert(MAIN_GROUP.to_string(), group);
+    }
+}
+
+/// The saved text of an entry edited as a thumbnailer, with its group renamed back.
+pub fn file_text(text: &str) -> String {
+    text.replacen(&format!("[{MAIN_GROUP}]"), &format!("[{GROUP}]"), 1)
+}
+
+/// Problems with a thumbnailer's Exec: it must write to %o and read %i or %u.
+pub fn exec_warnings(exec: &str) -> Vec<String> {
+    let mut codes = Vec::new();
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c == '%'
+            && let Some(code) = chars.next()
+        {
+            codes.push(code);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if !codes.contains(&'o') {
+        warnings.push(fl!("thumbnailer-nooutput"));
+    }
+    if !codes.contains(&'i') && !codes.contains(&'u') {
+        warnings.push(fl!("thumbnailer-noinput"));
+    }
+    for code in codes.iter().filter(|c| !CODES.contains(c)) {
+        warnings.push(fl!("thumbnailer-badcode", code = format!("%{code}")));
+    }
+    warnings
+}
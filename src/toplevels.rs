@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The app ids of open windows, read through the foreign toplevel protocol.
+
+use cosmic::cctk::{
+    self,
+    sctk::{
+        self,
+        registry::{ProvidesRegistryState, RegistryState},
+        registry_handlers,
+    },
+    toplevel_info::{ToplevelInfoHandler, ToplevelInfoState},
+    wayland_client::{Connection, QueueHandle, globals::registry_queue_init},
+    wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1,
+};
+
+/// An open window. For XWayland windows the app id is the WM_CLASS class.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RunningApp {
+    pub app_id: String,
+    pub title: String,
+}
+
+struct State {
+    registry_state: RegistryState,
+    toplevel_info_state: ToplevelInfoState,
+}
+
+impl ProvidesRegistryState for State {
+    fn registry(&mut self) -> &mut RegistryState {
+        &mut self.registry_state
+    }
+
+    registry_handlers!();
+}
+
+// Only the final list is of interest, not the individual events
+impl ToplevelInfoHandler for State {
+    fn toplevel_info_state(&mut self) -> &mut ToplevelInfoState {
+        &mut self.toplevel_info_state
+    }
+
+    fn new_toplevel(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _toplevel: &ExtForeignToplevelHandleV1,
+    ) {
+    }
+
+    fn update_toplevel(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _toplevel: &ExtForeignToplevelHandleV1,
+    ) {
+    }
+
+    fn toplevel_closed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _toplevel: &ExtForeignToplevelHandleV1,
+    ) {
+    }
+}
+
+cctk::delegate_toplevel_info!(State);
+sctk::delegate_registry!(State);
+
+/// Open windows with distinct app ids, sorted. Blocks for a few compositor roundtrips.
+pub fn running_apps() -> Result<Vec<RunningApp>, String> {
+    let conn = Connection::connect_to_env().map_err(|e| e.to_string())?;
+    let (globals, mut queue) = registry_queue_init::<State>(&conn).map_err(|e| e.to_string())?;
+    let qh = queue.handle();
+
+    let registry_state = RegistryState::new(&globals);
+    let toplevel_info_state = ToplevelInfoState::try_new(&registry_state, &qh)
+        .ok_or_else(|| "The compositor does not share its window list".to_string())?;
+    let mut state = State {
+        registry_state,
+        toplevel_info_state,
+    };
+
+    // Toplevels arrive after binding, their app ids and titles one roundtrip later
+    for _ in 0..3 {
+        queue.roundtrip(&mut state).map_err(|e| e.to_string())?;
+    }
+
+    let mut apps: Vec<RunningApp> = state
+        .toplevel_info_state
+        .toplevels()
+        .filter(|info| !info.app_id.is_empty())
+        .map(|info| RunningApp {
+            app_id: info.app_id.clone(),
+            title: info.title.clone(),
+        })
+        .collect();
+    apps.sort();
+    apps.dedup_by(|a, b| a.app_id == b.app_id);
+    Ok(apps)
+}
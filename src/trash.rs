@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Moving launchers to the trash and back, following the FreeDesktop Trash spec.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Move `path` to the trash through the Trash portal.
+pub async fn trash(path: PathBuf) -> Result<(), String> {
+    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+    ashpd::desktop::trash::trash_file(&file)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Put the most recently trashed copy of `path` back where it was.
+pub fn restore(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    let trash_dir = dirs::data_dir()
+        .map(|d| d.join("Trash"))
+        .ok_or_else(|| "No data directory".to_string())?;
+    let info = find_info(&trash_dir, path)
+        .ok_or_else(|| format!("{} is not in the trash", path.display()))?;
+
+    // info/NAME.trashinfo describes files/NAME
+    let name = info
+        .file_stem()
+        .ok_or_else(|| "Malformed trash entry".to_string())?;
+    fs::rename(trash_dir.join("files").join(name), path).map_err(|e| e.to_string())?;
+    // The file is back, a stale info file only confuses file managers
+    let _ = fs::remove_file(&info);
+    Ok(())
+}
+
+// The .trashinfo for the newest trashed copy of `original`.
+fn find_info(trash_dir: &Path, original: &Path) -> Option<PathBuf> {
+    let mut newest: Option<(String, PathBuf)> = None;
+    for entry in fs::read_dir(trash_dir.join("info")).ok()?.flatten() {
+        let info = entry.path();
+        if info.extension().is_none_or(|e| e != "trashinfo") {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&info) else {
+            continue;
+        };
+
+        let mut trashed_from = None;
+        let mut date = String::new();
+        for line in text.lines() {
+            if let Some(value) = line.strip_prefix("Path=") {
+                // Percent encoded like a file URI path
+                trashed_from = url::Url::parse(&format!("file://{value}"))
+                    .ok()
+                    .and_then(|u| u.to_file_path().ok());
+            } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                date = value.to_string();
+            }
+        }
+
+        // ISO 8601 dates sort as strings
+        if trashed_from.as_deref() == Some(original)
+            && newest.as_ref().is_none_or(|(d, _)| date > *d)
+        {
+            newest = Some((date, info));
+        }
+    }
+    newest.map(|(_, info)| info)
+}
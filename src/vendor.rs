@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Vendor extension keys common enough to get their own widgets instead of a
+//! row in the custom keys table.
+
+use crate::fl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vendor {
+    Cosmic,
+    Gnome,
+    Kde,
+    Purism,
+}
+
+impl Vendor {
+    pub const ALL: [Vendor; 4] = [Vendor::Cosmic, Vendor::Gnome, Vendor::Kde, Vendor::Purism];
+
+    pub fn title(self) -> String {
+        match self {
+            Vendor::Cosmic => fl!("vendor-cosmic"),
+            Vendor::Gnome => fl!("vendor-gnome"),
+            Vendor::Kde => fl!("vendor-kde"),
+            Vendor::Purism => fl!("vendor-purism"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Widget {
+    // true or false, a missing key is false
+    Toggle,
+    // One of the values
+    Choice(&'static [&'static str]),
+    // Any of the values, as a ;-separated list
+    Flags(&'static [&'static str]),
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VendorKey {
+    pub key: &'static str,
+    pub vendor: Vendor,
+    pub widget: Widget,
+}
+
+impl VendorKey {
+    pub fn label(&self) -> String {
+        match self.key {
+            "X-CosmicApplet" => fl!("vendor-cosmicapplet"),
+            "X-CosmicHoverPopup" => fl!("vendor-cosmichoverpopup"),
+            "X-OverflowPriority" => fl!("vendor-overflowpriority"),
+            "X-HostWaylandDisplay" => fl!("vendor-hostwaylanddisplay"),
+            "X-GNOME-UsesNotifications" => fl!("vendor-usesnotifications"),
+            "X-GNOME-SingleWindow" => fl!("vendor-singlewindow"),
+            "X-GNOME-FullName" => fl!("vendor-fullname"),
+            "X-KDE-RunOnDiscreteGpu" => fl!("vendor-discretegpu"),
+            "X-KDE-Protocols" => fl!("vendor-protocols"),
+            "X-Purism-FormFactor" => fl!("vendor-formfactor"),
+            key => key.to_string(),
+        }
+    }
+}
+
+/// What a vendor key does, for the help popover.
+pub fn explain(key: &str) -> Option<String> {
+    let text = match key {
+        "X-CosmicApplet" => fl!("help-cosmicapplet"),
+        "X-CosmicHoverPopup" => fl!("help-cosmichoverpopup"),
+        "X-OverflowPriority" => fl!("help-overflowpriority"),
+        "X-HostWaylandDisplay" => fl!("help-hostwaylanddisplay"),
+        "X-GNOME-UsesNotifications" => fl!("help-usesnotifications"),
+        "X-GNOME-SingleWindow" => fl!("help-singlewindow"),
+        "X-GNOME-FullName" => fl!("help-fullname"),
+        "X-KDE-RunOnDiscreteGpu" => fl!("help-discretegpu"),
+        "X-KDE-Protocols" => fl!("help-protocols"),
+        "X-Purism-FormFactor" => fl!("help-formfactor"),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Keys with their own widgets, grouped by vendor.
+pub const KEYS: &[VendorKey] = &[
+    VendorKey {
+        key: "X-CosmicApplet",
+        vendor: Vendor::Cosmic,
+        widget: Widget::Toggle,
+    },
+    VendorKey {
+        key: "X-CosmicHoverPopup",
+        vendor: Vendor::Cosmic,
+        widget: Widget::Choice(&["Auto", "End", "Center"]),
+    },
+    VendorKey {
+        key: "X-OverflowPriority",
+        vendor: Vendor::Cosmic,
+        widget: Widget::Text,
+    },
+    VendorKey {
+        key: "X-HostWaylandDisplay",
+        vendor: Vendor::Cosmic,
+        widget: Widget::Toggle,
+    },
+    VendorKey {
+        key: "X-GNOME-UsesNotifications",
+        vendor: Vendor::Gnome,
+        widget: Widget::Toggle,
+    },
+    VendorKey {
+        key: "X-GNOME-SingleWindow",
+        vendor: Vendor::Gnome,
+        widget: Widget::Toggle,
+    },
+    VendorKey {
+        key: "X-GNOME-FullName",
+        vendor: Vendor::Gnome,
+        widget: Widget::Text,
+    },
+    VendorKey {
+        key: "X-KDE-RunOnDiscreteGpu",
+        vendor: Vendor::Kde,
+        widget: Widget::Toggle,
+    },
+    VendorKey {
+        key: "X-KDE-Protocols",
+        vendor: Vendor::Kde,
+        widget: Widget::Text,
+    },
+    VendorKey {
+        key: "X-Purism-FormFactor",
+        vendor: Vendor::Purism,
+        widget: Widget::Flags(&["Workstation", "Mobile"]),
+    },
+];
+
+/// Whether `key` has its own widget, and so no row in the custom keys table.
+pub fn is_vendor_key(key: &str) -> bool {
+    KEYS.iter().any(|k| k.key == key)
+}
+
+/// The items of a ;-separated list with `flag` added or removed, None once empty.
+pub fn toggle_flag(list: Option<&str>, flag: &str, on: bool) -> Option<String> {
+    let mut items: Vec<&str> = list
+        .unwrap_or_default()
+        .split(';')
+        .filter(|i| !i.is_empty() && *i != flag)
+        .collect();
+    if on {
+        items.push(flag);
+    }
+    (!items.is_empty()).then(|| format!("{};", items.join(";")))
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Launchers that open a website in its own browser window.
+
+use crate::exec::{self, FIELD_CODES};
+use crate::mimeapps::{MimeApps, installed_desktop_files};
+use freedesktop_desktop_entry::DesktopEntry;
+use std::time::Duration;
+
+// Longest a site may take to answer before the entry keeps its placeholder name.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a browser opens a page as an app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppMode {
+    // Chromium and derivatives: a window without browser UI
+    Chromium,
+    // Firefox has no app window, a new window is the closest
+    Firefox,
+    // Anything else is just given the URL
+    Plain,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Browser {
+    pub name: String,
+    // The browser's own Exec, without field codes
+    pub command: String,
+    pub mode: AppMode,
+}
+
+impl Browser {
+    /// Exec for a launcher opening `url` in this browser.
+    pub fn exec_for(&self, url: &str) -> String {
+        match self.mode {
+            AppMode::Chromium => {
+                let app = format!("--app={url}");
+                format!("{} {}", self.command, exec::quote_literal(&app))
+            }
+            AppMode::Firefox => {
+                format!("{} --new-window {}", self.command, exec::quote_literal(url))
+            }
+            AppMode::Plain => format!("{} {}", self.command, exec::quote_literal(url)),
+        }
+    }
+}
+
+/// Installed web browsers, the default https handler first. Blocking I/O.
+pub fn installed_browsers(locales: &[String]) -> Vec<Browser> {
+    let default = MimeApps::load()
+        .default_for("x-scheme-handler/https")
+        .map(str::to_string);
+
+    let mut browsers: Vec<(bool, Browser)> = installed_desktop_files()
+        .into_iter()
+        .filter_map(|(id, path)| {
+            let entry = DesktopEntry::from_path(&path, Some(locales)).ok()?;
+            let categories = entry.categories().unwrap_or_default();
+            if !categories.contains(&"WebBrowser") || entry.no_display() {
+                return None;
+            }
+            let command = strip_field_codes(entry.exec()?)?;
+            let browser = Browser {
+                name: entry.name(locales)?.into_owned(),
+                mode: app_mode(&command),
+                command,
+            };
+            Some((default.as_deref() == Some(id.as_str()), browser))
+        })
+        .collect();
+
+    browsers.sort_by(|(a_default, a), (b_default, b)| {
+        b_default.cmp(a_default).then_with(|| a.name.cmp(&b.name))
+    });
+    browsers.into_iter().map(|(_, b)| b).collect()
+}
+
+// The browser is recognized by its command, which also covers Flatpak and Snap wrappers.
+fn app_mode(command: &str) -> AppMode {
+    let command = command.to_lowercase();
+    const CHROMIUM: &[&str] = &["chrom", "brave", "edge", "vivaldi", "opera", "thorium"];
+    if CHROMIUM.iter().any(|b| command.contains(b)) {
+        AppMode::Chromium
+    } else if command.contains("firefox") || command.contains("librewolf") {
+        AppMode::Firefox
+    } else {
+        AppMode::Plain
+    }
+}
+
+fn strip_field_codes(exec: &str) -> Option<String> {
+    let args: Vec<String> = exec::split(exec)
+        .ok()?
+        .into_iter()
+        .filter(|a| !FIELD_CODES.contains(&a.as_str()))
+        .collect();
+    (!args.is_empty()).then(|| exec::join(&args))
+}
+
+/// A name for the launcher until the site's own title is known, the host name.
+pub fn placeholder_name(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.host_str()
+                .map(|h| h.trim_start_matches("www.").to_string())
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Add https:// to a bare host name.
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{url}")
+    }
+}
+
+/// The site's name, from its og:site_name or otherwise its <title>.
+pub async fn fetch_site_name(url: String) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .ok()?;
+    let html = client.get(&url).send().await.ok()?.text().await.ok()?;
+    site_name(&html)
+}
+
+fn site_name(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+
+    // <meta property="og:site_name" content="…">
+    if let Some(at) = lower.find("og:site_name") {
+        let tag_end = lower[at..].find('>').map_or(lower.len(), |e| at + e);
+        let tag = &html[at..tag_end];
+        if let Some(c) = tag.to_ascii_lowercase().find("content=") {
+            let value = &tag[c + "content=".len()..];
+            let quote = value.chars().next().filter(|q| *q == '"' || *q == '\'');
+            if let Some(quote) = quote
+                && let Some(end) = value[1..].find(quote)
+            {
+                let name = decode_entities(value[1..=end].trim());
+                if !name.is_empty() {
+                    return Some(name);
+                }
+            }
+        }
+    }
+
+    let start = lower.find("<title")?;
+    let start = start + lower[start..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = decode_entities(html[start..end].trim());
+    (!title.is_empty()).then_some(title)
+}
+
+// The entities titles commonly contain, not a full HTML decoder.
+fn decode_entities(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
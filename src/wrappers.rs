@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Programs games are commonly started through, placed in front of Exec.
+
+use crate::exec::{self, ExecError, find_in_path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wrapper {
+    pub program: &'static str,
+    // Arguments added with the wrapper, gamescope needs `--` before the game
+    pub args: &'static [&'static str],
+}
+
+/// Known wrappers, outermost first. Toggling one keeps the others in this order.
+pub const WRAPPERS: &[Wrapper] = &[
+    Wrapper {
+        program: "gamescope",
+        args: &["-f", "--"],
+    },
+    Wrapper {
+        program: "prime-run",
+        args: &[],
+    },
+    Wrapper {
+        program: "gamemoderun",
+        args: &[],
+    },
+    Wrapper {
+        program: "mangohud",
+        args: &[],
+    },
+];
+
+impl Wrapper {
+    pub fn is_installed(&self) -> bool {
+        find_in_path(self.program).is_some()
+    }
+
+    fn matches(&self, arg: &str) -> bool {
+        arg == self.program || arg.rsplit('/').next() == Some(self.program)
+    }
+}
+
+// The wrappers at the start of `args`, each with its own arguments, and the
+// index the wrapped command starts at.
+fn leading_wrappers(args: &[String]) -> (Vec<(usize, Vec<String>)>, usize) {
+    let mut found = Vec::new();
+    let mut at = 0;
+    while let Some(arg) = args.get(at) {
+        let Some(index) = WRAPPERS.iter().position(|w| w.matches(arg)) else {
+            break;
+        };
+        let start = at;
+        at += 1;
+        // Everything up to gamescope's `--` are its own options
+        if !WRAPPERS[index].args.is_empty() {
+            match args[at..].iter().position(|a| a == "--") {
+                Some(end) => at += end + 1,
+                None => break,
+            }
+        }
+        found.push((index, args[start..at].to_vec()));
+    }
+    (found, at)
+}
+
+/// Whether `exec` starts through `wrapper`.
+pub fn is_wrapped(exec: &str, wrapper: &Wrapper) -> bool {
+    let Ok(args) = exec::split(exec) else {
+        return false;
+    };
+    let (found, _) = leading_wrappers(&args);
+    found.iter().any(|(index, _)| WRAPPERS[*index] == *wrapper)
+}
+
+/// `exec` with `wrapper` added or removed. Other wrappers and their arguments,
+/// like a customized gamescope resolution, are kept.
+pub fn set_wrapped(exec: &str, wrapper: &Wrapper, on: bool) -> Result<String, ExecError> {
+    let args = exec::split(exec)?;
+    let (mut found, command_start) = leading_wrappers(&args);
+    let index = WRAPPERS.iter().position(|w| w == wrapper);
+
+    found.retain(|(i, _)| Some(*i) != index);
+    if on && let Some(index) = index {
+        let mut own = vec![wrapper.program.to_string()];
+        own.extend(wrapper.args.iter().map(ToString::to_string));
+        found.push((index, own));
+    }
+    found.sort_by_key(|(i, _)| *i);
+
+    let mut wrapped: Vec<String> = found.into_iter().flat_map(|(_, a)| a).collect();
+    wrapped.extend_from_slice(&args[command_start..]);
+    Ok(exec::join(&wrapped))
+}
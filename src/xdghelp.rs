@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use log::info;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
@@ -19,40 +20,80 @@ static TITLE_EXECUTABLE: LazyLock<&'static str> =
 static TITLE_DIRECTORY: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("select-directory").into_boxed_str()));
 
+static TITLE_SAVE_FOLDER: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("select-savefolder").into_boxed_str()));
+
 static TITLE_ICON_FILE: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("select-icon").into_boxed_str()));
 
 static DESKTOP_FILES: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("name-desktopfiles").into_boxed_str()));
 
+static DIRECTORY_FILES: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("name-directoryfiles").into_boxed_str()));
+
 static EXECUTABLES: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("name-executables").into_boxed_str()));
 
+static TITLE_URL_SHORTCUT: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("select-urlshortcut").into_boxed_str()));
+
+static URL_SHORTCUTS: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("name-urlshortcuts").into_boxed_str()));
+
 static IMAGES: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("name-images").into_boxed_str()));
 
 static SAVE_DESKTOPFILE: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("save-desktopfile").into_boxed_str()));
 
+static TITLE_JSON_FILE: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("select-json").into_boxed_str()));
+
+static TITLE_SAMPLE_FILE: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("select-samplefile").into_boxed_str()));
+
+static JSON_FILES: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("name-jsonfiles").into_boxed_str()));
+
+static EXPORT_JSON: LazyLock<&'static str> =
+    LazyLock::new(|| Box::leak(fl!("export-json").into_boxed_str()));
+
 static SAVE: LazyLock<&'static str> =
     LazyLock::new(|| Box::leak(fl!("menu-save").into_boxed_str()));
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PickKind {
     DesktopFile,
+    // A .directory file, opened like DesktopFile
+    DirectoryEntry,
     Executable,
     TryExecutable,
     Directory,
     IconFile,
+    CompareLeft,
+    CompareRight,
+    UrlShortcut,
+    JsonFile,
+    SaveFolder,
+    // Any file, to find out which application opens it
+    SampleFile,
 }
 
 impl PickKind {
     pub fn title(self) -> &'static str {
         match self {
-            PickKind::DesktopFile => *TITLE_DESKTOP_FILE,
+            PickKind::DesktopFile
+            | PickKind::DirectoryEntry
+            | PickKind::CompareLeft
+            | PickKind::CompareRight => *TITLE_DESKTOP_FILE,
             PickKind::Executable | PickKind::TryExecutable => *TITLE_EXECUTABLE,
             PickKind::Directory => *TITLE_DIRECTORY,
+            PickKind::SaveFolder => *TITLE_SAVE_FOLDER,
             PickKind::IconFile => *TITLE_ICON_FILE,
+            PickKind::UrlShortcut => *TITLE_URL_SHORTCUT,
+            PickKind::JsonFile => *TITLE_JSON_FILE,
+            PickKind::SampleFile => *TITLE_SAMPLE_FILE,
         }
     }
 }
@@ -65,15 +106,42 @@ fn uri_to_path(u: &url::Url) -> Option<PathBuf> {
     }
 }
 
-pub async fn save_desktop_file(suggested_name: String, kind: DesktopEntryType) -> Option<PathBuf> {
+/// A command line argument as a path, accepting `file://` URIs as passed for `%u`/`%U`.
+pub fn path_from_arg(arg: &str) -> PathBuf {
+    url::Url::parse(arg)
+        .ok()
+        .and_then(|u| uri_to_path(&u))
+        .unwrap_or_else(|| PathBuf::from(arg))
+}
+
+/// The folder Save As starts in: `configured`, with `~/` for the home directory,
+/// or the user's applications directory when it's empty.
+pub fn save_folder(configured: &Path) -> Option<PathBuf> {
+    let home = dirs::home_dir();
+    if configured.as_os_str().is_empty() {
+        return home.map(|h| h.join(".local").join("share").join("applications"));
+    }
+    match configured.strip_prefix("~") {
+        Ok(relative) => home.map(|h| h.join(relative)),
+        Err(_) => Some(configured.to_owned()),
+    }
+}
+
+/// `~/.local/share/desktop-directories`, where menus look up .directory files.
+pub fn directory_entry_folder() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("desktop-directories"))
+}
+
+pub async fn save_desktop_file(
+    suggested_name: String,
+    kind: DesktopEntryType,
+    folder: Option<PathBuf>,
+) -> Option<PathBuf> {
     use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
 
     let base = || {
         let filter = if kind == DesktopEntryType::Directory {
-            FileFilter::new(*DESKTOP_FILES)
-                .glob("*.directory")
-                .glob("*.desktop")
-                .mimetype("application/x-desktop")
+            FileFilter::new(*DIRECTORY_FILES).glob("*.directory")
         } else {
             FileFilter::new(*DESKTOP_FILES)
                 .glob("*.desktop")
@@ -88,20 +156,50 @@ pub async fn save_desktop_file(suggested_name: String, kind: DesktopEntryType) -
             .filter(filter)
     };
 
-    let request =
-        match dirs::home_dir().map(|h| h.join(".local").join("share").join("applications")) {
-            None => base(),
-            Some(folder) => {
-                // Try building with current_folder first
-                match base().current_folder(folder) {
-                    Ok(req) => req,
-                    Err(e) => {
-                        log::error!("Failed to set start folder {e}");
-                        base()
-                    }
+    let request = match folder {
+        None => base(),
+        Some(folder) => {
+            // Try building with current_folder first
+            match base().current_folder(folder) {
+                Ok(req) => req,
+                Err(e) => {
+                    log::error!("Failed to set start folder {e}");
+                    base()
                 }
             }
-        };
+        }
+    };
+
+    let response = match request.send().await {
+        Ok(rq) => match rq.response() {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Portal response error: {e}");
+                return None;
+            }
+        },
+        Err(e) => {
+            log::error!("Portal send error: {e}");
+            return None;
+        }
+    };
+
+    response.uris().first().and_then(uri_to_path)
+}
+
+pub async fn save_json_file(suggested_name: String) -> Option<PathBuf> {
+    use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+
+    let filter = FileFilter::new(*JSON_FILES)
+        .glob("*.json")
+        .mimetype("application/json");
+
+    let request = SelectedFiles::save_file()
+        .title(*EXPORT_JSON)
+        .accept_label(*SAVE)
+        .current_name(suggested_name.as_str())
+        .modal(true)
+        .filter(filter);
 
     let response = match request.send().await {
         Ok(rq) => match rq.response() {
@@ -131,25 +229,41 @@ pub async fn open_path(kind: PickKind) -> (Option<PathBuf>, PickKind) {
     };
 
     let request = match kind {
-        PickKind::Directory => base().directory(true),
-        PickKind::DesktopFile => {
-            let filter = FileFilter::new(*DESKTOP_FILES)
+        PickKind::Directory | PickKind::SaveFolder => base().directory(true),
+        PickKind::DesktopFile
+        | PickKind::DirectoryEntry
+        | PickKind::CompareLeft
+        | PickKind::CompareRight => {
+            let desktop = FileFilter::new(*DESKTOP_FILES)
                 .glob("*.desktop")
+                .glob("*.thumbnailer")
                 .mimetype("application/x-desktop");
-
-            match dirs::home_dir().map(|h| h.join(".local").join("share").join("applications")) {
+            let directory = FileFilter::new(*DIRECTORY_FILES).glob("*.directory");
+
+            // The kind being edited is the folder and filter offered first
+            let (folder, filters) = if kind == PickKind::DirectoryEntry {
+                (directory_entry_folder(), [directory, desktop])
+            } else {
+                (
+                    dirs::home_dir().map(|h| h.join(".local").join("share").join("applications")),
+                    [desktop, directory],
+                )
+            };
+            let request = match folder {
                 None => base(),
                 Some(folder) => {
                     // Try building with current_folder first
                     match base().current_folder(folder) {
-                        Ok(req) => req.filter(filter),
+                        Ok(req) => req,
                         Err(e) => {
                             log::error!("Failed to set start folder {e}");
-                            base().filter(filter)
+                            base()
                         }
                     }
                 }
-            }
+            };
+            let [first, second] = filters;
+            request.filter(first).filter(second)
         }
         PickKind::Executable | PickKind::TryExecutable => {
             let filter = FileFilter::new(*EXECUTABLES)
@@ -171,6 +285,20 @@ pub async fn open_path(kind: PickKind) -> (Option<PathBuf>, PickKind) {
                 .mimetype("image/jpeg");
             base().filter(filter)
         }
+        PickKind::UrlShortcut => {
+            let filter = FileFilter::new(*URL_SHORTCUTS)
+                .glob("*.url")
+                .glob("*.URL")
+                .mimetype("application/x-mswinurl");
+            base().filter(filter)
+        }
+        PickKind::JsonFile => {
+            let filter = FileFilter::new(*JSON_FILES)
+                .glob("*.json")
+                .mimetype("application/json");
+            base().filter(filter)
+        }
+        PickKind::SampleFile => base(),
     };
 
     let response = match request.send().await {
@@ -191,49 +319,463 @@ pub async fn open_path(kind: PickKind) -> (Option<PathBuf>, PickKind) {
     (picked, kind)
 }
 
-pub struct IconCache {
-    by_name_no_ext: HashMap<String, PathBuf>,
-    by_full_name: HashMap<String, PathBuf>,
+/// Where a user-level override of the system file at `path` belongs, keeping the
+/// same desktop-file ID so it shadows the original.
+pub fn user_override_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    let parents: Vec<_> = path.components().map(|c| c.as_os_str()).collect();
+
+    // Autostart entries are looked up in $XDG_CONFIG_DIRS/autostart
+    if parents.iter().any(|c| *c == "autostart") {
+        return dirs::config_dir().map(|d| d.join("autostart").join(file_name));
+    }
+
+    let data = dirs::data_dir()?;
+
+    if path.extension().is_some_and(|e| e == "directory") {
+        return Some(data.join("desktop-directories").join(file_name));
+    }
+
+    // Subdirectories below applications/ are part of the desktop-file ID
+    let mut relative = PathBuf::new();
+    if let Some(pos) = parents.iter().rposition(|c| *c == "applications") {
+        for c in &parents[pos + 1..] {
+            relative.push(c);
+        }
+    } else {
+        relative.push(file_name);
+    }
+
+    Some(data.join("applications").join(relative))
 }
 
-impl Default for IconCache {
-    fn default() -> Self {
-        let mut cache = Self {
-            by_name_no_ext: HashMap::default(),
-            by_full_name: HashMap::default(),
-        };
-        cache.scan();
-        cache
+/// `$XDG_DATA_DIRS`, or its default.
+pub fn system_data_dirs() -> Vec<PathBuf> {
+    match env::var("XDG_DATA_DIRS") {
+        Ok(var) if !var.is_empty() => var.split(':').map(PathBuf::from).collect(),
+        _ => vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ],
+    }
+}
+
+/// Show `path` selected in its folder, in the file manager, through the OpenURI portal.
+pub async fn open_containing_folder(path: PathBuf) -> Result<(), String> {
+    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
+    ashpd::desktop::open_uri::OpenDirectoryRequest::default()
+        .send(&file)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Every directory launchers are installed in, by precedence. Flatpak's export
+/// directories are added when the session didn't put them in `$XDG_DATA_DIRS`.
+pub fn application_dirs() -> Vec<PathBuf> {
+    let flatpak = dirs::data_dir()
+        .map(|d| d.join("flatpak/exports/share"))
+        .into_iter()
+        .chain([PathBuf::from("/var/lib/flatpak/exports/share")]);
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for data in dirs::data_dir()
+        .into_iter()
+        .chain(system_data_dirs())
+        .chain(flatpak)
+    {
+        let applications = data.join("applications");
+        if !dirs.contains(&applications) {
+            dirs.push(applications);
+        }
+    }
+    dirs
+}
+
+/// Whether `name` is a valid well-known D-Bus name, e.g. `org.example.App`.
+pub fn is_valid_bus_name(name: &str) -> bool {
+    let elements: Vec<&str> = name.split('.').collect();
+    name.len() <= 255
+        && elements.len() >= 2
+        && elements.iter().all(|e| {
+            !e.is_empty()
+                && !e.starts_with(|c: char| c.is_ascii_digit())
+                && e.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+/// The session bus `.service` file activating `name`, if one is installed.
+pub fn find_dbus_service(name: &str) -> Option<PathBuf> {
+    dirs::data_dir()
+        .into_iter()
+        .chain(system_data_dirs())
+        .map(|dir| dir.join("dbus-1/services").join(format!("{name}.service")))
+        .find(|p| p.is_file())
+}
+
+/// Reasons a DBusActivatable=true launcher at `path` won't start, given the `service`
+/// file `find_dbus_service` found for it.
+pub fn dbus_activation_warnings(path: Option<&Path>, service: Option<&Path>) -> Vec<String> {
+    let Some(name) = path
+        .and_then(Path::file_stem)
+        .map(|s| s.to_string_lossy().into_owned())
+    else {
+        return vec![fl!("dbus-unsaved")];
+    };
+
+    if !is_valid_bus_name(&name) {
+        return vec![fl!("dbus-badname", name = name)];
+    }
+    if service.is_none() {
+        return vec![fl!("dbus-noservice", name = name)];
     }
+    Vec::new()
+}
+
+/// Whether `path` is an image usable as an Icon.
+pub fn is_icon_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+        matches!(
+            e.to_ascii_lowercase().as_str(),
+            "png" | "svg" | "svgz" | "xpm"
+        )
+    })
+}
+
+// Width and height from a PNG's IHDR chunk.
+fn png_size(path: &Path) -> Option<(u32, u32)> {
+    let mut header = [0u8; 24];
+    std::io::Read::read_exact(&mut fs::File::open(path).ok()?, &mut header).ok()?;
+    if &header[1..4] != b"PNG" {
+        return None;
+    }
+    let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Copy an image into the user's hicolor theme, returning the icon name to use for Icon.
+pub fn install_icon(path: &Path) -> std::io::Result<(String, PathBuf)> {
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .ok_or_else(|| std::io::Error::other("No file name"))?;
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    // Vector icons are scalable, bitmaps go in the directory for their size
+    let size_dir = match ext.as_str() {
+        "svg" | "svgz" => "scalable".to_string(),
+        _ => match png_size(path) {
+            Some((w, h)) => format!("{w}x{h}"),
+            None => "48x48".to_string(),
+        },
+    };
+    let dir = dirs::data_dir()
+        .ok_or_else(|| std::io::Error::other("No data directory"))?
+        .join("icons/hicolor")
+        .join(size_dir)
+        .join("apps");
+    fs::create_dir_all(&dir)?;
+
+    let target = dir.join(format!("{name}.{ext}"));
+    fs::copy(path, &target)?;
+    Ok((name, target))
+}
+
+/// Whether `path` lies in an autostart directory.
+pub fn is_autostart_file(path: &Path) -> bool {
+    path.parent()
+        .and_then(Path::file_name)
+        .is_some_and(|d| d == "autostart")
+}
+
+/// Where a launcher named like `path` is started at login, `$XDG_CONFIG_HOME/autostart`.
+pub fn user_autostart_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    dirs::config_dir().map(|d| d.join("autostart").join(file_name))
+}
+
+/// The autostart copy of a launcher named like `path`, user's first, if one exists.
+pub fn find_autostart_copy(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    dirs::config_dir()
+        .into_iter()
+        .chain(system_config_dirs())
+        .map(|d| d.join("autostart").join(file_name))
+        .find(|p| p.is_file())
+}
+
+/// Move a file, copying when the target is on another filesystem.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// A reverse-DNS desktop-file ID for a new application. The window class or the
+/// program's own ID are used when they already are one, so the file matches the
+/// app ID the compositor sees; otherwise the name below the `local.` prefix.
+pub fn suggested_app_id(
+    name: Option<&str>,
+    wm_class: Option<&str>,
+    exec: Option<&str>,
+) -> Option<String> {
+    if let Some(class) = wm_class.map(str::trim)
+        && is_valid_bus_name(class)
+    {
+        return Some(class.to_string());
+    }
+
+    let args = exec
+        .and_then(|e| crate::exec::split(e).ok())
+        .unwrap_or_default();
+    let program = args.first().map(|p| p.rsplit('/').next().unwrap_or(p));
+    // `flatpak run [options] org.example.App`
+    let own_id = if program == Some("flatpak") {
+        args.iter()
+            .skip_while(|a| *a != "run")
+            .skip(1)
+            .find(|a| !a.starts_with('-'))
+            .map(String::as_str)
+    } else {
+        program
+    };
+    if let Some(id) = own_id
+        && is_valid_bus_name(id)
+    {
+        return Some(id.to_string());
+    }
+
+    // "My cool app" → MyCoolApp
+    let camel: String = name?
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    let camel = camel.trim_start_matches(|c: char| c.is_ascii_digit());
+    (!camel.is_empty()).then(|| format!("local.{camel}"))
+}
+
+/// Problems with the file name of a launcher, as the desktop-file ID is derived from it.
+/// `dbus_service` is what `find_dbus_service` found for a DBusActivatable one.
+pub fn file_name_warnings(
+    path: &Path,
+    dbus_activatable: bool,
+    dbus_service: Option<&Path>,
+) -> Vec<String> {
+    let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+        return Vec::new();
+    };
+
+    let mut warnings = Vec::new();
+    if stem.contains(char::is_whitespace) {
+        warnings.push(fl!("filename-spaces"));
+    }
+    if !stem.is_ascii() {
+        warnings.push(fl!("filename-nonascii"));
+    }
+    if !path
+        .extension()
+        .is_some_and(|e| e == "desktop" || e == "directory")
+    {
+        warnings.push(fl!("filename-badext"));
+    }
+    if dbus_activatable {
+        warnings.extend(dbus_activation_warnings(Some(path), dbus_service));
+    } else if !is_valid_bus_name(&stem) {
+        warnings.push(fl!("filename-notrdnn", name = stem));
+    }
+    warnings
+}
+
+/// A launcher in another directory with the desktop-file ID of the one being saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdConflict {
+    pub path: PathBuf,
+    // The saved file comes first in lookup and hides this one
+    pub shadowed: bool,
+}
+
+/// Launchers elsewhere in the XDG data directories with the desktop-file ID a file
+/// at `path` would have. Files outside them have no ID and conflict with nothing.
+pub fn id_conflicts(path: &Path) -> Vec<IdConflict> {
+    let dirs: Vec<PathBuf> = if path.extension().is_some_and(|e| e == "directory") {
+        dirs::data_dir()
+            .into_iter()
+            .chain(system_data_dirs())
+            .map(|d| d.join("desktop-directories"))
+            .collect()
+    } else {
+        application_dirs()
+    };
+    let Some((own, relative)) = dirs
+        .iter()
+        .enumerate()
+        .find_map(|(i, d)| Some((i, path.strip_prefix(d).ok()?)))
+    else {
+        return Vec::new();
+    };
+    // kde/foo.desktop and kde-foo.desktop have the same ID
+    let id = relative.to_string_lossy().replace('/', "-");
+
+    let mut conflicts: Vec<IdConflict> = dirs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != own)
+        .flat_map(|(i, dir)| {
+            [dir.join(relative), dir.join(&id)]
+                .into_iter()
+                .filter(|p| p.is_file())
+                .map(move |path| IdConflict {
+                    path,
+                    shadowed: i > own,
+                })
+        })
+        .collect();
+    conflicts.dedup();
+    conflicts
+}
+
+/// `$XDG_CONFIG_DIRS`, or its default.
+pub fn system_config_dirs() -> Vec<PathBuf> {
+    match env::var("XDG_CONFIG_DIRS") {
+        Ok(var) if !var.is_empty() => var.split(':').map(PathBuf::from).collect(),
+        _ => vec![PathBuf::from("/etc/xdg")],
+    }
+}
+
+/// Where launchedit keeps its `area` state for `path` below `$XDG_STATE_HOME`,
+/// named after the full path with `/` escaped.
+pub fn state_path(area: &str, path: &Path) -> Option<PathBuf> {
+    let name = path
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('/', "%2F");
+    dirs::state_dir().map(|d| d.join("launchedit").join(area).join(name))
+}
+
+/// The system file a user-level entry at `path` shadows, if there is one.
+/// This is the reverse of `user_override_path`.
+pub fn shadowed_system_path(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    let data = dirs::data_dir()?;
+    let config = dirs::config_dir()?;
+
+    let layers = [
+        (
+            data.join("applications"),
+            system_data_dirs(),
+            "applications",
+        ),
+        (
+            data.join("desktop-directories"),
+            system_data_dirs(),
+            "desktop-directories",
+        ),
+        (config.join("autostart"), system_config_dirs(), "autostart"),
+    ];
+
+    for (user_dir, system_dirs, sub) in layers {
+        if let Ok(relative) = path.strip_prefix(&user_dir) {
+            return system_dirs
+                .into_iter()
+                .map(|d| d.join(sub).join(relative))
+                .find(|p| p.is_file());
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IconCache {
+    by_name_no_ext: HashMap<String, PathBuf>,
+    by_full_name: HashMap<String, PathBuf>,
+    // Last suggest() query and its result, the view asks on every redraw
+    suggestions: RefCell<Option<(String, Vec<String>)>>,
 }
 
 impl IconCache {
-    const THEMES: [&'static str; 3] = ["cosmic", "Adwaita", "hicolor"];
+    // Every theme implicitly falls back to hicolor
+    const FALLBACK_THEME: &'static str = "hicolor";
     const SIZES: [&'static str; 9] = [
         "scalable", "512x512", "256x256", "128x128", "64x64", "48x48", "32x32", "24x24", "16x16",
     ];
     const CONTEXTS: [&'static str; 4] = ["apps", "places", "mimetypes", "actions"];
+    const EXTENSIONS: [&'static str; 6] = ["png", "svg", "xpm", "ico", "jpg", "jpeg"];
+    const MAX_SUGGESTIONS: usize = 5;
+
+    // Collect icon paths below one base directory, in lookup priority order.
+    // This is blocking I/O, run it off the UI thread.
+    pub fn scan_base(base: &Path, themes: &[String]) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        for dir in Self::scanned_dirs(base, themes) {
+            Self::scan_dir(&dir, &mut found);
+        }
+        found
+    }
 
-    // Load all icons paths
-    pub fn scan(&mut self) {
-        let base_dirs = Self::icon_search_dirs();
-
-        for base in base_dirs {
-            for theme in Self::THEMES {
-                for size in Self::SIZES {
-                    for ctx in Self::CONTEXTS {
-                        let dir = base.join(theme).join(size).join(ctx);
-                        self.scan_dir(&dir);
-                    }
+    // The directories below a base that scan_base walks, in priority order.
+    pub fn scanned_dirs(base: &Path, themes: &[String]) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        for theme in themes {
+            for size in Self::SIZES {
+                for ctx in Self::CONTEXTS {
+                    dirs.push(base.join(theme).join(size).join(ctx));
                 }
             }
-            self.scan_dir(&base.join("pixmaps"));
         }
-        info!(
-            "Icon cache: Loaded {} base names, {} full names",
-            self.by_name_no_ext.len(),
-            self.by_full_name.len()
-        );
+        dirs.push(base.join("pixmaps"));
+        dirs
+    }
+
+    // Merge scanned paths into the cache, earlier entries win.
+    // Returns true if any new name became resolvable.
+    pub fn add_paths(&mut self, paths: &[PathBuf]) -> bool {
+        let mut changed = false;
+        for path in paths {
+            let Some(fname) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            if !self.by_full_name.contains_key(fname) {
+                self.by_full_name.insert(fname.to_string(), path.clone());
+                changed = true;
+            }
+            if !self.by_name_no_ext.contains_key(stem) {
+                self.by_name_no_ext.insert(stem.to_string(), path.clone());
+                changed = true;
+            }
+        }
+        if changed {
+            self.suggestions.replace(None);
+            info!(
+                "Icon cache: Loaded {} base names, {} full names",
+                self.by_name_no_ext.len(),
+                self.by_full_name.len()
+            );
+        }
+        changed
     }
 
     pub fn lookup(&self, name: &str) -> Option<&PathBuf> {
@@ -247,7 +789,123 @@ impl IconCache {
         None
     }
 
-    fn icon_search_dirs() -> Vec<PathBuf> {
+    /// Whether an Icon value shows up as an icon, either an existing file or a known name.
+    pub fn resolves(&self, icon: &str) -> bool {
+        if Path::new(icon).is_absolute() {
+            return Path::new(icon).is_file();
+        }
+        self.lookup(icon).is_some()
+    }
+
+    /// Installed icon names closest to `name` by edit distance, best first.
+    pub fn suggest(&self, name: &str) -> Vec<String> {
+        if let Some((query, names)) = self.suggestions.borrow().as_ref()
+            && query == name
+        {
+            return names.clone();
+        }
+
+        let wanted = name.to_lowercase();
+        // Allow about one typo per three characters
+        let max_distance = (wanted.chars().count() / 3).max(2);
+        let mut scored: Vec<(usize, &String)> = self
+            .by_name_no_ext
+            .keys()
+            .filter_map(|candidate| {
+                let d = edit_distance(&wanted, &candidate.to_lowercase());
+                (d <= max_distance).then_some((d, candidate))
+            })
+            .collect();
+        scored.sort();
+
+        let names: Vec<String> = scored
+            .into_iter()
+            .take(Self::MAX_SUGGESTIONS)
+            .map(|(_, n)| n.clone())
+            .collect();
+        self.suggestions
+            .replace(Some((name.to_string(), names.clone())));
+        names
+    }
+
+    /// The configured icon theme followed by the themes it inherits from,
+    /// breadth first as the Icon Theme spec looks them up, ending with hicolor.
+    /// This is blocking I/O.
+    pub fn theme_chain(bases: &[PathBuf]) -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut queue = std::collections::VecDeque::from([Self::configured_theme()]);
+
+        while let Some(theme) = queue.pop_front() {
+            if theme.is_empty() || chain.contains(&theme) {
+                continue;
+            }
+            queue.extend(Self::inherited_themes(bases, &theme));
+            chain.push(theme);
+        }
+
+        chain.retain(|t| t != Self::FALLBACK_THEME);
+        chain.push(Self::FALLBACK_THEME.to_string());
+        info!("Icon themes: {}", chain.join(", "));
+        chain
+    }
+
+    // The icon theme the shell uses: COSMIC's own setting in a COSMIC session,
+    // otherwise the GNOME setting most other desktops and toolkits follow.
+    fn configured_theme() -> String {
+        let cosmic_session = env::var("XDG_CURRENT_DESKTOP")
+            .is_ok_and(|d| d.split(':').any(|d| d.eq_ignore_ascii_case("COSMIC")));
+        if !cosmic_session && let Some(theme) = Self::gsettings_theme() {
+            return theme;
+        }
+        cosmic::config::icon_theme()
+    }
+
+    fn gsettings_theme() -> Option<String> {
+        let output = std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // Printed as a GVariant string, 'Adwaita'
+        let theme = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .trim_matches('\'')
+            .to_string();
+        (!theme.is_empty()).then_some(theme)
+    }
+
+    // Inherits= of the first index.theme found for `theme`, the same
+    // base directory order is used for the icons themselves.
+    fn inherited_themes(bases: &[PathBuf], theme: &str) -> Vec<String> {
+        let Some(text) = bases
+            .iter()
+            .find_map(|b| fs::read_to_string(b.join(theme).join("index.theme")).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut in_theme_group = false;
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_theme_group = line == "[Icon Theme]";
+            } else if in_theme_group
+                && let Some((key, value)) = line.split_once('=')
+                && key.trim() == "Inherits"
+            {
+                return value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+
+    pub fn icon_search_dirs() -> Vec<PathBuf> {
         let mut dirs = Vec::new();
 
         if let Ok(home) = env::var("XDG_DATA_HOME") {
@@ -276,8 +934,7 @@ impl IconCache {
         dirs
     }
 
-    fn scan_dir(&mut self, root: &Path) {
-        let exts = ["png", "svg", "xpm", "ico", "jpg", "jpeg"];
+    fn scan_dir(root: &Path, found: &mut Vec<PathBuf>) {
         let Ok(entries) = fs::read_dir(root) else {
             return;
         };
@@ -286,25 +943,33 @@ impl IconCache {
             let path = entry.path();
 
             if path.is_dir() {
-                self.scan_dir(&path);
+                Self::scan_dir(&path, found);
                 continue;
             }
 
             if let Some(ext) = path.extension().and_then(|e| e.to_str())
-                && exts.contains(&ext)
-                && let Some(fname) = path.file_name().and_then(|s| s.to_str())
+                && Self::EXTENSIONS.contains(&ext)
+                && path.file_name().and_then(|s| s.to_str()).is_some()
             {
-                let stem = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or_default();
-                self.by_full_name
-                    .entry(fname.to_string())
-                    .or_insert(path.clone());
-                self.by_name_no_ext
-                    .entry(stem.to_string())
-                    .or_insert(path.clone());
+                found.push(path);
             }
         }
     }
 }
+
+// Levenshtein distance between two strings, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,434 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The application menu layout from `applications.menu`, per the Desktop Menu spec.
+//! Edits go to the user's menu file, which merges the system menu and adds to it.
+
+use crate::fl;
+use crate::xdghelp::system_config_dirs;
+use log::info;
+use std::fs;
+use std::path::PathBuf;
+
+/// A rule in `<Include>` or `<Exclude>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    All,
+    Category(String),
+    Filename(String),
+    And(Vec<Rule>),
+    Or(Vec<Rule>),
+    Not(Vec<Rule>),
+}
+
+impl Rule {
+    /// A typed rule: desktop-file IDs end in `.desktop`, anything else is a category.
+    pub fn from_input(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            None
+        } else if text.ends_with(".desktop") {
+            Some(Rule::Filename(text.to_string()))
+        } else {
+            Some(Rule::Category(text.to_string()))
+        }
+    }
+
+    /// How the rule reads in the editor.
+    pub fn label(&self) -> String {
+        let join = |rules: &[Rule], op: String| {
+            let parts: Vec<String> = rules.iter().map(Rule::label).collect();
+            format!("({})", parts.join(&format!(" {op} ")))
+        };
+        match self {
+            Rule::All => fl!("menurule-all"),
+            Rule::Category(c) => fl!("menurule-category", name = c.as_str()),
+            Rule::Filename(f) => fl!("menurule-filename", id = f.as_str()),
+            Rule::And(rules) => join(rules, fl!("menurule-and")),
+            Rule::Or(rules) => join(rules, fl!("menurule-or")),
+            Rule::Not(rules) => format!(
+                "{} {}",
+                fl!("menurule-not"),
+                join(rules, fl!("menurule-or"))
+            ),
+        }
+    }
+
+    fn parse(node: roxmltree::Node) -> Option<Self> {
+        let children = || {
+            node.children()
+                .filter(|n| n.is_element())
+                .filter_map(Rule::parse)
+        };
+        let text = || node.text().map(|t| t.trim().to_string());
+        match node.tag_name().name() {
+            "All" => Some(Rule::All),
+            "Category" => text().map(Rule::Category),
+            "Filename" => text().map(Rule::Filename),
+            "And" => Some(Rule::And(children().collect())),
+            "Or" => Some(Rule::Or(children().collect())),
+            "Not" => Some(Rule::Not(children().collect())),
+            _ => None,
+        }
+    }
+
+    fn to_xml(&self) -> String {
+        let all = |rules: &[Rule]| rules.iter().map(Rule::to_xml).collect::<String>();
+        match self {
+            Rule::All => "<All/>".to_string(),
+            Rule::Category(c) => format!("<Category>{}</Category>", escape(c)),
+            Rule::Filename(f) => format!("<Filename>{}</Filename>", escape(f)),
+            Rule::And(rules) => format!("<And>{}</And>", all(rules)),
+            Rule::Or(rules) => format!("<Or>{}</Or>", all(rules)),
+            Rule::Not(rules) => format!("<Not>{}</Not>", all(rules)),
+        }
+    }
+}
+
+/// One `<Menu>` and its submenus.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Menu {
+    pub name: String,
+    // The .directory file naming the menu, by its ID
+    pub directory: Option<String>,
+    pub include: Vec<Rule>,
+    pub exclude: Vec<Rule>,
+    // <Deleted/> or <NotDeleted/>, None when the file says neither
+    pub deleted: Option<bool>,
+    pub submenus: Vec<Menu>,
+    // Elements the editor doesn't change, e.g. <Layout> or <Move>, kept verbatim
+    extra: Vec<String>,
+}
+
+impl Menu {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn parse(node: roxmltree::Node, source: &str) -> Self {
+        let mut menu = Menu::default();
+        for child in node.children().filter(|n| n.is_element()) {
+            let text = || {
+                child
+                    .text()
+                    .map(|t| t.trim().to_string())
+                    .unwrap_or_default()
+            };
+            let rules = || {
+                child
+                    .children()
+                    .filter(|n| n.is_element())
+                    .filter_map(Rule::parse)
+            };
+            match child.tag_name().name() {
+                "Name" => menu.name = text(),
+                // The last <Directory> is the one used
+                "Directory" => menu.directory = Some(text()),
+                "Include" => menu.include.extend(rules()),
+                "Exclude" => menu.exclude.extend(rules()),
+                "Deleted" => menu.deleted = Some(true),
+                "NotDeleted" => menu.deleted = Some(false),
+                "Menu" => menu.add_submenu(Menu::parse(child, source)),
+                "MergeFile" if child.attribute("type") == Some("parent") => {}
+                _ => menu.extra.push(source[child.range()].to_string()),
+            }
+        }
+        menu
+    }
+
+    // Menus with the same name are one menu
+    fn add_submenu(&mut self, submenu: Menu) {
+        match self.submenus.iter_mut().find(|m| m.name == submenu.name) {
+            Some(existing) => *existing = existing.merged(&submenu),
+            None => self.submenus.push(submenu),
+        }
+    }
+
+    /// This menu with `overlay`'s changes on top, as a menu file merging it sees it.
+    fn merged(&self, overlay: &Menu) -> Menu {
+        let mut menu = self.clone();
+        if overlay.directory.is_some() {
+            menu.directory.clone_from(&overlay.directory);
+        }
+        menu.deleted = overlay.deleted.or(menu.deleted);
+        // Rules are applied in order, a later rule undoes an earlier opposite one
+        menu.include.retain(|r| !overlay.exclude.contains(r));
+        menu.exclude.retain(|r| !overlay.include.contains(r));
+        for rule in &overlay.include {
+            if !menu.include.contains(rule) {
+                menu.include.push(rule.clone());
+            }
+        }
+        for rule in &overlay.exclude {
+            if !menu.exclude.contains(rule) {
+                menu.exclude.push(rule.clone());
+            }
+        }
+        for submenu in &overlay.submenus {
+            match menu.submenus.iter_mut().find(|m| m.name == submenu.name) {
+                Some(existing) => *existing = existing.merged(submenu),
+                None => menu
+                    .submenus
+                    .push(Menu::named(&submenu.name).merged(submenu)),
+            }
+        }
+        menu
+    }
+
+    /// The submenu at the path of names `at` below this one.
+    pub fn at(&self, at: &[String]) -> Option<&Menu> {
+        match at.split_first() {
+            None => Some(self),
+            Some((name, rest)) => self.submenus.iter().find(|m| m.name == *name)?.at(rest),
+        }
+    }
+
+    // Like at(), adding the menus that don't exist yet.
+    fn at_mut(&mut self, at: &[String]) -> &mut Menu {
+        let Some((name, rest)) = at.split_first() else {
+            return self;
+        };
+        let pos = match self.submenus.iter().position(|m| m.name == *name) {
+            Some(pos) => pos,
+            None => {
+                self.submenus.push(Menu::named(name));
+                self.submenus.len() - 1
+            }
+        };
+        self.submenus[pos].at_mut(rest)
+    }
+
+    fn to_xml(&self, out: &mut String, depth: usize, merge_parent: bool) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&format!("{indent}<Menu>\n"));
+        out.push_str(&format!("{indent}  <Name>{}</Name>\n", escape(&self.name)));
+        if merge_parent {
+            out.push_str(&format!("{indent}  <MergeFile type=\"parent\"/>\n"));
+        }
+        for extra in &self.extra {
+            out.push_str(&format!("{indent}  {extra}\n"));
+        }
+        if let Some(directory) = &self.directory {
+            out.push_str(&format!(
+                "{indent}  <Directory>{}</Directory>\n",
+                escape(directory)
+            ));
+        }
+        match self.deleted {
+            Some(true) => out.push_str(&format!("{indent}  <Deleted/>\n")),
+            Some(false) => out.push_str(&format!("{indent}  <NotDeleted/>\n")),
+            None => {}
+        }
+        for (tag, rules) in [("Include", &self.include), ("Exclude", &self.exclude)] {
+            if !rules.is_empty() {
+                let rules: String = rules.iter().map(Rule::to_xml).collect();
+                out.push_str(&format!("{indent}  <{tag}>{rules}</{tag}>\n"));
+            }
+        }
+        for submenu in &self.submenus {
+            submenu.to_xml(out, depth + 1, false);
+        }
+        out.push_str(&format!("{indent}</Menu>\n"));
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Menu files declare a DTD, which roxmltree refuses to parse.
+fn without_doctype(text: &str) -> String {
+    match (text.find("<!DOCTYPE"), text.find("<Menu")) {
+        (Some(start), Some(menu)) if start < menu => {
+            let end = text[start..].find('>').map_or(menu, |e| start + e + 1);
+            format!("{}{}", &text[..start], &text[end..])
+        }
+        _ => text.to_string(),
+    }
+}
+
+// The root menu of a file, and whether it merges the next file of the same name.
+fn parse_file(text: &str) -> Result<(Menu, bool), String> {
+    let text = without_doctype(text);
+    let doc = roxmltree::Document::parse(&text).map_err(|e| e.to_string())?;
+    let root = doc.root_element();
+    if !root.has_tag_name("Menu") {
+        return Err(fl!("xdgmenu-notmenu"));
+    }
+    let merge_parent = root
+        .children()
+        .any(|n| n.has_tag_name("MergeFile") && n.attribute("type") == Some("parent"));
+    Ok((Menu::parse(root, &text), merge_parent))
+}
+
+// `applications.menu`, prefixed by the desktop when it sets $XDG_MENU_PREFIX.
+fn file_name() -> String {
+    let prefix = std::env::var("XDG_MENU_PREFIX").unwrap_or_default();
+    format!("{prefix}applications.menu")
+}
+
+/// State of the menu editor window.
+#[derive(Debug, Clone, Default)]
+pub struct MenuEditor {
+    /// The user's menu file, where edits are saved.
+    pub path: PathBuf,
+    // Whether the user file merges the system menu, or replaces it
+    merge_parent: bool,
+    system: Menu,
+    // The user file's own content
+    overlay: Menu,
+    /// Names from the root menu down to the selected one.
+    pub selected: Vec<String>,
+    pub rule_input: String,
+    pub submenu_input: String,
+    pub changed: bool,
+    pub error: Option<String>,
+}
+
+impl MenuEditor {
+    pub fn open() -> std::io::Result<Self> {
+        let name = file_name();
+        let path = dirs::config_dir()
+            .map(|d| d.join("menus").join(&name))
+            .ok_or_else(|| std::io::Error::other("No configuration directory"))?;
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        let system_text = system_config_dirs()
+            .into_iter()
+            .map(|d| d.join("menus").join(&name))
+            .find_map(|p| fs::read_to_string(p).ok());
+        let system = match &system_text {
+            Some(text) => parse_file(text).map_err(invalid)?.0,
+            None => Menu::named("Applications"),
+        };
+
+        let (overlay, merge_parent) = match fs::read_to_string(&path) {
+            Ok(text) => parse_file(&text).map_err(invalid)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                (Menu::named(&system.name), system_text.is_some())
+            }
+            Err(e) => return Err(e),
+        };
+        info!(
+            "Editing menu {}, merging the system menu: {merge_parent}",
+            path.display()
+        );
+
+        Ok(Self {
+            path,
+            merge_parent,
+            system: if merge_parent {
+                system
+            } else {
+                Menu::named(&overlay.name)
+            },
+            overlay,
+            ..Self::default()
+        })
+    }
+
+    /// The menu as launchers see it, the system menu with the user's changes.
+    pub fn tree(&self) -> Menu {
+        self.system.merged(&self.overlay)
+    }
+
+    // The user's version of the selected menu, which edits change.
+    fn edited(&mut self) -> &mut Menu {
+        self.changed = true;
+        self.overlay.at_mut(&self.selected)
+    }
+
+    pub fn set_directory(&mut self, directory: String) {
+        let directory = directory.trim().to_string();
+        self.edited().directory = (!directory.is_empty()).then_some(directory);
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.edited().deleted = Some(hidden);
+    }
+
+    /// Include or exclude what was typed into the rule field.
+    pub fn add_rule(&mut self, include: bool) {
+        let Some(rule) = Rule::from_input(&std::mem::take(&mut self.rule_input)) else {
+            return;
+        };
+        let menu = self.edited();
+        let (add, undo) = if include {
+            (&mut menu.include, &mut menu.exclude)
+        } else {
+            (&mut menu.exclude, &mut menu.include)
+        };
+        undo.retain(|r| *r != rule);
+        if !add.contains(&rule) {
+            add.push(rule);
+        }
+    }
+
+    /// Take back rule `index` of the selected menu's includes or excludes. A rule
+    /// from the system menu can't be deleted, so the opposite rule cancels it.
+    pub fn remove_rule(&mut self, include: bool, index: usize) {
+        let tree = self.tree();
+        let Some(menu) = tree.at(&self.selected) else {
+            return;
+        };
+        let rules = if include {
+            &menu.include
+        } else {
+            &menu.exclude
+        };
+        let Some(rule) = rules.get(index).cloned() else {
+            return;
+        };
+        let menu = self.edited();
+        let (own, opposite) = if include {
+            (&mut menu.include, &mut menu.exclude)
+        } else {
+            (&mut menu.exclude, &mut menu.include)
+        };
+        if own.contains(&rule) {
+            own.retain(|r| *r != rule);
+        } else {
+            // Only once, and after any earlier copy so it comes last and wins
+            opposite.retain(|r| *r != rule);
+            opposite.push(rule);
+        }
+    }
+
+    /// Add a submenu named as typed below the selected menu, and select it.
+    pub fn add_submenu(&mut self) {
+        let name = std::mem::take(&mut self.submenu_input).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        self.edited().at_mut(std::slice::from_ref(&name));
+        self.selected.push(name);
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "<!DOCTYPE Menu PUBLIC \"-//freedesktop//DTD Menu 1.0//EN\"\n \
+             \"http://www.freedesktop.org/standards/menu-spec/1.0/menu.dtd\">\n",
+        );
+        self.overlay.to_xml(&mut text, 0, self.merge_parent);
+        text
+    }
+
+    pub fn save(&mut self) {
+        let written = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&self.path, self.to_text()));
+        match written {
+            Ok(()) => {
+                self.changed = false;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}